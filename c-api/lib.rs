@@ -39,6 +39,8 @@ pub enum resvg_error {
     INVALID_SIZE,
     /// Failed to parse an SVG data.
     PARSING_FAILED,
+    /// SVG images are nested too deep.
+    NESTED_SVG_DEPTH_LIMIT_REACHED,
 }
 
 /// @brief A path bbox representation.
@@ -904,6 +906,7 @@ fn convert_error(e: usvg::Error) -> resvg_error {
         usvg::Error::ElementsLimitReached => resvg_error::ELEMENTS_LIMIT_REACHED,
         usvg::Error::InvalidSize => resvg_error::INVALID_SIZE,
         usvg::Error::ParsingFailed(_) => resvg_error::PARSING_FAILED,
+        usvg::Error::NestedSvgDepthLimitReached => resvg_error::NESTED_SVG_DEPTH_LIMIT_REACHED,
     }
 }
 
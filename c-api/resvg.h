@@ -57,6 +57,10 @@ typedef enum {
      * Failed to parse an SVG data.
      */
     RESVG_ERROR_PARSING_FAILED,
+    /**
+     * SVG images are nested too deep.
+     */
+    RESVG_ERROR_NESTED_SVG_DEPTH_LIMIT_REACHED,
 } resvg_error;
 
 /**
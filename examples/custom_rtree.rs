@@ -4,14 +4,14 @@ use usvg::NodeExt;
 
 fn main() {
     let size = usvg::Size::new(200.0, 200.0).unwrap();
-    let tree = usvg::Tree {
+    let tree = usvg::Tree::new(
         size,
-        view_box: usvg::ViewBox {
+        usvg::ViewBox {
             rect: size.to_rect(0.0, 0.0),
             aspect: usvg::AspectRatio::default(),
         },
-        root: usvg::Node::new(usvg::NodeKind::Group(usvg::Group::default())),
-    };
+        usvg::Node::new(usvg::NodeKind::Group(usvg::Group::default())),
+    );
 
     let gradient = usvg::LinearGradient {
         id: "lg1".into(),
@@ -23,6 +23,7 @@ fn main() {
             units: usvg::Units::ObjectBoundingBox,
             transform: usvg::Transform::default(),
             spread_method: usvg::SpreadMethod::Pad,
+            color_interpolation: usvg::ColorInterpolation::SRGB,
             stops: vec![
                 usvg::Stop {
                     offset: usvg::StopOffset::ZERO,
@@ -676,6 +676,7 @@ impl AttributeId {
                 | AttributeId::FloodColor
                 | AttributeId::FloodOpacity
                 | AttributeId::FontFamily
+                | AttributeId::FontFeatureSettings // technically not presentation
                 | AttributeId::FontKerning // technically not presentation
                 | AttributeId::FontSize
                 | AttributeId::FontSizeAdjust
@@ -693,6 +694,7 @@ impl AttributeId {
                 | AttributeId::MarkerMid
                 | AttributeId::MarkerStart
                 | AttributeId::Mask
+                | AttributeId::MaskType
                 | AttributeId::MixBlendMode // technically not presentation
                 | AttributeId::Opacity
                 | AttributeId::Overflow
@@ -750,6 +752,7 @@ impl AttributeId {
                 | AttributeId::FloodColor
                 | AttributeId::FloodOpacity
                 | AttributeId::FontFamily
+                | AttributeId::FontFeatureSettings
                 | AttributeId::FontKerning
                 | AttributeId::FontSize
                 | AttributeId::FontStretch
@@ -763,6 +766,7 @@ impl AttributeId {
                 | AttributeId::MarkerMid
                 | AttributeId::MarkerStart
                 | AttributeId::Mask
+                | AttributeId::MaskType
                 | AttributeId::Opacity
                 | AttributeId::Overflow
                 | AttributeId::ShapeRendering
@@ -798,6 +802,7 @@ fn is_non_inheritable(id: AttributeId) -> bool {
             | AttributeId::FloodColor
             | AttributeId::FloodOpacity
             | AttributeId::Mask
+            | AttributeId::MaskType
             | AttributeId::Opacity
             | AttributeId::Overflow
             | AttributeId::LightingColor
@@ -805,5 +810,6 @@ fn is_non_inheritable(id: AttributeId) -> bool {
             | AttributeId::StopOpacity
             | AttributeId::TextDecoration
             | AttributeId::Transform
+            | AttributeId::VectorEffect
     )
 }
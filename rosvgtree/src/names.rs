@@ -247,6 +247,7 @@ pub enum AttributeId {
     MarkerUnits,
     MarkerWidth,
     Mask,
+    MaskType,
     MaskContentUnits,
     MaskUnits,
     MixBlendMode,
@@ -357,239 +358,240 @@ pub enum AttributeId {
 static ATTRIBUTES: Map<AttributeId> = Map {
     key: 732231254413039614,
     disps: &[
-        (0, 103),
-        (1, 31),
-        (0, 1),
-        (0, 2),
-        (0, 31),
-        (3, 20),
-        (0, 164),
-        (0, 97),
-        (0, 2),
-        (0, 4),
-        (0, 12),
-        (0, 166),
-        (0, 1),
-        (4, 174),
-        (2, 158),
-        (0, 13),
-        (0, 18),
-        (0, 0),
-        (0, 138),
-        (7, 16),
-        (1, 100),
-        (5, 19),
-        (10, 59),
-        (0, 5),
-        (0, 146),
-        (0, 44),
-        (0, 72),
-        (0, 26),
-        (0, 1),
-        (3, 147),
-        (0, 7),
-        (0, 11),
+        (2, 101),
         (0, 10),
-        (5, 91),
+        (1, 2),
+        (4, 190),
+        (0, 5),
+        (0, 111),
+        (1, 147),
+        (0, 149),
+        (0, 0),
+        (0, 40),
+        (0, 132),
+        (0, 109),
+        (0, 3),
+        (2, 99),
+        (0, 106),
+        (0, 14),
+        (0, 15),
+        (0, 0),
+        (12, 131),
+        (16, 76),
+        (0, 184),
+        (4, 100),
+        (2, 95),
+        (0, 0),
         (0, 74),
-        (4, 37),
-        (0, 138),
+        (1, 137),
+        (0, 130),
+        (0, 98),
+        (1, 0),
+        (0, 31),
         (0, 3),
-        (6, 164),
+        (0, 53),
+        (0, 12),
+        (7, 34),
+        (0, 52),
+        (0, 188),
+        (0, 112),
+        (0, 9),
+        (8, 52),
     ],
     entries: &[
-        ("color-interpolation", AttributeId::ColorInterpolation),
-        ("id", AttributeId::Id),
-        ("inline-size", AttributeId::InlineSize),
-        ("flood-opacity", AttributeId::FloodOpacity),
-        ("kernelMatrix", AttributeId::KernelMatrix),
-        ("font-stretch", AttributeId::FontStretch),
-        ("mask", AttributeId::Mask),
-        ("order", AttributeId::Order),
-        ("kerning", AttributeId::Kerning),
-        ("font-size-adjust", AttributeId::FontSizeAdjust),
-        ("limitingConeAngle", AttributeId::LimitingConeAngle),
-        ("azimuth", AttributeId::Azimuth),
-        ("k4", AttributeId::K4),
-        ("fill", AttributeId::Fill),
-        ("intercept", AttributeId::Intercept),
-        ("text-align-last", AttributeId::TextAlignLast),
-        ("text-decoration-fill", AttributeId::TextDecorationFill),
-        ("textLength", AttributeId::TextLength),
-        ("patternUnits", AttributeId::PatternUnits),
-        ("space", AttributeId::Space),
-        ("side", AttributeId::Side),
-        ("line-height", AttributeId::LineHeight),
-        ("stroke-linejoin", AttributeId::StrokeLinejoin),
-        ("xChannelSelector", AttributeId::XChannelSelector),
-        ("kernelUnitLength", AttributeId::KernelUnitLength),
-        ("k3", AttributeId::K3),
-        ("font-feature-settings", AttributeId::FontFeatureSettings),
-        ("fill-opacity", AttributeId::FillOpacity),
-        ("transform-box", AttributeId::TransformBox),
-        ("lengthAdjust", AttributeId::LengthAdjust),
-        ("marker-end", AttributeId::MarkerEnd),
-        ("y1", AttributeId::Y1),
+        ("text-decoration-stroke", AttributeId::TextDecorationStroke),
+        ("transform", AttributeId::Transform),
+        ("color", AttributeId::Color),
+        ("filterUnits", AttributeId::FilterUnits),
+        ("refY", AttributeId::RefY),
+        ("text-orientation", AttributeId::TextOrientation),
+        ("z", AttributeId::Z),
+        ("values", AttributeId::Values),
         ("specularExponent", AttributeId::SpecularExponent),
-        ("direction", AttributeId::Direction),
-        ("stroke-linecap", AttributeId::StrokeLinecap),
-        ("display", AttributeId::Display),
-        ("text-overflow", AttributeId::TextOverflow),
-        ("text-decoration-color", AttributeId::TextDecorationColor),
-        ("transform-origin", AttributeId::TransformOrigin),
-        ("lighting-color", AttributeId::LightingColor),
-        ("systemLanguage", AttributeId::SystemLanguage),
-        ("pointsAtZ", AttributeId::PointsAtZ),
         ("cy", AttributeId::Cy),
-        ("k2", AttributeId::K2),
-        ("specularConstant", AttributeId::SpecularConstant),
-        ("tableValues", AttributeId::TableValues),
-        ("dominant-baseline", AttributeId::DominantBaseline),
-        ("stroke-width", AttributeId::StrokeWidth),
-        ("dx", AttributeId::Dx),
-        ("font-variant-caps", AttributeId::FontVariantCaps),
-        ("shape-subtract", AttributeId::ShapeSubtract),
-        ("shape-image-threshold", AttributeId::ShapeImageThreshold),
-        ("gradientTransform", AttributeId::GradientTransform),
-        ("pointsAtX", AttributeId::PointsAtX),
-        ("cx", AttributeId::Cx),
-        ("filterUnits", AttributeId::FilterUnits),
-        ("image-rendering", AttributeId::ImageRendering),
+        ("radius", AttributeId::Radius),
+        ("paint-order", AttributeId::PaintOrder),
         ("fx", AttributeId::Fx),
-        ("gradientUnits", AttributeId::GradientUnits),
-        ("text-orientation", AttributeId::TextOrientation),
-        ("exponent", AttributeId::Exponent),
-        ("rotate", AttributeId::Rotate),
-        ("d", AttributeId::D),
-        ("mix-blend-mode", AttributeId::MixBlendMode),
-        ("rx", AttributeId::Rx),
+        ("font-variant-east-asian", AttributeId::FontVariantEastAsian),
+        ("font-variant-ligatures", AttributeId::FontVariantLigatures),
+        ("font-weight", AttributeId::FontWeight),
+        ("in2", AttributeId::In2),
+        ("stroke", AttributeId::Stroke),
         ("text-rendering", AttributeId::TextRendering),
-        ("glyph-orientation-horizontal", AttributeId::GlyphOrientationHorizontal),
-        ("enable-background", AttributeId::EnableBackground),
-        ("scale", AttributeId::Scale),
+        ("stroke-miterlimit", AttributeId::StrokeMiterlimit),
+        ("stdDeviation", AttributeId::StdDeviation),
+        ("dominant-baseline", AttributeId::DominantBaseline),
+        ("font-feature-settings", AttributeId::FontFeatureSettings),
+        ("font-variant-caps", AttributeId::FontVariantCaps),
+        ("order", AttributeId::Order),
+        ("ry", AttributeId::Ry),
+        ("markerUnits", AttributeId::MarkerUnits),
+        ("text-overflow", AttributeId::TextOverflow),
+        ("y2", AttributeId::Y2),
+        ("fill", AttributeId::Fill),
+        ("line-height", AttributeId::LineHeight),
+        ("font", AttributeId::Font),
+        ("stroke-width", AttributeId::StrokeWidth),
         ("in", AttributeId::In),
-        ("font-variant-numeric", AttributeId::FontVariantNumeric),
-        ("font-variant-east-asian", AttributeId::FontVariantEastAsian),
+        ("width", AttributeId::Width),
+        ("word-spacing", AttributeId::WordSpacing),
+        ("stroke-linejoin", AttributeId::StrokeLinejoin),
+        ("maskUnits", AttributeId::MaskUnits),
+        ("text-align-last", AttributeId::TextAlignLast),
+        ("exponent", AttributeId::Exponent),
+        ("gradientTransform", AttributeId::GradientTransform),
+        ("limitingConeAngle", AttributeId::LimitingConeAngle),
+        ("writing-mode", AttributeId::WritingMode),
+        ("seed", AttributeId::Seed),
+        ("font-size-adjust", AttributeId::FontSizeAdjust),
+        ("k1", AttributeId::K1),
+        ("tableValues", AttributeId::TableValues),
+        ("shape-margin", AttributeId::ShapeMargin),
+        ("spreadMethod", AttributeId::SpreadMethod),
+        ("color-interpolation", AttributeId::ColorInterpolation),
+        ("class", AttributeId::Class),
+        ("stroke-opacity", AttributeId::StrokeOpacity),
+        ("pointsAtY", AttributeId::PointsAtY),
+        ("type", AttributeId::Type),
         ("text-anchor", AttributeId::TextAnchor),
-        ("glyph-orientation-vertical", AttributeId::GlyphOrientationVertical),
-        ("dy", AttributeId::Dy),
+        ("k4", AttributeId::K4),
+        ("space", AttributeId::Space),
+        ("elevation", AttributeId::Elevation),
+        ("kernelMatrix", AttributeId::KernelMatrix),
+        ("font-size", AttributeId::FontSize),
+        ("font-style", AttributeId::FontStyle),
+        ("operator", AttributeId::Operator),
+        ("patternTransform", AttributeId::PatternTransform),
+        ("vector-effect", AttributeId::VectorEffect),
+        ("stroke-dasharray", AttributeId::StrokeDasharray),
+        ("stroke-linecap", AttributeId::StrokeLinecap),
         ("color-interpolation-filters", AttributeId::ColorInterpolationFilters),
-        ("clip-rule", AttributeId::ClipRule),
-        ("stroke-miterlimit", AttributeId::StrokeMiterlimit),
-        ("isolation", AttributeId::Isolation),
-        ("transform", AttributeId::Transform),
-        ("stroke-dashoffset", AttributeId::StrokeDashoffset),
-        ("z", AttributeId::Z),
+        ("result", AttributeId::Result),
+        ("letter-spacing", AttributeId::LetterSpacing),
+        ("preserveAspectRatio", AttributeId::PreserveAspectRatio),
         ("refX", AttributeId::RefX),
-        ("width", AttributeId::Width),
-        ("requiredExtensions", AttributeId::RequiredExtensions),
-        ("font-synthesis", AttributeId::FontSynthesis),
-        ("radius", AttributeId::Radius),
-        ("white-space", AttributeId::WhiteSpace),
-        ("patternTransform", AttributeId::PatternTransform),
+        ("flood-color", AttributeId::FloodColor),
+        ("shape-image-threshold", AttributeId::ShapeImageThreshold),
         ("maskContentUnits", AttributeId::MaskContentUnits),
-        ("stop-color", AttributeId::StopColor),
-        ("amplitude", AttributeId::Amplitude),
-        ("visibility", AttributeId::Visibility),
-        ("yChannelSelector", AttributeId::YChannelSelector),
-        ("color-rendering", AttributeId::ColorRendering),
+        ("y", AttributeId::Y),
+        ("text-decoration-fill", AttributeId::TextDecorationFill),
+        ("kernelUnitLength", AttributeId::KernelUnitLength),
+        ("fill-rule", AttributeId::FillRule),
+        ("unicode-bidi", AttributeId::UnicodeBidi),
+        ("opacity", AttributeId::Opacity),
         ("diffuseConstant", AttributeId::DiffuseConstant),
-        ("stroke", AttributeId::Stroke),
+        ("isolation", AttributeId::Isolation),
+        ("k3", AttributeId::K3),
+        ("flood-opacity", AttributeId::FloodOpacity),
+        ("lengthAdjust", AttributeId::LengthAdjust),
+        ("numOctaves", AttributeId::NumOctaves),
+        ("path", AttributeId::Path),
+        ("baseFrequency", AttributeId::BaseFrequency),
+        ("dy", AttributeId::Dy),
+        ("mask", AttributeId::Mask),
         ("text-decoration-style", AttributeId::TextDecorationStyle),
-        ("y2", AttributeId::Y2),
-        ("operator", AttributeId::Operator),
+        ("primitiveUnits", AttributeId::PrimitiveUnits),
+        ("pointsAtX", AttributeId::PointsAtX),
+        ("clip", AttributeId::Clip),
+        ("font-family", AttributeId::FontFamily),
+        ("font-kerning", AttributeId::FontKerning),
         ("font-variant", AttributeId::FontVariant),
-        ("clip-path", AttributeId::ClipPath),
-        ("edgeMode", AttributeId::EdgeMode),
-        ("text-indent", AttributeId::TextIndent),
-        ("numOctaves", AttributeId::NumOctaves),
-        ("font-size", AttributeId::FontSize),
+        ("overflow", AttributeId::Overflow),
+        ("unicode-range", AttributeId::UnicodeRange),
+        ("filter", AttributeId::Filter),
+        ("divisor", AttributeId::Divisor),
+        ("offset", AttributeId::Offset),
+        ("side", AttributeId::Side),
+        ("mode", AttributeId::Mode),
+        ("id", AttributeId::Id),
+        ("lighting-color", AttributeId::LightingColor),
+        ("intercept", AttributeId::Intercept),
+        ("markerHeight", AttributeId::MarkerHeight),
+        ("preserveAlpha", AttributeId::PreserveAlpha),
+        ("r", AttributeId::R),
+        ("font-variant-position", AttributeId::FontVariantPosition),
+        ("mix-blend-mode", AttributeId::MixBlendMode),
+        ("text-underline-position", AttributeId::TextUnderlinePosition),
+        ("kerning", AttributeId::Kerning),
+        ("amplitude", AttributeId::Amplitude),
+        ("inline-size", AttributeId::InlineSize),
+        ("font-stretch", AttributeId::FontStretch),
         ("alignment-baseline", AttributeId::AlignmentBaseline),
-        ("stroke-opacity", AttributeId::StrokeOpacity),
-        ("class", AttributeId::Class),
-        ("refY", AttributeId::RefY),
-        ("stitchTiles", AttributeId::StitchTiles),
-        ("x2", AttributeId::X2),
-        ("unicode-bidi", AttributeId::UnicodeBidi),
-        ("targetY", AttributeId::TargetY),
-        ("vector-effect", AttributeId::VectorEffect),
-        ("fy", AttributeId::Fy),
-        ("clipPathUnits", AttributeId::ClipPathUnits),
-        ("href", AttributeId::Href),
-        ("x1", AttributeId::X1),
-        ("result", AttributeId::Result),
-        ("fill-rule", AttributeId::FillRule),
-        ("color-profile", AttributeId::ColorProfile),
-        ("font", AttributeId::Font),
-        ("font-weight", AttributeId::FontWeight),
-        ("shape-padding", AttributeId::ShapePadding),
-        ("primitiveUnits", AttributeId::PrimitiveUnits),
         ("height", AttributeId::Height),
-        ("bias", AttributeId::Bias),
-        ("maskUnits", AttributeId::MaskUnits),
-        ("pointsAtY", AttributeId::PointsAtY),
-        ("paint-order", AttributeId::PaintOrder),
         ("text-decoration-line", AttributeId::TextDecorationLine),
-        ("preserveAlpha", AttributeId::PreserveAlpha),
+        ("orient", AttributeId::Orient),
+        ("y1", AttributeId::Y1),
+        ("targetY", AttributeId::TargetY),
         ("shape-rendering", AttributeId::ShapeRendering),
-        ("text-underline-position", AttributeId::TextUnderlinePosition),
-        ("stroke-dasharray", AttributeId::StrokeDasharray),
-        ("ry", AttributeId::Ry),
-        ("preserveAspectRatio", AttributeId::PreserveAspectRatio),
         ("markerWidth", AttributeId::MarkerWidth),
-        ("letter-spacing", AttributeId::LetterSpacing),
-        ("font-variant-position", AttributeId::FontVariantPosition),
-        ("y", AttributeId::Y),
+        ("clip-rule", AttributeId::ClipRule),
+        ("marker-mid", AttributeId::MarkerMid),
+        ("dx", AttributeId::Dx),
+        ("scale", AttributeId::Scale),
+        ("gradientUnits", AttributeId::GradientUnits),
+        ("font-synthesis", AttributeId::FontSynthesis),
+        ("color-profile", AttributeId::ColorProfile),
+        ("x1", AttributeId::X1),
+        ("visibility", AttributeId::Visibility),
+        ("baseline-shift", AttributeId::BaselineShift),
+        ("image-rendering", AttributeId::ImageRendering),
+        ("transform-box", AttributeId::TransformBox),
+        ("rx", AttributeId::Rx),
+        ("fy", AttributeId::Fy),
         ("style", AttributeId::Style),
-        ("requiredFeatures", AttributeId::RequiredFeatures),
-        ("text-align", AttributeId::TextAlign),
-        ("divisor", AttributeId::Divisor),
-        ("offset", AttributeId::Offset),
-        ("overflow", AttributeId::Overflow),
-        ("font-kerning", AttributeId::FontKerning),
         ("slope", AttributeId::Slope),
-        ("marker-mid", AttributeId::MarkerMid),
-        ("mode", AttributeId::Mode),
+        ("glyph-orientation-horizontal", AttributeId::GlyphOrientationHorizontal),
+        ("text-indent", AttributeId::TextIndent),
         ("x", AttributeId::X),
-        ("elevation", AttributeId::Elevation),
-        ("k1", AttributeId::K1),
-        ("startOffset", AttributeId::StartOffset),
-        ("clip", AttributeId::Clip),
-        ("surfaceScale", AttributeId::SurfaceScale),
+        ("clip-path", AttributeId::ClipPath),
+        ("xChannelSelector", AttributeId::XChannelSelector),
+        ("pointsAtZ", AttributeId::PointsAtZ),
+        ("glyph-orientation-vertical", AttributeId::GlyphOrientationVertical),
+        ("href", AttributeId::Href),
+        ("pathLength", AttributeId::PathLength),
         ("points", AttributeId::Points),
+        ("viewBox", AttributeId::ViewBox),
+        ("requiredExtensions", AttributeId::RequiredExtensions),
         ("targetX", AttributeId::TargetX),
-        ("text-decoration", AttributeId::TextDecoration),
-        ("font-style", AttributeId::FontStyle),
-        ("seed", AttributeId::Seed),
+        ("marker-end", AttributeId::MarkerEnd),
+        ("fill-opacity", AttributeId::FillOpacity),
         ("stop-opacity", AttributeId::StopOpacity),
-        ("values", AttributeId::Values),
-        ("stdDeviation", AttributeId::StdDeviation),
-        ("spreadMethod", AttributeId::SpreadMethod),
-        ("flood-color", AttributeId::FloodColor),
-        ("color", AttributeId::Color),
-        ("shape-inside", AttributeId::ShapeInside),
-        ("marker-start", AttributeId::MarkerStart),
-        ("text-decoration-stroke", AttributeId::TextDecorationStroke),
+        ("systemLanguage", AttributeId::SystemLanguage),
+        ("text-align", AttributeId::TextAlign),
+        ("transform-origin", AttributeId::TransformOrigin),
+        ("patternUnits", AttributeId::PatternUnits),
+        ("text-decoration-color", AttributeId::TextDecorationColor),
+        ("enable-background", AttributeId::EnableBackground),
+        ("white-space", AttributeId::WhiteSpace),
+        ("clipPathUnits", AttributeId::ClipPathUnits),
+        ("requiredFeatures", AttributeId::RequiredFeatures),
+        ("direction", AttributeId::Direction),
+        ("yChannelSelector", AttributeId::YChannelSelector),
+        ("bias", AttributeId::Bias),
+        ("shape-padding", AttributeId::ShapePadding),
+        ("shape-subtract", AttributeId::ShapeSubtract),
+        ("mask-type", AttributeId::MaskType),
+        ("cx", AttributeId::Cx),
+        ("rotate", AttributeId::Rotate),
+        ("display", AttributeId::Display),
+        ("edgeMode", AttributeId::EdgeMode),
         ("patternContentUnits", AttributeId::PatternContentUnits),
-        ("word-spacing", AttributeId::WordSpacing),
-        ("orient", AttributeId::Orient),
-        ("in2", AttributeId::In2),
-        ("unicode-range", AttributeId::UnicodeRange),
-        ("opacity", AttributeId::Opacity),
-        ("baseFrequency", AttributeId::BaseFrequency),
-        ("path", AttributeId::Path),
-        ("markerHeight", AttributeId::MarkerHeight),
-        ("pathLength", AttributeId::PathLength),
-        ("r", AttributeId::R),
-        ("writing-mode", AttributeId::WritingMode),
-        ("shape-margin", AttributeId::ShapeMargin),
-        ("viewBox", AttributeId::ViewBox),
-        ("font-family", AttributeId::FontFamily),
-        ("filter", AttributeId::Filter),
-        ("markerUnits", AttributeId::MarkerUnits),
-        ("type", AttributeId::Type),
-        ("baseline-shift", AttributeId::BaselineShift),
-        ("font-variant-ligatures", AttributeId::FontVariantLigatures),
+        ("startOffset", AttributeId::StartOffset),
+        ("k2", AttributeId::K2),
+        ("marker-start", AttributeId::MarkerStart),
+        ("specularConstant", AttributeId::SpecularConstant),
+        ("stroke-dashoffset", AttributeId::StrokeDashoffset),
+        ("surfaceScale", AttributeId::SurfaceScale),
+        ("d", AttributeId::D),
+        ("shape-inside", AttributeId::ShapeInside),
+        ("stop-color", AttributeId::StopColor),
+        ("font-variant-numeric", AttributeId::FontVariantNumeric),
+        ("color-rendering", AttributeId::ColorRendering),
+        ("x2", AttributeId::X2),
+        ("textLength", AttributeId::TextLength),
+        ("stitchTiles", AttributeId::StitchTiles),
+        ("text-decoration", AttributeId::TextDecoration),
+        ("azimuth", AttributeId::Azimuth),
     ],
 };
 
@@ -19,13 +19,11 @@ pub fn clip(
     clip_canvas.transform = canvas.transform;
     clip_canvas.apply_transform(cp.transform.to_native());
 
-    if cp.units == usvg::Units::ObjectBoundingBox {
-        let bbox = bbox
-            .to_rect()
-            .log_none(|| log::warn!("Clipping of zero-sized shapes is not allowed."))?;
-
-        clip_canvas.apply_transform(usvg::Transform::from_bbox(bbox).to_native());
-    }
+    let units_ts = cp
+        .units
+        .bbox_transform(bbox.to_rect())
+        .log_none(|| log::warn!("Clipping of zero-sized shapes is not allowed."))?;
+    clip_canvas.apply_transform(units_ts.to_native());
 
     draw_children(
         tree,
@@ -68,7 +66,7 @@ fn draw_children(
             usvg::NodeKind::Path(ref path_node) => {
                 crate::path::draw(tree, path_node, mode, canvas);
             }
-            usvg::NodeKind::Group(ref g) => {
+            usvg::NodeKind::Group(ref g) if !g.hidden => {
                 if let Some(ref cp) = g.clip_path {
                     // If a `clipPath` child also has a `clip-path`
                     // then we should render this child on a new canvas,
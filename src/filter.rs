@@ -433,23 +433,9 @@ pub(crate) fn calc_region(
     ts: &usvg::Transform,
     pixmap: &tiny_skia::Pixmap,
 ) -> Result<usvg::ScreenRect, Error> {
-    let path = usvg::PathData::from_rect(filter.rect);
-
-    let region_ts = if filter.units == usvg::Units::ObjectBoundingBox {
-        let bbox = bbox.ok_or(Error::InvalidRegion)?;
-        let bbox_ts = usvg::Transform::from_bbox(bbox);
-        let mut ts2 = *ts;
-        ts2.append(&bbox_ts);
-        ts2
-    } else {
-        *ts
-    };
-
     let canvas_rect = usvg::ScreenRect::new(0, 0, pixmap.width(), pixmap.height()).unwrap();
-    let region = path
-        .bbox_with_transform(region_ts, None)
-        .ok_or(Error::InvalidRegion)?
-        .to_rect()
+    let region = filter
+        .region(bbox, *ts)
         .ok_or(Error::InvalidRegion)?
         .to_screen_rect()
         .fit_to_rect(canvas_rect);
@@ -1277,10 +1263,6 @@ fn scale_coordinates(
     ts: &usvg::Transform,
 ) -> Option<(f64, f64)> {
     let (sx, sy) = ts.get_scale();
-    if units == usvg::Units::ObjectBoundingBox {
-        let bbox = bbox?;
-        Some((x * sx * bbox.width(), y * sy * bbox.height()))
-    } else {
-        Some((x * sx, y * sy))
-    }
+    let units_ts = units.bbox_transform(bbox)?;
+    Some((x * sx * units_ts.a, y * sy * units_ts.d))
 }
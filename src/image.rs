@@ -44,6 +44,10 @@ pub fn draw_kind(
             }
             None => log::warn!("Failed to decode a GIF image."),
         },
+        #[cfg(feature = "raster-images")]
+        usvg::ImageKind::WEBP(_) => {
+            log::warn!("WebP images are not supported.");
+        }
         #[cfg(not(feature = "raster-images"))]
         _ => {
             log::warn!("Images decoding was disabled by a build feature.");
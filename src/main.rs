@@ -179,7 +179,8 @@ OPTIONS:
                                 geometricPrecision]
   --image-rendering HINT        Selects the default image rendering method
                                 [default: optimizeQuality]
-                                [possible values: optimizeQuality, optimizeSpeed]
+                                [possible values: optimizeQuality, optimizeSpeed,
+                                pixelated, crisp-edges]
   --resources-dir DIR           Sets a directory that will be used during
                                 relative paths resolving.
                                 Expected to be the same as the directory that
@@ -508,7 +509,7 @@ fn parse_args() -> Result<Args, String> {
         text_rendering: args.text_rendering,
         image_rendering: args.image_rendering,
         default_size,
-        image_href_resolver: usvg::ImageHrefResolver::default(),
+        ..usvg::Options::default()
     };
 
     Ok(Args {
@@ -13,19 +13,17 @@ pub fn mask(
     bbox: usvg::PathBbox,
     canvas: &mut Canvas,
 ) -> Option<()> {
-    let bbox = if mask.units == usvg::Units::ObjectBoundingBox
-        || mask.content_units == usvg::Units::ObjectBoundingBox
-    {
-        if let Some(bbox) = bbox.to_rect() {
-            bbox
-        } else {
+    let (r, content_ts) = match (
+        mask.region(bbox.to_rect()),
+        mask.content_transform(bbox.to_rect()),
+    ) {
+        (Some(r), Some(ts)) => (r, ts),
+        _ => {
             // `objectBoundingBox` units and zero-sized bbox? Clear the canvas and return.
             // Technically a UB, but this is what Chrome and Firefox do.
             canvas.pixmap.fill(tiny_skia::Color::TRANSPARENT);
             return None;
         }
-    } else {
-        usvg::Rect::new_bbox() // actual value doesn't matter, unreachable
     };
 
     let mut mask_pixmap = tiny_skia::Pixmap::new(canvas.pixmap.width(), canvas.pixmap.height())?;
@@ -33,12 +31,6 @@ pub fn mask(
         let mut mask_canvas = Canvas::from(mask_pixmap.as_mut());
         mask_canvas.transform = canvas.transform;
 
-        let r = if mask.units == usvg::Units::ObjectBoundingBox {
-            mask.rect.bbox_transform(bbox)
-        } else {
-            mask.rect
-        };
-
         let rr = tiny_skia::Rect::from_xywh(
             r.x() as f32,
             r.y() as f32,
@@ -49,20 +41,18 @@ pub fn mask(
             mask_canvas.set_clip_rect(rr);
         }
 
-        if mask.content_units == usvg::Units::ObjectBoundingBox {
-            mask_canvas.apply_transform(usvg::Transform::from_bbox(bbox).to_native());
-        }
+        mask_canvas.apply_transform(content_ts.to_native());
 
         crate::render::render_group(tree, &mask.root, &mut RenderState::Ok, &mut mask_canvas);
     }
 
-    {
+    if mask.kind == usvg::MaskType::Luminance {
         use rgb::FromSlice;
         image_to_mask(mask_pixmap.data_mut().as_rgba_mut());
     }
 
     if let Some(ref mask) = mask.mask {
-        self::mask(tree, mask, bbox.to_path_bbox(), canvas);
+        self::mask(tree, mask, bbox, canvas);
     }
 
     let mut paint = tiny_skia::PixmapPaint::default();
@@ -31,6 +31,10 @@ pub fn fill(
         usvg::Paint::RadialGradient(ref rg) => {
             prepare_radial(rg, opacity, bbox, &mut paint);
         }
+        usvg::Paint::ConicGradient(_) => {
+            log::warn!("Conic gradients are not supported.");
+            return None;
+        }
         usvg::Paint::Pattern(ref pattern) => {
             let global_ts = usvg::Transform::from_native(canvas.transform);
             let (patt_pix, patt_ts) = prepare_pattern_pixmap(tree, pattern, &global_ts, bbox)?;
@@ -82,6 +86,10 @@ pub fn stroke(
             usvg::Paint::RadialGradient(ref rg) => {
                 prepare_radial(rg, opacity, bbox, &mut paint);
             }
+            usvg::Paint::ConicGradient(_) => {
+                log::warn!("Conic gradients are not supported.");
+                return None;
+            }
             usvg::Paint::Pattern(ref pattern) => {
                 let global_ts = usvg::Transform::from_native(canvas.transform);
                 let (patt_pix, patt_ts) = prepare_pattern_pixmap(tree, pattern, &global_ts, bbox)?;
@@ -136,19 +144,12 @@ fn prepare_linear(
         usvg::SpreadMethod::Repeat => tiny_skia::SpreadMode::Repeat,
     };
 
-    let transform = {
-        if g.units == usvg::Units::ObjectBoundingBox {
-            let bbox = bbox
-                .to_rect()
-                .log_none(|| log::warn!("Gradient on zero-sized shapes is not allowed."))?;
-
-            let mut ts = usvg::Transform::from_bbox(bbox);
-            ts.append(&g.transform);
-            ts.to_native()
-        } else {
-            g.transform.to_native()
-        }
-    };
+    let mut transform = g
+        .units
+        .bbox_transform(bbox.to_rect())
+        .log_none(|| log::warn!("Gradient on zero-sized shapes is not allowed."))?;
+    transform.append(&g.transform);
+    let transform = transform.to_native();
 
     let mut points = Vec::with_capacity(g.stops.len());
     for stop in &g.stops {
@@ -192,19 +193,12 @@ fn prepare_radial(
         usvg::SpreadMethod::Repeat => tiny_skia::SpreadMode::Repeat,
     };
 
-    let transform = {
-        if g.units == usvg::Units::ObjectBoundingBox {
-            let bbox = bbox
-                .to_rect()
-                .log_none(|| log::warn!("Gradient on zero-sized shapes is not allowed."))?;
-
-            let mut ts = usvg::Transform::from_bbox(bbox);
-            ts.append(&g.transform);
-            ts.to_native()
-        } else {
-            g.transform.to_native()
-        }
-    };
+    let mut transform = g
+        .units
+        .bbox_transform(bbox.to_rect())
+        .log_none(|| log::warn!("Gradient on zero-sized shapes is not allowed."))?;
+    transform.append(&g.transform);
+    let transform = transform.to_native();
 
     let mut points = Vec::with_capacity(g.stops.len());
     for stop in &g.stops {
@@ -243,15 +237,9 @@ fn prepare_pattern_pixmap(
     global_ts: &usvg::Transform,
     bbox: usvg::PathBbox,
 ) -> Option<(tiny_skia::Pixmap, usvg::Transform)> {
-    let r = if pattern.units == usvg::Units::ObjectBoundingBox {
-        let bbox = bbox
-            .to_rect()
-            .log_none(|| log::warn!("Pattern on zero-sized shapes is not allowed."))?;
-
-        pattern.rect.bbox_transform(bbox)
-    } else {
-        pattern.rect
-    };
+    let r = pattern
+        .tile_rect(bbox.to_rect())
+        .log_none(|| log::warn!("Pattern on zero-sized shapes is not allowed."))?;
 
     let mut ts2 = *global_ts;
     ts2.append(&pattern.transform);
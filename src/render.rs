@@ -117,7 +117,9 @@ pub(crate) fn render_node(
         }
         usvg::NodeKind::Image(ref img) => Some(crate::image::draw(img, canvas)),
         usvg::NodeKind::Group(ref g) => {
-            if g.should_isolate() {
+            if g.hidden {
+                None
+            } else if g.should_isolate() {
                 render_group_impl(tree, node, g, state, canvas)
             } else {
                 render_group(tree, node, state, canvas)
@@ -105,8 +105,10 @@ fn init(mut seed: i32) -> (Vec<usize>, Vec<Vec<Vec<f64>>>) {
     let mut lattice_selector = vec![0; B_LEN];
     let mut gradient = vec![vec![vec![0.0; 2]; B_LEN]; 4];
 
+    // Same as `setup_seed` from the SVG spec.
+    // The remainder is negated and not the seed itself to avoid overflow on `i32::MIN`.
     if seed <= 0 {
-        seed = -seed % (RAND_M - 1) + 1;
+        seed = -(seed % (RAND_M - 1)) + 1;
     }
 
     if seed > RAND_M - 1 {
@@ -313,3 +315,46 @@ fn s_curve(t: f64) -> f64 {
 fn lerp(t: f64, a: f64, b: f64) -> f64 {
     a + t * (b - a)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RGBA8;
+
+    fn render(seed: i32, stitch_tiles: bool, fractal_noise: bool) -> Vec<RGBA8> {
+        let mut data = vec![RGBA8::default(); 4 * 4];
+        turbulence(
+            0.0,
+            0.0,
+            1.0,
+            1.0,
+            0.05,
+            0.05,
+            2,
+            seed,
+            stitch_tiles,
+            fractal_noise,
+            ImageRefMut::new(&mut data, 4, 4),
+        );
+        data
+    }
+
+    #[test]
+    fn sample_values() {
+        // Locks the noise output, so it will stay the same across releases.
+        assert_eq!(render(1, false, false)[5], RGBA8::new(29, 3, 19, 29));
+        assert_eq!(render(1, false, true)[5], RGBA8::new(142, 128, 118, 142));
+    }
+
+    #[test]
+    fn seed_setup() {
+        // Non-positive seeds are mapped as `-(seed % (m - 1)) + 1`, so 0 is the same as 1.
+        assert_eq!(render(0, false, false), render(1, false, false));
+        assert_eq!(render(-5, false, false), render(6, false, false));
+        assert_ne!(render(1, false, false), render(2, false, false));
+
+        // Must not overflow.
+        render(i32::MIN, false, false);
+        render(i32::MAX, false, false);
+    }
+}
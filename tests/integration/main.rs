@@ -4,6 +4,7 @@ use usvg::{fontdb, TreeParsing, TreeTextToPath};
 
 #[rustfmt::skip]
 mod render;
+mod text;
 
 const IMAGE_SIZE: u32 = 300;
 
@@ -0,0 +1,308 @@
+use std::cell::RefCell;
+
+use usvg::{fontdb, FuzzyEq, TextShaper, TreeParsing};
+
+use crate::GLOBAL_FONTDB;
+
+/// A `DefaultShaper` wrapper that remembers all produced glyph runs.
+#[derive(Default)]
+struct RecordingShaper {
+    runs: RefCell<Vec<Vec<usvg::ShapedGlyph>>>,
+}
+
+impl TextShaper for RecordingShaper {
+    fn shape(
+        &self,
+        text: &str,
+        font: fontdb::ID,
+        span: &usvg::TextSpan,
+        direction: usvg::TextDirection,
+        bidi_override: bool,
+        fontdb: &fontdb::Database,
+    ) -> Option<Vec<usvg::ShapedGlyph>> {
+        let glyphs =
+            usvg::DefaultShaper.shape(text, font, span, direction, bidi_override, fontdb)?;
+        self.runs.borrow_mut().push(glyphs.clone());
+        Some(glyphs)
+    }
+}
+
+fn convert(text: &str, shaper: &dyn TextShaper) -> usvg::Tree {
+    let mut tree = usvg::Tree::from_str(text, &usvg::Options::default()).unwrap();
+    let db = GLOBAL_FONTDB.lock().unwrap();
+    usvg::convert_tree_text_with_shaper(&mut tree, &db, shaper);
+    tree
+}
+
+/// Returns glyph clusters of the first shaped run in the visual order.
+fn shape_clusters(text: &str) -> Vec<usvg::ShapedGlyph> {
+    let shaper = RecordingShaper::default();
+    convert(text, &shaper);
+    let runs = shaper.runs.into_inner();
+    runs.into_iter().next().unwrap()
+}
+
+#[test]
+fn mixed_bidi_text() {
+    // "abc " followed by a 5 letters Arabic word, 2 bytes per letter.
+    let text = "abc مرحبا";
+
+    let ltr = shape_clusters(&format!(
+        "<svg xmlns='http://www.w3.org/2000/svg' font-family='Amiri'>
+            <text>{}</text>
+        </svg>",
+        text
+    ));
+    let clusters: Vec<_> = ltr.iter().map(|g| g.cluster).collect();
+    // The Latin run goes first, while the Arabic run is mirrored.
+    assert_eq!(&clusters[..4], &[0, 1, 2, 3]);
+    assert!(clusters[4..].windows(2).all(|w| w[0] > w[1]));
+    assert_eq!(clusters.last(), Some(&4));
+
+    let rtl = shape_clusters(&format!(
+        "<svg xmlns='http://www.w3.org/2000/svg' font-family='Amiri'>
+            <text direction='rtl'>{}</text>
+        </svg>",
+        text
+    ));
+    let clusters: Vec<_> = rtl.iter().map(|g| g.cluster).collect();
+    // The Arabic run is placed on the left side now, but the Latin run is still not mirrored.
+    assert!(clusters[..clusters.len() - 4]
+        .windows(2)
+        .all(|w| w[0] > w[1]));
+    assert_eq!(&clusters[clusters.len() - 4..], &[3, 0, 1, 2]);
+
+    let overridden = shape_clusters(&format!(
+        "<svg xmlns='http://www.w3.org/2000/svg' font-family='Amiri'>
+            <text direction='rtl' unicode-bidi='bidi-override'>{}</text>
+        </svg>",
+        text
+    ));
+    let clusters: Vec<_> = overridden.iter().map(|g| g.cluster).collect();
+    // Everything is mirrored.
+    assert!(clusters.windows(2).all(|w| w[0] > w[1]));
+}
+
+/// A shaper that places all glyphs at the same position.
+struct ZeroAdvanceShaper;
+
+impl TextShaper for ZeroAdvanceShaper {
+    fn shape(
+        &self,
+        text: &str,
+        font: fontdb::ID,
+        span: &usvg::TextSpan,
+        direction: usvg::TextDirection,
+        bidi_override: bool,
+        fontdb: &fontdb::Database,
+    ) -> Option<Vec<usvg::ShapedGlyph>> {
+        let mut glyphs =
+            usvg::DefaultShaper.shape(text, font, span, direction, bidi_override, fontdb)?;
+        for glyph in &mut glyphs {
+            glyph.x_advance = 0;
+        }
+
+        Some(glyphs)
+    }
+}
+
+/// A shaper that produces clusters outside of the text.
+struct InvalidClusterShaper;
+
+impl TextShaper for InvalidClusterShaper {
+    fn shape(
+        &self,
+        text: &str,
+        font: fontdb::ID,
+        span: &usvg::TextSpan,
+        direction: usvg::TextDirection,
+        bidi_override: bool,
+        fontdb: &fontdb::Database,
+    ) -> Option<Vec<usvg::ShapedGlyph>> {
+        let mut glyphs =
+            usvg::DefaultShaper.shape(text, font, span, direction, bidi_override, fontdb)?;
+        for glyph in &mut glyphs {
+            glyph.cluster = text.len();
+        }
+
+        Some(glyphs)
+    }
+}
+
+fn text_bbox(text: &str, shaper: &dyn TextShaper) -> Option<usvg::PathBbox> {
+    use usvg::NodeExt;
+
+    let svg = format!(
+        "<svg xmlns='http://www.w3.org/2000/svg' font-family='Noto Sans'>
+            <text x='10' y='50'>{}</text>
+        </svg>",
+        text
+    );
+    convert(&svg, shaper).root.calculate_bbox()
+}
+
+#[test]
+fn custom_shaper() {
+    let single = text_bbox("A", &usvg::DefaultShaper).unwrap();
+    let default = text_bbox("AAAA", &usvg::DefaultShaper).unwrap();
+    assert!(default.width() > single.width() * 3.0);
+
+    // All glyphs are drawn on top of each other.
+    let overlapped = text_bbox("AAAA", &ZeroAdvanceShaper).unwrap();
+    assert!(usvg::FuzzyEq::fuzzy_eq(&overlapped, &single));
+}
+
+#[test]
+fn custom_shaper_with_invalid_clusters() {
+    assert!(text_bbox("AAAA", &InvalidClusterShaper).is_none());
+}
+
+fn glyph_ids(style: &str) -> Vec<u16> {
+    let glyphs = shape_clusters(&format!(
+        "<svg xmlns='http://www.w3.org/2000/svg' font-family='Noto Serif'>
+            <text {}>ffine</text>
+        </svg>",
+        style
+    ));
+    glyphs.iter().map(|g| g.id).collect()
+}
+
+#[test]
+fn font_feature_settings() {
+    let default = glyph_ids("");
+    let no_liga = glyph_ids("font-feature-settings='\"liga\" 0'");
+    let smcp = glyph_ids("font-feature-settings='\"smcp\"'");
+    let smcp_off = glyph_ids("font-feature-settings='\"smcp\" off'");
+
+    // "ffi" is a ligature by default.
+    assert_eq!(default.len(), 3);
+    assert_eq!(no_liga.len(), 5);
+    assert_eq!(&default[1..], &no_liga[3..]);
+
+    // Small caps replace all glyphs.
+    assert_eq!(smcp.len(), 5);
+    assert!(smcp.iter().zip(&no_liga).all(|(a, b)| a != b));
+    assert_eq!(smcp_off, default);
+}
+
+/// Returns bounding boxes of all paths produced from the text, in the tree order.
+///
+/// Each span produces a separate path.
+fn paths_bboxes(text: &str) -> Vec<usvg::PathBbox> {
+    let svg = format!(
+        "<svg xmlns='http://www.w3.org/2000/svg' font-family='Noto Sans'>{}</svg>",
+        text
+    );
+    let tree = convert(&svg, &usvg::DefaultShaper);
+    let mut bboxes = Vec::new();
+    for node in tree.root.descendants() {
+        if let usvg::NodeKind::Path(ref path) = *node.borrow() {
+            bboxes.push(path.data.bbox().unwrap());
+        }
+    }
+
+    bboxes
+}
+
+#[test]
+fn text_length() {
+    for length in [40.0, 100.0] {
+        for adjust in ["spacing", "spacingAndGlyphs"] {
+            let bboxes = paths_bboxes(&format!(
+                "<text x='10' y='50' textLength='{}' lengthAdjust='{}'>Text</text>",
+                length, adjust
+            ));
+            // Glyphs side bearings are not a part of the bbox.
+            let bbox = bboxes[0];
+            assert!(
+                (bbox.width() - length).abs() < 2.0,
+                "{} {}: {}",
+                adjust,
+                length,
+                bbox.width()
+            );
+            assert!((bbox.right() - (10.0 + length)).abs() < 2.0);
+        }
+    }
+}
+
+#[test]
+fn vertical_text() {
+    let bboxes = paths_bboxes(
+        "<text x='50' y='50' writing-mode='tb'>\
+            <tspan>H</tspan><tspan>H</tspan><tspan>H</tspan>\
+         </text>",
+    );
+    assert_eq!(bboxes.len(), 3);
+    // Glyphs are stacked along the Y axis.
+    for pair in bboxes.windows(2) {
+        assert!(pair[0].x().fuzzy_eq(&pair[1].x()));
+        assert!(pair[0].bottom() < pair[1].top());
+    }
+
+    // `text-anchor` applies along the Y axis.
+    let top = |anchor: &str| {
+        let text = format!(
+            "<text x='50' y='50' writing-mode='tb' text-anchor='{}'>HHH</text>",
+            anchor
+        );
+        let bbox = paths_bboxes(&text)[0];
+        assert!(bbox.x().fuzzy_eq(&bboxes[0].x()));
+        bbox.top()
+    };
+    let start = top("start");
+    let middle = top("middle");
+    let end = top("end");
+    assert!(start > 50.0);
+    assert!(middle < start);
+    assert!((start - end).fuzzy_eq(&((start - middle) * 2.0)));
+}
+
+#[test]
+fn baseline_shift() {
+    let bboxes = paths_bboxes("<text x='10' y='50'>H<tspan baseline-shift='sub'>2</tspan>O</text>");
+    assert_eq!(bboxes.len(), 3);
+    // The subscript is placed lower than its neighbours.
+    assert!(bboxes[1].bottom() > bboxes[0].bottom());
+    assert!(bboxes[1].bottom() > bboxes[2].bottom());
+
+    let bboxes =
+        paths_bboxes("<text x='10' y='50'>H<tspan baseline-shift='super'>2</tspan>O</text>");
+    assert!(bboxes[1].top() < bboxes[0].top());
+    assert!(bboxes[1].top() < bboxes[2].top());
+
+    // Nested shifts are accumulated.
+    let bboxes = paths_bboxes(
+        "<text x='10' y='50'>\
+            H<tspan baseline-shift='-5'>2<tspan baseline-shift='-5'>3</tspan></tspan>O\
+         </text>",
+    );
+    assert_eq!(bboxes.len(), 4);
+    let baseline = bboxes[0].bottom();
+    assert!((bboxes[1].bottom() - baseline - 5.0).abs() < 0.5);
+    assert!((bboxes[2].bottom() - baseline - 10.0).abs() < 0.5);
+}
+
+#[test]
+fn letter_and_word_spacing() {
+    let width = |text: &str| paths_bboxes(text)[0].width();
+
+    // Applied between each pair of characters.
+    let natural = width("<text x='10' y='50'>HHHH</text>");
+    let spaced = width("<text x='10' y='50' letter-spacing='5'>HHHH</text>");
+    assert!((spaced - natural).fuzzy_eq(&(5.0 * 3.0)));
+
+    let spaced = width("<text x='10' y='50' letter-spacing='-5'>HHHH</text>");
+    assert!((spaced - natural).fuzzy_eq(&(-5.0 * 3.0)));
+
+    // Applied to each space.
+    let natural = width("<text x='10' y='50'>H H H</text>");
+    let spaced = width("<text x='10' y='50' word-spacing='10'>H H H</text>");
+    assert!((spaced - natural).fuzzy_eq(&(10.0 * 2.0)));
+
+    let spaced = width("<text x='10' y='50' word-spacing='-10'>H H H</text>");
+    assert!((spaced - natural).fuzzy_eq(&(-10.0 * 2.0)));
+
+    // Glyphs will overlap, but must not panic.
+    paths_bboxes("<text x='10' y='50' letter-spacing='-100' word-spacing='-100'>H H H</text>");
+}
@@ -8,6 +8,7 @@ use std::str::FromStr;
 use rosvgtree::{self, AttributeId as AId, ElementId as EId};
 use usvg_tree::{ClipPath, FuzzyEq, Group, Node, NodeKind, Transform, Units};
 
+use crate::paint_server::convert_units;
 use crate::{converter, SvgNodeExt2};
 
 pub(crate) fn convert(
@@ -39,9 +40,7 @@ pub(crate) fn convert(
         }
     }
 
-    let units = node
-        .parse_attribute(AId::ClipPathUnits)
-        .unwrap_or(Units::UserSpaceOnUse);
+    let units = convert_units(node, AId::ClipPathUnits, Units::UserSpaceOnUse);
     let mut clip = ClipPath {
         id: node.element_id().to_string(),
         units,
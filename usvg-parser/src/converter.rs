@@ -5,13 +5,14 @@
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
+use std::sync::Arc;
 
 use rosvgtree::{self, AttributeId as AId, ElementId as EId};
 use svgtypes::{Length, LengthUnit as Unit};
 use usvg_tree::*;
 
 use crate::rosvgtree_ext::{FromValue, OpacityWrapper, SvgNodeExt, SvgNodeExt2};
-use crate::{Error, Options};
+use crate::{Error, Options, SharedLimits};
 
 #[derive(Clone)]
 pub struct State<'a> {
@@ -28,6 +29,8 @@ pub struct State<'a> {
     /// Width and height can be set independently.
     pub(crate) use_size: (Option<f64>, Option<f64>),
     pub(crate) opt: &'a Options,
+    /// Shared by a document and all SVG images nested in it.
+    pub(crate) limits: &'a SharedLimits,
 }
 
 #[derive(Default)]
@@ -93,16 +96,29 @@ pub(crate) fn convert_doc(svg_doc: &rosvgtree::Document, opt: &Options) -> Resul
             .unwrap_or_default(),
     };
 
-    let mut tree = Tree {
-        size,
-        view_box,
-        root: Node::new(NodeKind::Group(Group::default())),
-    };
+    let mut tree = Tree::new(size, view_box, Node::new(NodeKind::Group(Group::default())));
 
     if !svg.is_visible_element(opt) {
         return Ok(tree);
     }
 
+    // Nested SVG images are loaded using the document options,
+    // therefore they will share the limits with the current document.
+    let limits = match opt.shared_limits {
+        Some(ref limits) => limits.clone(),
+        None => Arc::new(SharedLimits::new(opt.elements_limit)),
+    };
+    let doc_opt;
+    let opt = if opt.shared_limits.is_some() {
+        opt
+    } else {
+        doc_opt = Options {
+            shared_limits: Some(limits.clone()),
+            ..opt.clone()
+        };
+        &doc_opt
+    };
+
     let state = State {
         parent_clip_path: None,
         parent_markers: Vec::new(),
@@ -111,6 +127,7 @@ pub(crate) fn convert_doc(svg_doc: &rosvgtree::Document, opt: &Options) -> Resul
         view_box: view_box.rect,
         use_size: (None, None),
         opt,
+        limits: &limits,
     };
 
     let mut cache = Cache::default();
@@ -126,6 +143,10 @@ pub(crate) fn convert_doc(svg_doc: &rosvgtree::Document, opt: &Options) -> Resul
 
     convert_children(svg_doc.root(), &state, &mut cache, &mut tree.root);
 
+    if let Some(e) = limits.error() {
+        return Err(e);
+    }
+
     remove_empty_groups(&mut tree);
 
     if restore_viewbox {
@@ -144,6 +165,7 @@ fn resolve_svg_size(svg: &rosvgtree::Node, opt: &Options) -> (Result<Size, Error
         view_box: Rect::new(0.0, 0.0, 100.0, 100.0).unwrap(),
         use_size: (None, None),
         opt,
+        limits: &SharedLimits::new(opt.elements_limit),
     };
 
     let def = Length::new(100.0, Unit::Percent);
@@ -252,10 +274,35 @@ pub(crate) fn convert_element(
         return None;
     }
 
-    if !node.is_visible_element(state.opt) {
+    // Each `use` instantiates the referenced elements again,
+    // so the number of converted elements can grow exponentially.
+    // Stop converting once the limit is reached. An error will be returned by `convert_doc`.
+    if !state.limits.take_elements(1) {
         return None;
     }
 
+    // `display:none` elements can be preserved inside a hidden group.
+    // Not inside a `clipPath`, since it cannot have groups.
+    let keep_hidden = state.opt.keep_hidden_nodes
+        && state.parent_clip_path.is_none()
+        && node.attribute(AId::Display) == Some("none")
+        && node.is_valid_element(state.opt);
+
+    if !keep_hidden && !node.is_visible_element(state.opt) {
+        return None;
+    }
+
+    let mut hidden_parent;
+    let parent = if keep_hidden {
+        hidden_parent = parent.append_kind(NodeKind::Group(Group {
+            hidden: true,
+            ..Group::default()
+        }));
+        &mut hidden_parent
+    } else {
+        parent
+    };
+
     if tag_name == EId::Use {
         crate::use_node::convert(node, state, cache, parent);
         return None;
@@ -332,6 +379,10 @@ pub(crate) fn convert_clip_path_elements(
             continue;
         }
 
+        if !state.limits.take_elements(1) {
+            return;
+        }
+
         if tag_name == EId::Use {
             crate::use_node::convert(node, state, cache, parent);
             continue;
@@ -506,6 +557,7 @@ pub(crate) fn convert_group(
             filter_fill,
             filter_stroke,
             enable_background,
+            hidden: false,
         }));
 
         GroupKind::Create(g)
@@ -625,11 +677,12 @@ fn convert_path(
     let rendering_mode: ShapeRendering = node
         .find_and_parse_attribute(AId::ShapeRendering)
         .unwrap_or(state.opt.shape_rendering);
+    let vector_effect = node.parse_attribute(AId::VectorEffect).unwrap_or_default();
 
     // TODO: handle `markers` before `stroke`
-    let raw_paint_order: svgtypes::PaintOrder = node
-        .find_and_parse_attribute(AId::PaintOrder)
-        .unwrap_or_default();
+    let explicit_paint_order: Option<svgtypes::PaintOrder> =
+        node.find_and_parse_attribute(AId::PaintOrder);
+    let raw_paint_order = explicit_paint_order.unwrap_or_default();
     let paint_order = svg_paint_order_to_usvg(raw_paint_order);
 
     // If a path doesn't have a fill or a stroke than it's invisible.
@@ -652,7 +705,9 @@ fn convert_path(
         fill,
         stroke,
         paint_order,
+        raw_paint_order: explicit_paint_order.map(|v| v.order.map(svg_paint_order_kind_to_usvg)),
         rendering_mode,
+        vector_effect,
         text_bbox: None,
         data: path,
     }));
@@ -666,6 +721,14 @@ fn convert_path(
     }
 }
 
+fn svg_paint_order_kind_to_usvg(kind: svgtypes::PaintOrderKind) -> PaintOrderKind {
+    match kind {
+        svgtypes::PaintOrderKind::Fill => PaintOrderKind::Fill,
+        svgtypes::PaintOrderKind::Stroke => PaintOrderKind::Stroke,
+        svgtypes::PaintOrderKind::Markers => PaintOrderKind::Markers,
+    }
+}
+
 pub fn svg_paint_order_to_usvg(order: svgtypes::PaintOrder) -> PaintOrder {
     match (order.order[0], order.order[1]) {
         (svgtypes::PaintOrderKind::Stroke, _) => PaintOrder::StrokeAndFill,
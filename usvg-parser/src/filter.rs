@@ -18,11 +18,11 @@ use crate::paint_server::{convert_units, resolve_number};
 use crate::rosvgtree_ext::{OpacityWrapper, SvgColorExt, SvgNodeExt, SvgNodeExt2};
 use crate::{converter, FromValue, OptionLog};
 
-impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::filter::ColorInterpolation {
+impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::ColorInterpolation {
     fn parse(_: rosvgtree::Node, _: rosvgtree::AttributeId, value: &str) -> Option<Self> {
         match value {
-            "sRGB" => Some(usvg_tree::filter::ColorInterpolation::SRGB),
-            "linearRGB" => Some(usvg_tree::filter::ColorInterpolation::LinearRGB),
+            "sRGB" => Some(usvg_tree::ColorInterpolation::SRGB),
+            "linearRGB" => Some(usvg_tree::ColorInterpolation::LinearRGB),
             _ => None,
         }
     }
@@ -58,6 +58,7 @@ pub(crate) fn convert(
             units: Units::ObjectBoundingBox,
             primitive_units: Units::UserSpaceOnUse,
             rect,
+            color_interpolation: ColorInterpolation::SRGB,
             primitives: vec![Primitive {
                 x: None,
                 y: None,
@@ -213,6 +214,9 @@ fn convert_url(
         units,
         primitive_units,
         rect,
+        color_interpolation: node
+            .find_and_parse_attribute(AId::ColorInterpolationFilters)
+            .unwrap_or_default(),
         primitives,
     });
 
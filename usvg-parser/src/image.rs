@@ -13,15 +13,30 @@ use crate::{converter, OptionLog, Options, SvgNodeExt, TreeParsing};
 
 /// A shorthand for [ImageHrefResolver]'s data function.
 pub type ImageHrefDataResolverFn =
-    Box<dyn Fn(&str, Arc<Vec<u8>>, &Options) -> Option<ImageKind> + Send + Sync>;
+    Arc<dyn Fn(&str, Arc<Vec<u8>>, &Options) -> Option<ImageKind> + Send + Sync>;
 /// A shorthand for [ImageHrefResolver]'s string function.
-pub type ImageHrefStringResolverFn = Box<dyn Fn(&str, &Options) -> Option<ImageKind> + Send + Sync>;
+pub type ImageHrefStringResolverFn = Arc<dyn Fn(&str, &Options) -> Option<ImageKind> + Send + Sync>;
 
 /// An `xlink:href` resolver for `<image>` elements.
 ///
 /// This type can be useful if you want to have an alternative `xlink:href` handling
 /// to the default one. For example, you can forbid access to local files (which is allowed by default)
 /// or add support for resolving actual URLs (usvg doesn't do any network requests).
+///
+/// The following policies are available:
+///
+/// - [`ImageHrefResolver::default`] loads data URLs and any local files.
+///   Kept for backward compatibility. Should not be used for untrusted SVGs.
+/// - [`ImageHrefResolver::data_uri_only`] loads data URLs only
+///   and never accesses the file system.
+/// - [`ImageHrefResolver::resources_dir_only`] loads data URLs and files
+///   inside [Options::resources_dir](crate::Options::resources_dir) only.
+/// - A custom policy can be implemented by setting `resolve_data` and `resolve_string`
+///   to your own closures, which can return any `ImageKind`.
+///
+/// Nested SVG images use the same resolver, but without
+/// [Options::resources_dir](crate::Options::resources_dir).
+#[derive(Clone)]
 pub struct ImageHrefResolver {
     /// Resolver function that will be used when `xlink:href` contains a
     /// [Data URL](https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/Data_URIs).
@@ -34,6 +49,7 @@ pub struct ImageHrefResolver {
 }
 
 impl Default for ImageHrefResolver {
+    /// Creates a resolver that loads data URLs and any local files.
     fn default() -> Self {
         ImageHrefResolver {
             resolve_data: ImageHrefResolver::default_data_resolver(),
@@ -43,28 +59,61 @@ impl Default for ImageHrefResolver {
 }
 
 impl ImageHrefResolver {
+    /// Creates a resolver that loads only
+    /// [Data URLs](https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/Data_URIs).
+    ///
+    /// All other `xlink:href` values are ignored, so the file system is never accessed.
+    pub fn data_uri_only() -> Self {
+        ImageHrefResolver {
+            resolve_data: ImageHrefResolver::default_data_resolver(),
+            resolve_string: Arc::new(|href: &str, _: &Options| {
+                log::warn!("'{}' is not a data URL. Skipped.", href);
+                None
+            }),
+        }
+    }
+
+    /// Creates a resolver that loads Data URLs and files
+    /// inside [Options::resources_dir](crate::Options::resources_dir) only.
+    ///
+    /// Absolute paths and paths outside of the resources directory,
+    /// including the ones resolved via `..` or symlinks, are ignored.
+    /// When `resources_dir` is not set, no files will be loaded.
+    pub fn resources_dir_only() -> Self {
+        ImageHrefResolver {
+            resolve_data: ImageHrefResolver::default_data_resolver(),
+            resolve_string: Arc::new(|href: &str, opts: &Options| {
+                let path = match resolve_path_inside_resources_dir(href, opts) {
+                    Some(path) => path,
+                    None => {
+                        log::warn!("'{}' is outside of the resources directory. Skipped.", href);
+                        return None;
+                    }
+                };
+
+                load_image_file(href, &path, opts)
+            }),
+        }
+    }
+
     /// Creates a default
     /// [Data URL](https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/Data_URIs)
     /// resolver closure.
     ///
     /// base64 encoded data is already decoded.
     ///
-    /// The default implementation would try to load JPEG, PNG, GIF, SVG and SVGZ types.
+    /// The default implementation would try to load JPEG, PNG, GIF, WebP, SVG and SVGZ types.
     /// Note that it will simply match the `mime` or data's magic.
     /// The actual images would not be decoded. It's up to the renderer.
     pub fn default_data_resolver() -> ImageHrefDataResolverFn {
-        Box::new(
+        Arc::new(
             move |mime: &str, data: Arc<Vec<u8>>, opts: &Options| match mime {
                 "image/jpg" | "image/jpeg" => Some(ImageKind::JPEG(data)),
                 "image/png" => Some(ImageKind::PNG(data)),
                 "image/gif" => Some(ImageKind::GIF(data)),
+                "image/webp" => Some(ImageKind::WEBP(data)),
                 "image/svg+xml" => load_sub_svg(&data, opts),
-                "text/plain" => match get_image_data_format(&data) {
-                    Some(ImageFormat::JPEG) => Some(ImageKind::JPEG(data)),
-                    Some(ImageFormat::PNG) => Some(ImageKind::PNG(data)),
-                    Some(ImageFormat::GIF) => Some(ImageKind::GIF(data)),
-                    _ => load_sub_svg(&data, opts),
-                },
+                "text/plain" => ImageKind::from_bytes(data, opts),
                 _ => None,
             },
         )
@@ -78,28 +127,11 @@ impl ImageHrefResolver {
     /// Paths have to be absolute or relative to the input SVG file or relative to
     /// [Options::resources_dir](crate::Options::resources_dir).
     pub fn default_string_resolver() -> ImageHrefStringResolverFn {
-        Box::new(move |href: &str, opts: &Options| {
+        Arc::new(move |href: &str, opts: &Options| {
             let path = opts.get_abs_path(std::path::Path::new(href));
 
             if path.exists() {
-                let data = match std::fs::read(&path) {
-                    Ok(data) => data,
-                    Err(_) => {
-                        log::warn!("Failed to load '{}'. Skipped.", href);
-                        return None;
-                    }
-                };
-
-                match get_image_file_format(&path, &data) {
-                    Some(ImageFormat::JPEG) => Some(ImageKind::JPEG(Arc::new(data))),
-                    Some(ImageFormat::PNG) => Some(ImageKind::PNG(Arc::new(data))),
-                    Some(ImageFormat::GIF) => Some(ImageKind::GIF(Arc::new(data))),
-                    Some(ImageFormat::SVG) => load_sub_svg(&data, opts),
-                    _ => {
-                        log::warn!("'{}' is not a PNG, JPEG, GIF or SVG(Z) image.", href);
-                        None
-                    }
-                }
+                load_image_file(href, &path, opts)
             } else {
                 log::warn!("'{}' is not a path to an image.", href);
                 None
@@ -108,6 +140,68 @@ impl ImageHrefResolver {
     }
 }
 
+fn load_image_file(href: &str, path: &std::path::Path, opts: &Options) -> Option<ImageKind> {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(_) => {
+            log::warn!("Failed to load '{}'. Skipped.", href);
+            return None;
+        }
+    };
+
+    match get_image_file_format(path, &data) {
+        Some(ImageFormat::JPEG) => Some(ImageKind::JPEG(Arc::new(data))),
+        Some(ImageFormat::PNG) => Some(ImageKind::PNG(Arc::new(data))),
+        Some(ImageFormat::GIF) => Some(ImageKind::GIF(Arc::new(data))),
+        Some(ImageFormat::WEBP) => Some(ImageKind::WEBP(Arc::new(data))),
+        Some(ImageFormat::SVG) => load_sub_svg(&data, opts),
+        _ => {
+            log::warn!("'{}' is not a PNG, JPEG, GIF, WebP or SVG(Z) image.", href);
+            None
+        }
+    }
+}
+
+fn resolve_path_inside_resources_dir(href: &str, opts: &Options) -> Option<std::path::PathBuf> {
+    let dir = opts.resources_dir.as_ref()?.canonicalize().ok()?;
+
+    let rel_path = std::path::Path::new(href);
+    if rel_path.has_root() {
+        return None;
+    }
+
+    // Resolves `..` and symlinks as well.
+    let path = dir.join(rel_path).canonicalize().ok()?;
+    if path.starts_with(&dir) {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// A trait to detect `usvg_tree::ImageKind` from raw data.
+pub trait ImageKindParsing: Sized {
+    /// Detects an image kind using the data's magic.
+    ///
+    /// JPEG, PNG, GIF and WebP data is stored as is and would not be decoded.
+    /// Anything else would be parsed as an SVG or SVGZ image.
+    ///
+    /// Returns `None` when data is neither a supported raster image nor a valid SVG.
+    fn from_bytes(data: Arc<Vec<u8>>, opt: &Options) -> Option<Self>;
+}
+
+impl ImageKindParsing for ImageKind {
+    fn from_bytes(data: Arc<Vec<u8>>, opt: &Options) -> Option<Self> {
+        match get_image_data_format(&data) {
+            Some(ImageFormat::JPEG) => Some(ImageKind::JPEG(data)),
+            Some(ImageFormat::PNG) => Some(ImageKind::PNG(data)),
+            Some(ImageFormat::GIF) => Some(ImageKind::GIF(data)),
+            Some(ImageFormat::WEBP) => Some(ImageKind::WEBP(data)),
+            _ => load_sub_svg(&data, opt),
+        }
+    }
+}
+
 impl std::fmt::Debug for ImageHrefResolver {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("ImageHrefResolver { .. }")
@@ -119,6 +213,7 @@ enum ImageFormat {
     PNG,
     JPEG,
     GIF,
+    WEBP,
     SVG,
 }
 
@@ -141,12 +236,13 @@ pub(crate) fn convert(
         .unwrap_or(state.opt.image_rendering);
 
     let actual_size = match kind {
-        ImageKind::JPEG(ref data) | ImageKind::PNG(ref data) | ImageKind::GIF(ref data) => {
-            imagesize::blob_size(data)
-                .ok()
-                .and_then(|size| Size::new(size.width as f64, size.height as f64))
-                .log_none(|| log::warn!("Image has an invalid size. Skipped."))?
-        }
+        ImageKind::JPEG(ref data)
+        | ImageKind::PNG(ref data)
+        | ImageKind::GIF(ref data)
+        | ImageKind::WEBP(ref data) => imagesize::blob_size(data)
+            .ok()
+            .and_then(|size| Size::new(size.width as f64, size.height as f64))
+            .log_none(|| log::warn!("Image has an invalid size. Skipped."))?,
         ImageKind::SVG(ref svg) => svg.size,
     };
 
@@ -171,6 +267,7 @@ pub(crate) fn convert(
         visibility,
         view_box,
         rendering_mode,
+        overflow: node.parse_attribute(AId::Overflow).unwrap_or_default(),
         kind,
     }));
 
@@ -193,7 +290,7 @@ pub(crate) fn get_href_data(href: &str, opt: &Options) -> Option<ImageKind> {
     }
 }
 
-/// Checks that file has a PNG, a GIF or a JPEG magic bytes.
+/// Checks that file has a PNG, a GIF, a WebP or a JPEG magic bytes.
 /// Or an SVG(Z) extension.
 fn get_image_file_format(path: &std::path::Path, data: &[u8]) -> Option<ImageFormat> {
     let ext = path.extension().and_then(|e| e.to_str())?.to_lowercase();
@@ -204,12 +301,13 @@ fn get_image_file_format(path: &std::path::Path, data: &[u8]) -> Option<ImageFor
     get_image_data_format(data.get(0..8)?)
 }
 
-/// Checks that file has a PNG, a GIF or a JPEG magic bytes.
+/// Checks that file has a PNG, a GIF, a WebP or a JPEG magic bytes.
 fn get_image_data_format(data: &[u8]) -> Option<ImageFormat> {
     match imagesize::image_type(data).ok()? {
         imagesize::ImageType::Gif => Some(ImageFormat::GIF),
         imagesize::ImageType::Jpeg => Some(ImageFormat::JPEG),
         imagesize::ImageType::Png => Some(ImageFormat::PNG),
+        imagesize::ImageType::Webp => Some(ImageFormat::WEBP),
         _ => None,
     }
 }
@@ -219,6 +317,30 @@ fn get_image_data_format(data: &[u8]) -> Option<ImageFormat> {
 /// Unlike `Tree::from_*` methods, this one will also remove all `image` elements
 /// from the loaded SVG, as required by the spec.
 pub(crate) fn load_sub_svg(data: &[u8], opt: &Options) -> Option<ImageKind> {
+    if let Some(ref limits) = opt.shared_limits {
+        // Stop loading as soon as any limit is reached, since the whole document
+        // will be rejected anyway.
+        if limits.error().is_some() {
+            return None;
+        }
+
+        if opt.nested_svg_depth_limit == 0 {
+            log::warn!("Nested SVG images depth limit has been reached.");
+            limits.set_depth_limit_reached();
+            return None;
+        }
+
+        // An image that references itself multiple times would be loaded
+        // exponentially many times, even if it's empty.
+        // Therefore each load is counted as well.
+        if !limits.take_elements(1) {
+            return None;
+        }
+    } else if opt.nested_svg_depth_limit == 0 {
+        log::warn!("Nested SVG images depth limit has been reached. Skipped.");
+        return None;
+    }
+
     let mut sub_opt = Options::default();
     sub_opt.resources_dir = None;
     sub_opt.dpi = opt.dpi;
@@ -228,6 +350,11 @@ pub(crate) fn load_sub_svg(data: &[u8], opt: &Options) -> Option<ImageKind> {
     sub_opt.text_rendering = opt.text_rendering;
     sub_opt.image_rendering = opt.image_rendering;
     sub_opt.default_size = opt.default_size;
+    sub_opt.elements_limit = opt.elements_limit;
+    sub_opt.nested_svg_depth_limit = opt.nested_svg_depth_limit - 1;
+    // Nested SVG images must follow the caller's policy as well.
+    sub_opt.image_href_resolver = opt.image_href_resolver.clone();
+    sub_opt.shared_limits = opt.shared_limits.clone();
 
     let tree = match Tree::from_data(data, &sub_opt) {
         Ok(tree) => tree,
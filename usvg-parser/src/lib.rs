@@ -38,7 +38,7 @@ mod units;
 mod use_node;
 
 pub use crate::options::*;
-pub use image::ImageHrefResolver;
+pub use image::{ImageHrefResolver, ImageKindParsing};
 pub use rosvgtree::{self, roxmltree};
 
 use crate::rosvgtree_ext::{FromValue, SvgNodeExt, SvgNodeExt2};
@@ -52,9 +52,13 @@ pub enum Error {
     /// Compressed SVG must use the GZip algorithm.
     MalformedGZip,
 
-    /// We do not allow SVG with more than 1_000_000 elements for security reasons.
+    /// We do not allow SVG with more than `Options::elements_limit` elements for security reasons.
     ElementsLimitReached,
 
+    /// We do not allow SVG images nested deeper than `Options::nested_svg_depth_limit`
+    /// for security reasons.
+    NestedSvgDepthLimitReached,
+
     /// SVG doesn't have a valid size.
     ///
     /// Occurs when width and/or height are <= 0.
@@ -84,6 +88,9 @@ impl std::fmt::Display for Error {
             Error::ElementsLimitReached => {
                 write!(f, "the maximum number of SVG elements has been reached")
             }
+            Error::NestedSvgDepthLimitReached => {
+                write!(f, "the maximum depth of nested SVG images has been reached")
+            }
             Error::InvalidSize => {
                 write!(f, "SVG has an invalid size")
             }
@@ -9,7 +9,7 @@ use rosvgtree::{self, AttributeId as AId, ElementId as EId};
 use strict_num::NonZeroPositiveF64;
 use svgtypes::Length;
 use usvg_tree::{
-    ClipPath, FuzzyEq, FuzzyZero, Group, Node, NodeExt, NodeKind, Path, PathData,
+    ClipPath, FuzzyEq, FuzzyZero, Group, Node, NodeExt, NodeKind, Overflow, Path, PathData,
     PathSegment as Segment, Rect, Size, Transform, ViewBox,
 };
 
@@ -97,11 +97,11 @@ fn resolve(
             .unwrap_or_default(),
     });
 
-    let has_overflow = {
-        let overflow = marker_node.attribute(AId::Overflow);
-        // `overflow` is `hidden` by default.
-        overflow.is_none() || overflow == Some("hidden") || overflow == Some("scroll")
-    };
+    // `overflow` is `hidden` by default.
+    let has_overflow = marker_node
+        .parse_attribute::<Overflow>(AId::Overflow)
+        .unwrap_or_default()
+        == Overflow::Hidden;
 
     let clip_path = if has_overflow {
         let clip_rect = if let Some(vbox) = view_box {
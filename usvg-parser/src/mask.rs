@@ -6,11 +6,22 @@ use std::rc::Rc;
 
 use rosvgtree::{self, AttributeId as AId, ElementId as EId};
 use svgtypes::{Length, LengthUnit as Unit};
-use usvg_tree::{Group, Mask, Node, NodeKind, Rect, Units};
+use usvg_tree::{Group, Mask, MaskType, Node, NodeKind, Rect, Units};
 
-use crate::rosvgtree_ext::SvgNodeExt2;
+use crate::paint_server::convert_units;
+use crate::rosvgtree_ext::{FromValue, SvgNodeExt2};
 use crate::{converter, OptionLog, SvgNodeExt};
 
+impl<'a, 'input: 'a> FromValue<'a, 'input> for MaskType {
+    fn parse(_: rosvgtree::Node, _: AId, value: &str) -> Option<Self> {
+        match value {
+            "luminance" => Some(MaskType::Luminance),
+            "alpha" => Some(MaskType::Alpha),
+            _ => None,
+        }
+    }
+}
+
 pub(crate) fn convert(
     node: rosvgtree::Node,
     state: &converter::State,
@@ -26,12 +37,8 @@ pub(crate) fn convert(
         return Some(mask.clone());
     }
 
-    let units = node
-        .parse_attribute(AId::MaskUnits)
-        .unwrap_or(Units::ObjectBoundingBox);
-    let content_units = node
-        .parse_attribute(AId::MaskContentUnits)
-        .unwrap_or(Units::UserSpaceOnUse);
+    let units = convert_units(node, AId::MaskUnits, Units::ObjectBoundingBox);
+    let content_units = convert_units(node, AId::MaskContentUnits, Units::UserSpaceOnUse);
 
     let rect = Rect::new(
         node.convert_length(AId::X, units, state, Length::new(-10.0, Unit::Percent)),
@@ -58,6 +65,8 @@ pub(crate) fn convert(
         units,
         content_units,
         rect,
+        kind: node.parse_attribute(AId::MaskType).unwrap_or_default(),
+        overflow: node.parse_attribute(AId::Overflow).unwrap_or_default(),
         mask,
         root: Node::new(NodeKind::Group(Group::default())),
     };
@@ -2,12 +2,15 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
 use usvg_tree::{ImageRendering, ShapeRendering, Size, TextRendering};
 
-use crate::ImageHrefResolver;
+use crate::{Error, ImageHrefResolver};
 
 /// Processing options.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Options {
     /// Directory that will be used during relative paths resolving.
     ///
@@ -78,6 +81,58 @@ pub struct Options {
     ///
     /// Default: see type's documentation for details
     pub image_href_resolver: ImageHrefResolver,
+
+    /// The maximum number of elements to convert.
+    ///
+    /// Elements referenced by `use` are counted every time they are instantiated.
+    /// When the limit is reached, `Error::ElementsLimitReached` will be returned.
+    /// Elements of nested SVG images are counted as well, so the limit is shared
+    /// by a document and all SVG images nested in it. Each nested SVG image load
+    /// is counted as an element too.
+    ///
+    /// Protects from `use` elements amplification attacks in untrusted SVGs.
+    ///
+    /// Default: 1_000_000
+    pub elements_limit: usize,
+
+    /// The maximum depth of nested SVG images.
+    ///
+    /// SVG images loaded by `image` elements and `feImage` filter primitives can reference
+    /// other SVG images or even themselves. When an image is nested deeper than the limit,
+    /// `Error::NestedSvgDepthLimitReached` will be returned.
+    /// `0` doesn't allow any SVG images.
+    ///
+    /// Default: 8
+    pub nested_svg_depth_limit: u32,
+
+    /// Keeps elements with `display:none` instead of removing them.
+    ///
+    /// Such elements will be placed inside a `Group` with the `hidden` flag set,
+    /// which is not rendered. Useful for viewers that show them later.
+    ///
+    /// Hidden `clipPath` children and text spans are still removed.
+    ///
+    /// Default: false
+    pub keep_hidden_nodes: bool,
+
+    /// Logs stops and attributes inherited by gradients via `xlink:href`.
+    ///
+    /// Each inherited value is logged with the `info` level, along with the ID
+    /// of the gradient it was taken from. Useful for debugging gradient templates.
+    ///
+    /// Default: false
+    pub log_gradient_inheritance: bool,
+
+    /// Limits shared with the parent document.
+    ///
+    /// Set for nested SVG images loaded by the default `ImageHrefResolver`.
+    /// A custom resolver that loads SVG images via `Tree::from_data` should copy it
+    /// from the `Options` it was called with, so nested images will be counted
+    /// against the parent document limits.
+    /// When not set, the document has its own limits.
+    ///
+    /// Default: `None`
+    pub shared_limits: Option<Arc<SharedLimits>>,
 }
 
 impl Default for Options {
@@ -94,6 +149,64 @@ impl Default for Options {
             image_rendering: ImageRendering::default(),
             default_size: Size::new(100.0, 100.0).unwrap(),
             image_href_resolver: ImageHrefResolver::default(),
+            elements_limit: 1_000_000,
+            nested_svg_depth_limit: 8,
+            keep_hidden_nodes: false,
+            log_gradient_inheritance: false,
+            shared_limits: None,
+        }
+    }
+}
+
+/// Parsing limits state shared by a document and all SVG images nested in it.
+///
+/// See `Options::shared_limits`.
+#[derive(Debug)]
+pub struct SharedLimits {
+    elements_left: AtomicUsize,
+    elements_limit_reached: AtomicBool,
+    depth_limit_reached: AtomicBool,
+}
+
+impl SharedLimits {
+    pub(crate) fn new(elements_limit: usize) -> Self {
+        SharedLimits {
+            elements_left: AtomicUsize::new(elements_limit),
+            elements_limit_reached: AtomicBool::new(false),
+            depth_limit_reached: AtomicBool::new(false),
+        }
+    }
+
+    /// Counts converted elements.
+    ///
+    /// Returns `false` when the limit is reached.
+    pub(crate) fn take_elements(&self, count: usize) -> bool {
+        let res = self
+            .elements_left
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+                left.checked_sub(count)
+            });
+
+        if res.is_err() {
+            self.elements_left.store(0, Ordering::Relaxed);
+            self.elements_limit_reached.store(true, Ordering::Relaxed);
+        }
+
+        !self.elements_limit_reached.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_depth_limit_reached(&self) {
+        self.depth_limit_reached.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns an error for the first reached limit.
+    pub(crate) fn error(&self) -> Option<Error> {
+        if self.elements_limit_reached.load(Ordering::Relaxed) {
+            Some(Error::ElementsLimitReached)
+        } else if self.depth_limit_reached.load(Ordering::Relaxed) {
+            Some(Error::NestedSvgDepthLimitReached)
+        } else {
+            None
         }
     }
 }
@@ -47,7 +47,12 @@ pub(crate) fn convert(
 
 #[inline(never)]
 fn convert_linear(node: rosvgtree::Node, state: &converter::State) -> Option<ServerOrColor> {
-    let stops = convert_stops(find_gradient_with_stops(node)?);
+    let stops_node = find_gradient_with_stops(node)?;
+    if state.opt.log_gradient_inheritance {
+        log_gradient_inheritance(node, stops_node, &[AId::X1, AId::Y1, AId::X2, AId::Y2]);
+    }
+
+    let stops = convert_stops(stops_node);
     if stops.len() < 2 {
         return stops_to_color(&stops);
     }
@@ -73,6 +78,7 @@ fn convert_linear(node: rosvgtree::Node, state: &converter::State) -> Option<Ser
             units,
             transform,
             spread_method: convert_spread_method(node),
+            color_interpolation: convert_color_interpolation(node),
             stops,
         },
     };
@@ -84,7 +90,16 @@ fn convert_linear(node: rosvgtree::Node, state: &converter::State) -> Option<Ser
 
 #[inline(never)]
 fn convert_radial(node: rosvgtree::Node, state: &converter::State) -> Option<ServerOrColor> {
-    let stops = convert_stops(find_gradient_with_stops(node)?);
+    let stops_node = find_gradient_with_stops(node)?;
+    if state.opt.log_gradient_inheritance {
+        log_gradient_inheritance(
+            node,
+            stops_node,
+            &[AId::Cx, AId::Cy, AId::R, AId::Fx, AId::Fy],
+        );
+    }
+
+    let stops = convert_stops(stops_node);
     if stops.len() < 2 {
         return stops_to_color(&stops);
     }
@@ -136,6 +151,7 @@ fn convert_radial(node: rosvgtree::Node, state: &converter::State) -> Option<Ser
             units,
             transform,
             spread_method,
+            color_interpolation: convert_color_interpolation(node),
             stops,
         },
     };
@@ -191,6 +207,7 @@ fn convert_pattern(
         transform,
         rect,
         view_box,
+        overflow: node.parse_attribute(AId::Overflow).unwrap_or_default(),
         root: Node::new(NodeKind::Group(Group::default())),
     };
 
@@ -208,6 +225,12 @@ fn convert_spread_method(node: rosvgtree::Node) -> SpreadMethod {
     node.parse_attribute(AId::SpreadMethod).unwrap_or_default()
 }
 
+fn convert_color_interpolation(node: rosvgtree::Node) -> ColorInterpolation {
+    // Unlike `filter`, gradients are interpolated in sRGB by default.
+    node.find_and_parse_attribute(AId::ColorInterpolation)
+        .unwrap_or(ColorInterpolation::SRGB)
+}
+
 pub(crate) fn convert_units(node: rosvgtree::Node, name: AId, def: Units) -> Units {
     let node = resolve_attr(node, name);
     node.parse_attribute(name).unwrap_or(def)
@@ -234,6 +257,37 @@ fn find_gradient_with_stops<'a, 'input: 'a>(
     None
 }
 
+/// Logs stops and attributes a gradient inherits via `xlink:href`.
+///
+/// `attrs` are gradient-specific attributes, in addition to the common ones.
+fn log_gradient_inheritance(node: rosvgtree::Node, stops_node: rosvgtree::Node, attrs: &[AId]) {
+    let id = node.element_id();
+    if stops_node != node {
+        log::info!(
+            "Gradient '{}' inherits stops from '{}'.",
+            id,
+            stops_node.element_id()
+        );
+    }
+
+    let common = [
+        AId::GradientUnits,
+        AId::GradientTransform,
+        AId::SpreadMethod,
+    ];
+    for aid in attrs.iter().chain(common.iter()) {
+        let link = resolve_attr(node, *aid);
+        if link != node {
+            log::info!(
+                "Gradient '{}' inherits '{}' from '{}'.",
+                id,
+                aid,
+                link.element_id()
+            );
+        }
+    }
+}
+
 fn find_pattern_with_children<'a, 'input: 'a>(
     node: rosvgtree::Node<'a, 'input>,
 ) -> Option<rosvgtree::Node<'a, 'input>> {
@@ -193,51 +193,49 @@ impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::ShapeRendering {
     }
 }
 
-impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::TextRendering {
+impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::Overflow {
     fn parse(_: Node, _: AttributeId, value: &str) -> Option<Self> {
         match value {
-            "optimizeSpeed" => Some(usvg_tree::TextRendering::OptimizeSpeed),
-            "optimizeLegibility" => Some(usvg_tree::TextRendering::OptimizeLegibility),
-            "geometricPrecision" => Some(usvg_tree::TextRendering::GeometricPrecision),
+            "visible" | "auto" => Some(usvg_tree::Overflow::Visible),
+            "hidden" | "scroll" => Some(usvg_tree::Overflow::Hidden),
             _ => None,
         }
     }
 }
 
-impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::ImageRendering {
+impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::VectorEffect {
     fn parse(_: Node, _: AttributeId, value: &str) -> Option<Self> {
         match value {
-            "optimizeQuality" => Some(usvg_tree::ImageRendering::OptimizeQuality),
-            "optimizeSpeed" => Some(usvg_tree::ImageRendering::OptimizeSpeed),
+            "none" => Some(usvg_tree::VectorEffect::None),
+            "non-scaling-stroke" => Some(usvg_tree::VectorEffect::NonScalingStroke),
             _ => None,
         }
     }
 }
 
-impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::BlendMode {
+impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::TextRendering {
     fn parse(_: Node, _: AttributeId, value: &str) -> Option<Self> {
         match value {
-            "normal" => Some(usvg_tree::BlendMode::Normal),
-            "multiply" => Some(usvg_tree::BlendMode::Multiply),
-            "screen" => Some(usvg_tree::BlendMode::Screen),
-            "overlay" => Some(usvg_tree::BlendMode::Overlay),
-            "darken" => Some(usvg_tree::BlendMode::Darken),
-            "lighten" => Some(usvg_tree::BlendMode::Lighten),
-            "color-dodge" => Some(usvg_tree::BlendMode::ColorDodge),
-            "color-burn" => Some(usvg_tree::BlendMode::ColorBurn),
-            "hard-light" => Some(usvg_tree::BlendMode::HardLight),
-            "soft-light" => Some(usvg_tree::BlendMode::SoftLight),
-            "difference" => Some(usvg_tree::BlendMode::Difference),
-            "exclusion" => Some(usvg_tree::BlendMode::Exclusion),
-            "hue" => Some(usvg_tree::BlendMode::Hue),
-            "saturation" => Some(usvg_tree::BlendMode::Saturation),
-            "color" => Some(usvg_tree::BlendMode::Color),
-            "luminosity" => Some(usvg_tree::BlendMode::Luminosity),
+            "optimizeSpeed" => Some(usvg_tree::TextRendering::OptimizeSpeed),
+            "optimizeLegibility" => Some(usvg_tree::TextRendering::OptimizeLegibility),
+            "geometricPrecision" => Some(usvg_tree::TextRendering::GeometricPrecision),
             _ => None,
         }
     }
 }
 
+impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::ImageRendering {
+    fn parse(_: Node, _: AttributeId, value: &str) -> Option<Self> {
+        value.parse().ok()
+    }
+}
+
+impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::BlendMode {
+    fn parse(_: Node, _: AttributeId, value: &str) -> Option<Self> {
+        value.parse().ok()
+    }
+}
+
 impl<'a, 'input: 'a> FromValue<'a, 'input> for Node<'a, 'input> {
     fn parse(node: Node<'a, 'input>, aid: AttributeId, value: &str) -> Option<Self> {
         let id = if aid == AttributeId::Href {
@@ -280,6 +278,7 @@ pub trait SvgNodeExt {
         def: svgtypes::Length,
     ) -> f64;
     fn is_visible_element(&self, opt: &crate::Options) -> bool;
+    fn is_valid_element(&self, opt: &crate::Options) -> bool;
 }
 
 impl SvgNodeExt for Node<'_, '_> {
@@ -378,8 +377,11 @@ impl SvgNodeExt for Node<'_, '_> {
     }
 
     fn is_visible_element(&self, opt: &crate::Options) -> bool {
-        self.attribute(AttributeId::Display) != Some("none")
-            && self.has_valid_transform(AttributeId::Transform)
+        self.attribute(AttributeId::Display) != Some("none") && self.is_valid_element(opt)
+    }
+
+    fn is_valid_element(&self, opt: &crate::Options) -> bool {
+        self.has_valid_transform(AttributeId::Transform)
             && crate::switch::is_condition_passed(*self, opt)
     }
 }
@@ -6,7 +6,7 @@ use std::rc::Rc;
 
 use rosvgtree::{self, AttributeId as AId, ElementId as EId};
 use svgtypes::Length;
-use usvg_tree::{FuzzyEq, IsValidLength, PathData, Rect, Units};
+use usvg_tree::{IsValidLength, PathData, Rect, Units};
 
 use crate::rosvgtree_ext::SvgNodeExt2;
 use crate::{converter, units, SvgNodeExt};
@@ -26,39 +26,8 @@ pub(crate) fn convert(node: rosvgtree::Node, state: &converter::State) -> Option
 
 pub(crate) fn convert_path(node: rosvgtree::Node) -> Option<Rc<PathData>> {
     let value: &str = node.attribute(AId::D)?;
-    let mut path = PathData::new();
-    for segment in svgtypes::SimplifyingPathParser::from(value) {
-        let segment = match segment {
-            Ok(v) => v,
-            Err(_) => break,
-        };
-
-        match segment {
-            svgtypes::SimplePathSegment::MoveTo { x, y } => {
-                path.push_move_to(x, y);
-            }
-            svgtypes::SimplePathSegment::LineTo { x, y } => {
-                path.push_line_to(x, y);
-            }
-            svgtypes::SimplePathSegment::CurveTo {
-                x1,
-                y1,
-                x2,
-                y2,
-                x,
-                y,
-            } => {
-                path.push_curve_to(x1, y1, x2, y2, x, y);
-            }
-            svgtypes::SimplePathSegment::Quadratic { x1, y1, x, y } => {
-                path.push_quad_to(x1, y1, x, y);
-            }
-            svgtypes::SimplePathSegment::ClosePath => {
-                path.push_close_path();
-            }
-        }
-    }
-
+    // Segments before an error are still rendered.
+    let (path, _) = PathData::from_svg_string_partial(value);
     if path.len() >= 2 {
         Some(Rc::new(path))
     } else {
@@ -88,41 +57,13 @@ fn convert_rect(node: rosvgtree::Node, state: &converter::State) -> Option<Rc<Pa
     let x = node.convert_user_length(AId::X, state, Length::zero());
     let y = node.convert_user_length(AId::Y, state, Length::zero());
 
-    let (mut rx, mut ry) = resolve_rx_ry(node, state);
+    let (rx, ry) = resolve_rx_ry(node, state);
 
-    // Clamp rx/ry to the half of the width/height.
+    // Conversion according to https://www.w3.org/TR/SVG11/shapes.html#RectElement
     //
+    // `rx`/`ry` will be clamped to the half of the width/height.
     // Should be done only after resolving.
-    if rx > width / 2.0 {
-        rx = width / 2.0;
-    }
-    if ry > height / 2.0 {
-        ry = height / 2.0;
-    }
-
-    // Conversion according to https://www.w3.org/TR/SVG11/shapes.html#RectElement
-    let path = if rx.fuzzy_eq(&0.0) {
-        PathData::from_rect(Rect::new(x, y, width, height)?)
-    } else {
-        let mut p = PathData::new();
-        p.push_move_to(x + rx, y);
-
-        p.push_line_to(x + width - rx, y);
-        p.push_arc_to(rx, ry, 0.0, false, true, x + width, y + ry);
-
-        p.push_line_to(x + width, y + height - ry);
-        p.push_arc_to(rx, ry, 0.0, false, true, x + width - rx, y + height);
-
-        p.push_line_to(x + rx, y + height);
-        p.push_arc_to(rx, ry, 0.0, false, true, x, y + height - ry);
-
-        p.push_line_to(x, y + ry);
-        p.push_arc_to(rx, ry, 0.0, false, true, x + rx, y);
-
-        p.push_close_path();
-
-        p
-    };
+    let path = PathData::from_rounded_rect(Rect::new(x, y, width, height)?, rx, ry);
 
     Some(Rc::new(path))
 }
@@ -237,7 +178,7 @@ fn convert_circle(node: rosvgtree::Node, state: &converter::State) -> Option<Rc<
         return None;
     }
 
-    Some(Rc::new(ellipse_to_path(cx, cy, r, r)))
+    Some(Rc::new(PathData::from_circle(cx, cy, r)))
 }
 
 fn convert_ellipse(node: rosvgtree::Node, state: &converter::State) -> Option<Rc<PathData>> {
@@ -261,16 +202,5 @@ fn convert_ellipse(node: rosvgtree::Node, state: &converter::State) -> Option<Rc
         return None;
     }
 
-    Some(Rc::new(ellipse_to_path(cx, cy, rx, ry)))
-}
-
-fn ellipse_to_path(cx: f64, cy: f64, rx: f64, ry: f64) -> PathData {
-    let mut p = PathData::new();
-    p.push_move_to(cx + rx, cy);
-    p.push_arc_to(rx, ry, 0.0, false, true, cx, cy + ry);
-    p.push_arc_to(rx, ry, 0.0, false, true, cx - rx, cy);
-    p.push_arc_to(rx, ry, 0.0, false, true, cx, cy - ry);
-    p.push_arc_to(rx, ry, 0.0, false, true, cx + rx, cy);
-    p.push_close_path();
-    p
+    Some(Rc::new(PathData::from_ellipse(cx, cy, rx, ry)))
 }
@@ -2,6 +2,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::str::FromStr;
+
 use rosvgtree::{self, AttributeId as AId};
 use usvg_tree::{Color, Fill, FuzzyEq, Opacity, Paint, Stroke, StrokeMiterlimit, Units};
 
@@ -102,14 +104,15 @@ pub(crate) fn resolve_stroke(
     let miterlimit = node
         .find_and_parse_attribute(AId::StrokeMiterlimit)
         .unwrap_or(4.0);
-    let miterlimit = if miterlimit < 1.0 { 1.0 } else { miterlimit };
-    let miterlimit = StrokeMiterlimit::new(miterlimit);
+    let miterlimit = StrokeMiterlimit::clamp_min_1(miterlimit);
 
     let stroke_opacity = node
         .parse_attribute::<OpacityWrapper>(AId::StrokeOpacity)
         .map(|v| v.0)
         .unwrap_or(Opacity::ONE);
 
+    // Percentage `stroke-dasharray` and `stroke-dashoffset` values are resolved
+    // against the current viewport diagonal, normalized by `sqrt(2)`.
     let stroke = Stroke {
         paint,
         dasharray: conv_dasharray(node, state),
@@ -164,6 +167,8 @@ fn convert_paint(
             Some(Paint::Color(color))
         }
         svgtypes::Paint::Color(svg_color) => {
+            // `svgtypes` resolves hex, keyword, `rgb[a]()` and `hsl[a]()` colors.
+            // The alpha channel becomes a part of the fill/stroke opacity.
             let (color, alpha) = svg_color.split_alpha();
             *opacity = alpha;
             Some(Paint::Color(color))
@@ -230,10 +235,37 @@ fn conv_dasharray(node: rosvgtree::Node, state: &converter::State) -> Option<Vec
     let node = node
         .ancestors()
         .find(|n| n.has_attribute(AId::StrokeDasharray))?;
-    let list = super::units::convert_list(node, AId::StrokeDasharray, state)?;
+    let text: &str = node.attribute(AId::StrokeDasharray)?;
+    if text.trim() == "none" {
+        return None;
+    }
+
+    // Each value is resolved separately, so a single invalid value
+    // will not discard the whole list.
+    // Percentage values are resolved against the viewport diagonal.
+    let mut list = Vec::new();
+    for value in text
+        .split(|c: char| c == ',' || c.is_ascii_whitespace())
+        .filter(|s| !s.is_empty())
+    {
+        match svgtypes::Length::from_str(value) {
+            Ok(length) => list.push(super::units::convert_length(
+                length,
+                node,
+                AId::StrokeDasharray,
+                Units::UserSpaceOnUse,
+                state,
+            )),
+            Err(_) => log::warn!("Invalid stroke-dasharray value: '{}'. Skipped.", value),
+        }
+    }
 
     // `A negative value is an error`
-    if list.iter().any(|n| n.is_sign_negative()) {
+    if let Some(n) = list.iter().find(|n| n.is_sign_negative()) {
+        log::warn!(
+            "Negative stroke-dasharray value: {}. Dashing is disabled.",
+            n
+        );
         return None;
     }
 
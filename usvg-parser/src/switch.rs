@@ -107,7 +107,9 @@ fn is_valid_sys_lang(node: rosvgtree::Node, opt: &Options) -> bool {
 
             // 'Evaluates to `true` if one of the languages indicated by user preferences exactly
             // equals one of the languages given in the value of this parameter.'
-            if opt.languages.iter().any(|v| v == lang) {
+            //
+            // Language tags are case-insensitive.
+            if opt.languages.iter().any(|v| v.eq_ignore_ascii_case(lang)) {
                 has_match = true;
                 break;
             }
@@ -117,7 +119,11 @@ fn is_valid_sys_lang(node: rosvgtree::Node, opt: &Options) -> bool {
             // the first tag character following the prefix is `-`.'
             if let Some(idx) = lang.bytes().position(|c| c == b'-') {
                 let lang_prefix = &lang[..idx];
-                if opt.languages.iter().any(|v| v == lang_prefix) {
+                if opt
+                    .languages
+                    .iter()
+                    .any(|v| v.eq_ignore_ascii_case(lang_prefix))
+                {
                     has_match = true;
                     break;
                 }
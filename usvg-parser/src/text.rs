@@ -73,6 +73,16 @@ impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::LengthAdjust {
     }
 }
 
+impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::TextDirection {
+    fn parse(_: rosvgtree::Node, _: rosvgtree::AttributeId, value: &str) -> Option<Self> {
+        match value {
+            "ltr" => Some(usvg_tree::TextDirection::LeftToRight),
+            "rtl" => Some(usvg_tree::TextDirection::RightToLeft),
+            _ => None,
+        }
+    }
+}
+
 impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::FontStyle {
     fn parse(_: rosvgtree::Node, _: rosvgtree::AttributeId, value: &str) -> Option<Self> {
         match value {
@@ -107,6 +117,13 @@ pub(crate) fn convert(
         positions: pos_list,
         rotate: rotate_list,
         writing_mode,
+        direction: text_node
+            .find_and_parse_attribute(AId::Direction)
+            .unwrap_or_default(),
+        bidi_override: matches!(
+            text_node.attribute(AId::UnicodeBidi),
+            Some("bidi-override") | Some("isolate-override")
+        ),
         chunks,
     };
     parent.append_kind(NodeKind::Text(text));
@@ -263,7 +280,10 @@ fn collect_text_chunks_impl(
             small_caps: parent
                 .find_attribute(AId::FontVariant)
                 .and_then(|n| n.attribute(AId::FontVariant))
-                == Some("small-caps"),
+                .unwrap_or("")
+                .split_whitespace()
+                .any(|s| s == "small-caps"),
+            font_features: convert_font_features(parent),
             apply_kerning,
             decoration: resolve_decoration(text_node, parent, state, cache),
             visibility: parent
@@ -355,6 +375,14 @@ fn resolve_text_flow(node: rosvgtree::Node, state: &converter::State) -> Option<
             path
         };
 
+    // `side=right` places the text on the other side of the path,
+    // which is the same as using a reversed path.
+    let path = if node.attribute(AId::Side) == Some("right") {
+        Rc::new(path.reverse())
+    } else {
+        path
+    };
+
     let start_offset: Length = node.parse_attribute(AId::StartOffset).unwrap_or_default();
     let start_offset = if start_offset.unit == LengthUnit::Percent {
         // 'If a percentage is given, then the `startOffset` represents
@@ -412,6 +440,93 @@ fn convert_font(node: rosvgtree::Node, state: &converter::State) -> Font {
     }
 }
 
+fn convert_font_features(node: rosvgtree::Node) -> Vec<FontFeature> {
+    fn feature(tag: &[u8; 4], value: u32) -> FontFeature {
+        FontFeature { tag: *tag, value }
+    }
+
+    let mut features = Vec::new();
+
+    if let Some(variant) = node
+        .find_attribute(AId::FontVariant)
+        .and_then(|n| n.attribute(AId::FontVariant))
+    {
+        for keyword in variant.split_whitespace() {
+            match keyword {
+                // Disables all ligatures and contextual forms.
+                "none" => {
+                    for tag in [b"liga", b"clig", b"dlig", b"hlig", b"calt"] {
+                        features.push(feature(tag, 0));
+                    }
+                }
+                "no-common-ligatures" => {
+                    features.push(feature(b"liga", 0));
+                    features.push(feature(b"clig", 0));
+                }
+                "common-ligatures" => {
+                    features.push(feature(b"liga", 1));
+                    features.push(feature(b"clig", 1));
+                }
+                "no-discretionary-ligatures" => features.push(feature(b"dlig", 0)),
+                "discretionary-ligatures" => features.push(feature(b"dlig", 1)),
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(settings) = node
+        .find_attribute(AId::FontFeatureSettings)
+        .and_then(|n| n.attribute(AId::FontFeatureSettings))
+    {
+        match parse_font_feature_settings(settings) {
+            Some(list) => features.extend(list),
+            None => log::warn!("Invalid font-feature-settings value: '{}'.", settings),
+        }
+    }
+
+    features
+}
+
+/// Parses a `font-feature-settings` value.
+///
+/// Returns `None` when at least one entry is invalid, since the whole declaration
+/// should be ignored in this case.
+fn parse_font_feature_settings(text: &str) -> Option<Vec<FontFeature>> {
+    let text = text.trim();
+    if text == "normal" {
+        return Some(Vec::new());
+    }
+
+    let mut features = Vec::new();
+    for entry in text.split(',') {
+        let entry = entry.trim();
+        let quote = entry.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+
+        let entry = &entry[1..];
+        let end = entry.find(quote)?;
+        let tag = &entry.as_bytes()[..end];
+        if tag.len() != 4 || !tag.iter().all(|c| (0x20..=0x7E).contains(c)) {
+            return None;
+        }
+
+        let value = match entry[end + 1..].trim() {
+            "" | "on" => 1,
+            "off" => 0,
+            n => n.parse().ok()?,
+        };
+
+        features.push(FontFeature {
+            tag: [tag[0], tag[1], tag[2], tag[3]],
+            value,
+        });
+    }
+
+    Some(features)
+}
+
 // TODO: properly resolve narrower/wider
 fn conv_font_stretch(node: rosvgtree::Node) -> FontStretch {
     if let Some(n) = node.ancestors().find(|n| n.has_attribute(AId::FontStretch)) {
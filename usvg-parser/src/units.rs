@@ -4,7 +4,7 @@
 
 use rosvgtree::{self, AttributeId as AId};
 use svgtypes::{Length, LengthUnit as Unit};
-use usvg_tree::Units;
+use usvg_tree::{Rect, Units};
 
 use crate::converter;
 use crate::rosvgtree_ext::SvgNodeExt2;
@@ -29,43 +29,51 @@ pub(crate) fn convert_length(
         Unit::Pt => n * dpi / 72.0,
         Unit::Pc => n * dpi / 6.0,
         Unit::Percent => {
-            if object_units == Units::ObjectBoundingBox {
-                length.number / 100.0
-            } else {
-                let view_box = state.view_box;
+            let view_box = units_viewport(object_units, state);
 
-                match aid {
-                    AId::Cx
-                    | AId::Dx
-                    | AId::Fx
-                    | AId::MarkerWidth
-                    | AId::RefX
-                    | AId::Rx
-                    | AId::Width
-                    | AId::X
-                    | AId::X1
-                    | AId::X2 => convert_percent(length, view_box.width()),
-                    AId::Cy
-                    | AId::Dy
-                    | AId::Fy
-                    | AId::Height
-                    | AId::MarkerHeight
-                    | AId::RefY
-                    | AId::Ry
-                    | AId::Y
-                    | AId::Y1
-                    | AId::Y2 => convert_percent(length, view_box.height()),
-                    _ => {
-                        let mut vb_len = view_box.width().powi(2) + view_box.height().powi(2);
-                        vb_len = (vb_len / 2.0).sqrt();
-                        convert_percent(length, vb_len)
-                    }
+            match aid {
+                AId::Cx
+                | AId::Dx
+                | AId::Fx
+                | AId::MarkerWidth
+                | AId::RefX
+                | AId::Rx
+                | AId::Width
+                | AId::X
+                | AId::X1
+                | AId::X2 => convert_percent(length, view_box.width()),
+                AId::Cy
+                | AId::Dy
+                | AId::Fy
+                | AId::Height
+                | AId::MarkerHeight
+                | AId::RefY
+                | AId::Ry
+                | AId::Y
+                | AId::Y1
+                | AId::Y2 => convert_percent(length, view_box.height()),
+                _ => {
+                    let mut vb_len = view_box.width().powi(2) + view_box.height().powi(2);
+                    vb_len = (vb_len / 2.0).sqrt();
+                    convert_percent(length, vb_len)
                 }
             }
         }
     }
 }
 
+/// Returns the viewport that percentage lengths in `units` are relative to.
+///
+/// `objectBoundingBox` lengths are fractions of the object bounding box,
+/// which are mapped to the user space later via `Units::bbox_transform`.
+/// Therefore, the viewport is a unit square in this case.
+pub(crate) fn units_viewport(units: Units, state: &converter::State) -> Rect {
+    match units {
+        Units::UserSpaceOnUse => state.view_box,
+        Units::ObjectBoundingBox => Rect::new(0.0, 0.0, 1.0, 1.0).unwrap(),
+    }
+}
+
 #[inline(never)]
 pub(crate) fn convert_list(
     node: rosvgtree::Node,
@@ -7,7 +7,8 @@ use std::rc::Rc;
 use rosvgtree::{self, AttributeId as AId, ElementId as EId};
 use svgtypes::{Length, LengthUnit};
 use usvg_tree::{
-    FuzzyEq, Group, IsValidLength, Node, NodeExt, NodeKind, Path, PathData, Rect, Size, Transform,
+    FuzzyEq, Group, IsValidLength, Node, NodeExt, NodeKind, Overflow, Path, PathData, Rect, Size,
+    Transform,
 };
 
 use crate::rosvgtree_ext::SvgNodeExt2;
@@ -40,6 +41,14 @@ pub(crate) fn convert(
 
     let linked_to_symbol = child.tag_name() == Some(EId::Symbol);
 
+    // A `symbol` establishes a new viewport, so percentage units inside of it
+    // should be resolved against it and not against the `use` element's viewport.
+    let symbol_state = if linked_to_symbol {
+        Some(symbol_state(node, child, state))
+    } else {
+        None
+    };
+
     if linked_to_symbol {
         if let Some(ts) = viewbox_transform(node, child, state) {
             new_ts.append(&ts);
@@ -63,6 +72,7 @@ pub(crate) fn convert(
                 converter::GroupKind::Ignore => return None,
             };
 
+            let state = symbol_state.as_ref().unwrap_or(state);
             convert_children(child, new_ts, state, cache, &mut parent);
             return None;
         }
@@ -78,6 +88,7 @@ pub(crate) fn convert(
             converter::GroupKind::Ignore => return None,
         };
 
+        let state = symbol_state.as_ref().unwrap_or(state);
         convert_children(child, orig_ts, state, cache, &mut parent);
     } else {
         let linked_to_svg = child.tag_name() == Some(EId::Svg);
@@ -170,6 +181,26 @@ pub(crate) fn convert_svg(
     }
 }
 
+fn symbol_state<'a>(
+    use_node: rosvgtree::Node,
+    symbol_node: rosvgtree::Node,
+    state: &converter::State<'a>,
+) -> converter::State<'a> {
+    let mut new_state = state.clone();
+    new_state.view_box = {
+        if let Some(vb) = symbol_node.parse_viewbox() {
+            vb
+        } else {
+            // No `viewBox` attribute? Then the viewport is defined by
+            // the `use` element `width` and `height`.
+            let (w, h) = use_node_size(use_node, state);
+            Rect::new(0.0, 0.0, w, h).unwrap_or(state.view_box)
+        }
+    };
+
+    new_state
+}
+
 fn clip_element(
     node: rosvgtree::Node,
     clip_rect: Rect,
@@ -247,10 +278,7 @@ fn get_clip_rect(
     state: &converter::State,
 ) -> Option<Rect> {
     // No need to clip elements with overflow:visible.
-    if matches!(
-        symbol_node.attribute(AId::Overflow),
-        Some("visible") | Some("auto")
-    ) {
+    if symbol_node.parse_attribute(AId::Overflow) == Some(Overflow::Visible) {
         return None;
     }
 
@@ -0,0 +1,57 @@
+// Installs a global logger, therefore must be in its own test binary.
+
+use usvg_parser::TreeParsing;
+
+#[test]
+fn log_gradient_inheritance() {
+    use std::sync::Mutex;
+
+    static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    struct Logger;
+    impl log::Log for Logger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            let msg = record.args().to_string();
+            if msg.contains(" inherits ") {
+                MESSAGES.lock().unwrap().push(msg);
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <linearGradient id='lg1' x1='0.5' gradientUnits='userSpaceOnUse'>
+            <stop offset='0' stop-color='red'/>
+            <stop offset='1' stop-color='green'/>
+        </linearGradient>
+        <linearGradient id='lg2' xlink:href='#lg1' x1='0.2'/>
+        <rect width='10' height='10' fill='url(#lg2)'/>
+    </svg>
+    ";
+
+    log::set_logger(&Logger).unwrap();
+    log::set_max_level(log::LevelFilter::Info);
+
+    // Disabled by default.
+    usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    assert!(MESSAGES.lock().unwrap().is_empty());
+
+    let opt = usvg_parser::Options {
+        log_gradient_inheritance: true,
+        ..usvg_parser::Options::default()
+    };
+    usvg_tree::Tree::from_str(svg, &opt).unwrap();
+    assert_eq!(
+        *MESSAGES.lock().unwrap(),
+        vec![
+            "Gradient 'lg2' inherits stops from 'lg1'.",
+            "Gradient 'lg2' inherits 'gradientUnits' from 'lg1'.",
+        ]
+    );
+}
@@ -1,5 +1,5 @@
 use usvg_parser::TreeParsing;
-use usvg_tree::FuzzyEq;
+use usvg_tree::{FuzzyEq, NodeExt};
 
 #[test]
 fn clippath_with_invalid_child() {
@@ -97,3 +97,1864 @@ fn invalid_size_1() {
     let result = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default());
     assert!(result.is_err());
 }
+
+#[test]
+fn node_by_id_index() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 1 1'>
+        <g id='g1' opacity='0.5'>
+            <rect id='rect1' width='10' height='10'/>
+        </g>
+        <rect id='rect1' width='20' height='20'/>
+    </svg>
+    ";
+
+    let mut tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let rect1 = tree.node_by_id("rect1").unwrap();
+    assert!(tree.node_by_id("g1").is_some());
+    assert!(rect1.parent().unwrap() == tree.node_by_id("g1").unwrap());
+    assert!(tree.node_by_id("").is_none());
+    assert!(tree.node_by_id("rect2").is_none());
+
+    // A removed node is not returned.
+    rect1.detach();
+    let rect1 = tree.node_by_id("rect1").unwrap();
+    assert!(rect1.parent().unwrap() == tree.root);
+
+    let set_id = |node: &usvg_tree::Node, id: &str| {
+        if let usvg_tree::NodeKind::Path(ref mut path) = *node.borrow_mut() {
+            path.id = id.to_string();
+        }
+    };
+
+    // A changed ID is detected.
+    set_id(&rect1, "rect2");
+    assert!(tree.node_by_id("rect1").is_none());
+    assert!(tree.node_by_id("rect2").unwrap() == rect1);
+
+    // New IDs require invalidation.
+    set_id(&rect1, "rect3");
+    assert!(tree.node_by_id("rect3").is_none());
+    tree.invalidate_id_index();
+    assert!(tree.node_by_id("rect3").unwrap() == rect1);
+}
+
+#[test]
+fn mask_type() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 1 1'>
+        <mask id='mask1' mask-type='alpha'>
+            <rect width='10' height='10'/>
+        </mask>
+        <mask id='mask2' style='mask-type:alpha'>
+            <rect width='10' height='10'/>
+        </mask>
+        <mask id='mask3'>
+            <rect width='10' height='10'/>
+        </mask>
+        <rect mask='url(#mask1)' width='10' height='10'/>
+        <rect mask='url(#mask2)' width='10' height='10'/>
+        <rect mask='url(#mask3)' width='10' height='10'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let kinds: Vec<_> = tree
+        .root
+        .children()
+        .map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Group(ref g) => g.mask.as_ref().unwrap().kind,
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            usvg_tree::MaskType::Alpha,
+            usvg_tree::MaskType::Alpha,
+            usvg_tree::MaskType::Luminance,
+        ]
+    );
+}
+
+#[test]
+fn stroke_dasharray() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' width='200' height='200' viewBox='0 0 200 100'>
+        <path d='M 0 0 L 10 0' stroke='black' stroke-dasharray='5 10% 0.5in'/>
+        <path d='M 0 0 L 10 0' stroke='black' stroke-dasharray='5, invalid, 10%'/>
+        <path d='M 0 0 L 10 0' stroke='black' stroke-dasharray='5 -10%'/>
+        <path d='M 0 0 L 10 0' stroke='black' stroke-dasharray='none'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let lists: Vec<_> = tree
+        .root
+        .children()
+        .map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Path(ref path) => path.stroke.as_ref().unwrap().dasharray.clone(),
+            _ => unreachable!(),
+        })
+        .collect();
+
+    // Percentages are relative to the normalized viewport diagonal.
+    let percent = ((200.0f64 * 200.0 + 100.0 * 100.0) / 2.0).sqrt() / 10.0;
+    let check = |list: &Option<Vec<f64>>, expected: &[f64]| {
+        let list = list.as_ref().unwrap();
+        assert_eq!(list.len(), expected.len());
+        for (a, b) in list.iter().zip(expected) {
+            assert!((a - b).abs() < 0.0001, "{} != {}", a, b);
+        }
+    };
+
+    check(&lists[0], &[5.0, percent, 48.0, 5.0, percent, 48.0]);
+    check(&lists[1], &[5.0, percent]);
+    assert!(lists[2].is_none());
+    assert!(lists[3].is_none());
+}
+
+#[test]
+fn stroke_dash_percentages() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' width='300' height='400' viewBox='0 0 300 400'>
+        <path d='M 0 0 L 10 0' stroke='black' stroke-dasharray='5%,2%' stroke-dashoffset='10%'/>
+        <svg viewBox='0 0 30 40'>
+            <path d='M 0 0 L 10 0' stroke='black' stroke-dasharray='5%,2%' stroke-dashoffset='10%'/>
+        </svg>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let strokes: Vec<_> = tree
+        .root
+        .descendants()
+        .filter_map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Path(ref path) => path.stroke.clone(),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(strokes.len(), 2);
+
+    // sqrt(w^2 + h^2) / sqrt(2)
+    let check = |stroke: &usvg_tree::Stroke, w: f64, h: f64| {
+        let diag = ((w * w + h * h) / 2.0).sqrt();
+        let list = stroke.dasharray.as_ref().unwrap();
+        assert_eq!(list.len(), 2);
+        assert!((list[0] - diag * 0.05).abs() < 0.0001);
+        assert!((list[1] - diag * 0.02).abs() < 0.0001);
+        assert!((stroke.dashoffset as f64 - diag * 0.1).abs() < 0.001);
+    };
+
+    check(&strokes[0], 300.0, 400.0);
+    // Nested `svg` establishes a new viewport.
+    check(&strokes[1], 30.0, 40.0);
+}
+
+#[test]
+fn vector_effect() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' width='200' height='200'>
+        <path d='M 0 0 L 10 0' stroke='black' vector-effect='non-scaling-stroke'/>
+        <path d='M 0 0 L 10 0' stroke='black' vector-effect='invalid'/>
+        <g vector-effect='non-scaling-stroke'>
+            <path d='M 0 0 L 10 0' stroke='black'/>
+        </g>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let effects: Vec<_> = tree
+        .root
+        .descendants()
+        .filter_map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Path(ref path) => Some(path.vector_effect),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        effects,
+        &[
+            usvg_tree::VectorEffect::NonScalingStroke,
+            usvg_tree::VectorEffect::None,
+            // `vector-effect` is not inherited.
+            usvg_tree::VectorEffect::None,
+        ]
+    );
+}
+
+#[test]
+fn raw_paint_order() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' width='200' height='200'>
+        <path d='M 0 0 L 10 0' stroke='black' paint-order='markers stroke'/>
+        <path d='M 0 0 L 10 0' stroke='black'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let paths: Vec<_> = tree
+        .root
+        .descendants()
+        .filter_map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Path(ref path) => Some((path.paint_order, path.raw_paint_order)),
+            _ => None,
+        })
+        .collect();
+
+    use usvg_tree::PaintOrderKind;
+    assert_eq!(
+        paths,
+        &[
+            (
+                usvg_tree::PaintOrder::StrokeAndFill,
+                Some([
+                    PaintOrderKind::Markers,
+                    PaintOrderKind::Stroke,
+                    PaintOrderKind::Fill
+                ])
+            ),
+            (usvg_tree::PaintOrder::FillAndStroke, None),
+        ]
+    );
+}
+
+#[test]
+fn color_functions() {
+    fn fill(value: &str) -> (usvg_tree::Color, f64) {
+        let svg = format!(
+            "<svg xmlns='http://www.w3.org/2000/svg' width='200' height='200'>
+                <rect width='10' height='10' fill='{}' color='hsla(120, 100%, 50%, 0.5)'/>
+            </svg>",
+            value
+        );
+        let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+        let node = tree.root.first_child().unwrap();
+        let fill = match *node.borrow() {
+            usvg_tree::NodeKind::Path(ref path) => path.fill.clone().unwrap(),
+            _ => unreachable!(),
+        };
+        match fill.paint {
+            usvg_tree::Paint::Color(c) => (c, fill.opacity.get()),
+            _ => unreachable!(),
+        }
+    }
+
+    let rgb = usvg_tree::Color::new_rgb;
+    let check = |value: &str, color: usvg_tree::Color, opacity: f64| {
+        let (c, o) = fill(value);
+        assert_eq!(c, color, "{}", value);
+        assert!(
+            (o - opacity).abs() < 0.01,
+            "{}: {} != {}",
+            value,
+            o,
+            opacity
+        );
+    };
+
+    check("rgb(10, 20, 30)", rgb(10, 20, 30), 1.0);
+    check("rgb(100%, 50%, 0%)", rgb(255, 128, 0), 1.0);
+    check("rgba(10, 20, 30, 0.5)", rgb(10, 20, 30), 0.5);
+    check("rgba(100%, 0%, 0%, 0.25)", rgb(255, 0, 0), 0.25);
+    check("hsl(120, 100%, 50%)", rgb(0, 255, 0), 1.0);
+    check("hsl(0, 100%, 50%)", rgb(255, 0, 0), 1.0);
+    check("hsla(240, 100%, 50%, 0.5)", rgb(0, 0, 255), 0.5);
+    check("HSLA(0, 100%, 50%, 1)", rgb(255, 0, 0), 1.0);
+    check("currentColor", rgb(0, 255, 0), 0.5);
+}
+
+#[test]
+fn color_interpolation() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' width='200' height='200'>
+        <linearGradient id='lg1'>
+            <stop offset='0' stop-color='red'/>
+            <stop offset='1' stop-color='blue'/>
+        </linearGradient>
+        <linearGradient id='lg2' color-interpolation='linearRGB'>
+            <stop offset='0' stop-color='red'/>
+            <stop offset='1' stop-color='blue'/>
+        </linearGradient>
+        <filter id='filter1'>
+            <feFlood flood-color='green'/>
+        </filter>
+        <filter id='filter2' color-interpolation-filters='sRGB'>
+            <feFlood flood-color='green'/>
+        </filter>
+        <rect width='10' height='10' fill='url(#lg1)' filter='url(#filter1)'/>
+        <rect width='10' height='10' fill='url(#lg2)' filter='url(#filter2)'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let mut gradients = Vec::new();
+    let mut filters = Vec::new();
+    for node in tree.root.descendants() {
+        match *node.borrow() {
+            usvg_tree::NodeKind::Group(ref g) => {
+                filters.extend(g.filters.iter().map(|f| f.color_interpolation));
+            }
+            usvg_tree::NodeKind::Path(ref path) => {
+                if let Some(usvg_tree::Paint::LinearGradient(ref lg)) =
+                    path.fill.as_ref().map(|f| f.paint.clone())
+                {
+                    gradients.push(lg.color_interpolation);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    use usvg_tree::ColorInterpolation;
+    assert_eq!(
+        gradients,
+        &[ColorInterpolation::SRGB, ColorInterpolation::LinearRGB]
+    );
+    assert_eq!(
+        filters,
+        &[ColorInterpolation::LinearRGB, ColorInterpolation::SRGB]
+    );
+}
+
+#[test]
+fn paint_content_eq() {
+    fn fill_paint(svg: &str) -> usvg_tree::Paint {
+        let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+        let node = tree.root.first_child().unwrap();
+        let kind = node.borrow();
+        match *kind {
+            usvg_tree::NodeKind::Path(ref path) => path.fill.as_ref().unwrap().paint.clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    let gradient = |x2: &str| {
+        fill_paint(&format!(
+            "
+            <svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 100 100'>
+                <linearGradient id='lg1' x2='{}'>
+                    <stop offset='0' stop-color='red'/>
+                    <stop offset='1' stop-color='blue'/>
+                </linearGradient>
+                <rect fill='url(#lg1)' width='10' height='10'/>
+            </svg>
+            ",
+            x2
+        ))
+    };
+
+    let pattern = |rect_attrs: &str, overflow: &str| {
+        fill_paint(&format!(
+            "
+            <svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 100 100'>
+                <pattern id='patt1' width='10' height='10' patternUnits='userSpaceOnUse'
+                         overflow='{}'>
+                    <rect {} width='5' height='5'/>
+                </pattern>
+                <rect fill='url(#patt1)' width='10' height='10'/>
+            </svg>
+            ",
+            overflow, rect_attrs
+        ))
+    };
+
+    let lg1 = gradient("0.5");
+    let lg2 = gradient("0.5");
+    assert!(lg1 != lg2);
+    assert!(lg1.content_eq(&lg2));
+    assert!(!lg1.content_eq(&gradient("0.7")));
+
+    let patt1 = pattern("fill='green'", "hidden");
+    let patt2 = pattern("fill='green'", "hidden");
+    assert!(patt1 != patt2);
+    assert!(patt1.content_eq(&patt2));
+    assert!(!patt1.content_eq(&pattern("fill='red'", "hidden")));
+    assert!(!patt1.content_eq(&pattern("fill='green'", "visible")));
+    assert!(!patt1.content_eq(&pattern(
+        "fill='green' vector-effect='non-scaling-stroke'",
+        "hidden"
+    )));
+    assert!(!patt1.content_eq(&lg1));
+}
+
+#[test]
+fn tree_paints() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 100 100'>
+        <linearGradient id='lg1'>
+            <stop offset='0' stop-color='white'/>
+            <stop offset='1' stop-color='black'/>
+        </linearGradient>
+        <pattern id='patt1' width='10' height='10' patternUnits='userSpaceOnUse'>
+            <rect fill='yellow' width='5' height='5'/>
+        </pattern>
+        <mask id='mask1'>
+            <rect fill='green' width='10' height='10'/>
+        </mask>
+        <rect fill='red' stroke='url(#lg1)' width='10' height='10'/>
+        <rect fill='url(#patt1)' mask='url(#mask1)' width='10' height='10'/>
+        <rect fill='url(#patt1)' width='10' height='10'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+
+    let paints: Vec<_> = tree.paints().collect();
+    assert_eq!(paints.len(), 6);
+    assert!(matches!(paints[1], usvg_tree::Paint::LinearGradient(_)));
+    assert!(matches!(paints[3], usvg_tree::Paint::Pattern(_)));
+    assert!(paints[3] == paints[5]);
+
+    let colors: Vec<_> = tree.colors().collect();
+    assert_eq!(
+        colors,
+        vec![
+            usvg_tree::Color::new_rgb(255, 0, 0),
+            usvg_tree::Color::new_rgb(0, 128, 0),
+            usvg_tree::Color::new_rgb(255, 255, 0),
+        ]
+    );
+}
+
+#[test]
+fn tree_passes() {
+    use usvg_tree::passes::{RemapColors, RemoveEmptyGroups, TreePass};
+
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 100 100'>
+        <pattern id='patt1' width='10' height='10' patternUnits='userSpaceOnUse'>
+            <rect fill='yellow' width='5' height='5'/>
+        </pattern>
+        <g opacity='0.5'>
+            <rect fill='red' stroke='blue' width='10' height='10'/>
+        </g>
+        <rect fill='url(#patt1)' width='10' height='10'/>
+    </svg>
+    ";
+
+    let mut tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+
+    let passes: Vec<Box<dyn TreePass>> = vec![
+        Box::new(RemapColors(|c: usvg_tree::Color| {
+            usvg_tree::Color::new_rgb(255 - c.red, 255 - c.green, 255 - c.blue)
+        })),
+        Box::new(RemoveEmptyGroups),
+    ];
+    tree.run_passes(&passes);
+
+    let colors: Vec<_> = tree.colors().collect();
+    assert_eq!(
+        colors,
+        vec![
+            usvg_tree::Color::new_rgb(0, 255, 255),
+            usvg_tree::Color::new_rgb(255, 255, 0),
+            usvg_tree::Color::new_rgb(0, 0, 255),
+        ]
+    );
+}
+
+#[test]
+fn webp_data_url() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <image xlink:href='data:image/webp;base64,UklGRhoAAABXRUJQVlA4TA0AAAAvAAAAEAcQERGIiP4HAA=='/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let image = tree.root.first_child().unwrap();
+    match *image.borrow() {
+        usvg_tree::NodeKind::Image(ref image) => {
+            assert!(matches!(image.kind, usvg_tree::ImageKind::WEBP(_)));
+            assert!(image.view_box.rect.width().fuzzy_eq(&1.0));
+            assert!(image.view_box.rect.height().fuzzy_eq(&1.0));
+        }
+        _ => unreachable!(),
+    };
+}
+
+#[test]
+fn overflow() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <pattern id='patt1' width='10' height='10' overflow='visible'>
+            <rect fill='green' width='20' height='20'/>
+        </pattern>
+        <mask id='mask1'>
+            <rect fill='white' width='20' height='20'/>
+        </mask>
+        <rect fill='url(#patt1)' mask='url(#mask1)' width='10' height='10'/>
+        <image overflow='auto' xlink:href='data:image/webp;base64,UklGRhoAAABXRUJQVlA4TA0AAAAvAAAAEAcQERGIiP4HAA=='/>
+        <image overflow='scroll' xlink:href='data:image/webp;base64,UklGRhoAAABXRUJQVlA4TA0AAAAvAAAAEAcQERGIiP4HAA=='/>
+    </svg>
+    ";
+
+    use usvg_tree::Overflow;
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let mut nodes = tree.root.children();
+
+    let group = nodes.next().unwrap();
+    match *group.borrow() {
+        usvg_tree::NodeKind::Group(ref g) => {
+            assert_eq!(g.mask.as_ref().unwrap().overflow, Overflow::Hidden);
+        }
+        _ => unreachable!(),
+    }
+
+    match *group.first_child().unwrap().borrow() {
+        usvg_tree::NodeKind::Path(ref path) => match path.fill.as_ref().unwrap().paint {
+            usvg_tree::Paint::Pattern(ref patt) => assert_eq!(patt.overflow, Overflow::Visible),
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    }
+
+    let images: Vec<_> = nodes
+        .map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Image(ref image) => image.overflow,
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(images, &[Overflow::Visible, Overflow::Hidden]);
+}
+
+#[test]
+fn switch_system_language() {
+    fn selected(languages: &[&str]) -> String {
+        let svg = "
+        <svg xmlns='http://www.w3.org/2000/svg'>
+            <switch>
+                <rect id='de' systemLanguage='de' width='10' height='10'/>
+                <rect id='en-US' systemLanguage='ru, en-US' width='10' height='10'/>
+                <rect id='en' systemLanguage='en' width='10' height='10'/>
+                <rect id='fallback' width='10' height='10'/>
+            </switch>
+        </svg>
+        ";
+
+        let opt = usvg_parser::Options {
+            languages: languages.iter().map(|s| s.to_string()).collect(),
+            ..usvg_parser::Options::default()
+        };
+        let tree = usvg_tree::Tree::from_str(svg, &opt).unwrap();
+        let ids: Vec<_> = tree
+            .root
+            .descendants()
+            .filter(|n| matches!(*n.borrow(), usvg_tree::NodeKind::Path(_)))
+            .map(|n| n.borrow().id().to_string())
+            .collect();
+        assert_eq!(ids.len(), 1);
+        ids[0].clone()
+    }
+
+    assert_eq!(selected(&["de"]), "de");
+    // The first matching child wins.
+    assert_eq!(selected(&["en-US"]), "en-US");
+    // `en` matches the `en-US` prefix.
+    assert_eq!(selected(&["en"]), "en-US");
+    assert_eq!(selected(&["EN-us"]), "en-US");
+    // `en-GB` matches neither `en-US` nor `en`.
+    assert_eq!(selected(&["en-GB"]), "fallback");
+    assert_eq!(selected(&["fr"]), "fallback");
+}
+
+#[test]
+fn image_kind_from_bytes() {
+    use std::sync::Arc;
+    use usvg_parser::ImageKindParsing;
+    use usvg_tree::ImageKind;
+
+    let opt = usvg_parser::Options::default();
+
+    let gif = b"GIF89a\x01\x00\x01\x00\x00\x00\x00".to_vec();
+    let kind = ImageKind::from_bytes(Arc::new(gif), &opt);
+    assert!(matches!(kind, Some(ImageKind::GIF(_))));
+
+    let svg = b"<svg xmlns='http://www.w3.org/2000/svg' width='10' height='20'/>".to_vec();
+    match ImageKind::from_bytes(Arc::new(svg), &opt) {
+        Some(ImageKind::SVG(ref tree)) => assert!(tree.size.height().fuzzy_eq(&20.0)),
+        _ => unreachable!(),
+    }
+
+    let kind = ImageKind::from_bytes(Arc::new(b"not an image".to_vec()), &opt);
+    assert!(kind.is_none());
+}
+
+#[test]
+fn symbol_percentage_units() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <symbol id='symbol1'>
+            <rect width='50%' height='50%'/>
+        </symbol>
+        <symbol id='symbol2' viewBox='0 0 40 20'>
+            <rect width='50%' height='50%'/>
+        </symbol>
+        <use xlink:href='#symbol1' width='100' height='60'/>
+        <use xlink:href='#symbol2' width='80' height='40'/>
+        <use xlink:href='#symbol1'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let sizes: Vec<_> = tree
+        .root
+        .descendants()
+        .filter_map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Path(ref path) => {
+                let bbox = path.data.bbox().unwrap();
+                Some((bbox.width(), bbox.height()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    // Clip paths are not part of the tree, so only the `rect`s are left.
+    assert_eq!(sizes, vec![(50.0, 30.0), (20.0, 10.0), (100.0, 100.0)]);
+}
+
+#[test]
+fn text_length() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg'>
+        <text x='10' y='20' textLength='100' lengthAdjust='spacingAndGlyphs'>Text</text>
+        <text x='10' y='40' textLength='50%'>Text</text>
+        <text x='10' y='60' textLength='-10'>Text</text>
+    </svg>
+    ";
+
+    use usvg_tree::LengthAdjust;
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let spans: Vec<_> = tree
+        .root
+        .children()
+        .map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Text(ref text) => {
+                let span = &text.chunks[0].spans[0];
+                (span.text_length, span.length_adjust)
+            }
+            _ => unreachable!(),
+        })
+        .collect();
+
+    assert_eq!(
+        spans,
+        vec![
+            (Some(100.0), LengthAdjust::SpacingAndGlyphs),
+            (Some(50.0), LengthAdjust::Spacing),
+            (None, LengthAdjust::Spacing),
+        ]
+    );
+}
+
+#[test]
+fn writing_mode() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg'>
+        <text x='10' y='20' writing-mode='tb'>Text</text>
+        <text x='10' y='40' writing-mode='vertical-rl'>Text</text>
+        <text x='10' y='60' writing-mode='lr'>Text</text>
+        <text x='10' y='80'>Text</text>
+        <g writing-mode='tb-rl'>
+            <text x='10' y='100'>Text</text>
+        </g>
+    </svg>
+    ";
+
+    use usvg_tree::WritingMode;
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let modes: Vec<_> = tree
+        .root
+        .descendants()
+        .filter_map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Text(ref text) => Some(text.writing_mode),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        modes,
+        vec![
+            WritingMode::TopToBottom,
+            WritingMode::TopToBottom,
+            WritingMode::LeftToRight,
+            WritingMode::LeftToRight,
+            WritingMode::TopToBottom,
+        ]
+    );
+}
+
+#[test]
+fn text_direction() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg'>
+        <text x='10' y='20'>Text</text>
+        <text x='10' y='40' direction='rtl'>Text</text>
+        <g direction='rtl'>
+            <text x='10' y='60' unicode-bidi='bidi-override'>Text</text>
+        </g>
+        <text x='10' y='80' direction='ltr' unicode-bidi='embed'>Text</text>
+    </svg>
+    ";
+
+    use usvg_tree::TextDirection;
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let texts: Vec<_> = tree
+        .root
+        .descendants()
+        .filter_map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Text(ref text) => Some((text.direction, text.bidi_override)),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        texts,
+        vec![
+            (TextDirection::LeftToRight, false),
+            (TextDirection::RightToLeft, false),
+            (TextDirection::RightToLeft, true),
+            (TextDirection::LeftToRight, false),
+        ]
+    );
+}
+
+#[test]
+fn text_path_side() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <path id='path1' d='M 20 100 L 180 100'/>
+        <text><textPath xlink:href='#path1' startOffset='10'>Text</textPath></text>
+        <text><textPath xlink:href='#path1' side='right'>Text</textPath></text>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let paths: Vec<_> = tree
+        .root
+        .descendants()
+        .filter_map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Text(ref text) => match text.chunks[0].text_flow {
+                usvg_tree::TextFlow::Path(ref path) => {
+                    Some((path.start_offset, path.path.points().to_vec()))
+                }
+                usvg_tree::TextFlow::Linear => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        paths,
+        vec![
+            (10.0, vec![20.0, 100.0, 180.0, 100.0]),
+            (0.0, vec![180.0, 100.0, 20.0, 100.0]),
+        ]
+    );
+}
+
+#[test]
+fn font_features() {
+    let svg = r#"
+    <svg xmlns='http://www.w3.org/2000/svg'>
+        <text>Text</text>
+        <text font-variant='small-caps no-common-ligatures'>Text</text>
+        <text font-variant='none'>Text</text>
+        <g style='font-feature-settings: "liga" off, "ss01", "salt" 2'>
+            <text>Text</text>
+        </g>
+        <text style='font-feature-settings: "liga" on, "toolong"'>Text</text>
+    </svg>
+    "#;
+
+    use usvg_tree::FontFeature;
+
+    let f = |tag: &[u8; 4], value| FontFeature { tag: *tag, value };
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let spans: Vec<_> = tree
+        .root
+        .descendants()
+        .filter_map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Text(ref text) => {
+                let span = &text.chunks[0].spans[0];
+                Some((span.small_caps, span.font_features.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        spans,
+        vec![
+            (false, vec![]),
+            (true, vec![f(b"liga", 0), f(b"clig", 0)]),
+            (
+                false,
+                vec![
+                    f(b"liga", 0),
+                    f(b"clig", 0),
+                    f(b"dlig", 0),
+                    f(b"hlig", 0),
+                    f(b"calt", 0),
+                ],
+            ),
+            (false, vec![f(b"liga", 0), f(b"ss01", 1), f(b"salt", 2)]),
+            (false, vec![]),
+        ]
+    );
+}
+
+#[test]
+fn tree_deep_clone() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg'>
+        <linearGradient id='lg1'>
+            <stop offset='0' stop-color='white'/>
+            <stop offset='1' stop-color='black'/>
+        </linearGradient>
+        <linearGradient id='lg2'>
+            <stop offset='0' stop-color='red'/>
+            <stop offset='1' stop-color='blue'/>
+        </linearGradient>
+        <rect width='10' height='10' fill='url(#lg1)'/>
+        <rect width='10' height='10' fill='url(#lg1)' stroke='url(#lg2)'/>
+    </svg>
+    ";
+
+    use std::rc::Rc;
+    use usvg_tree::{NodeKind, Paint};
+
+    fn gradients(tree: &usvg_tree::Tree) -> Vec<Rc<usvg_tree::LinearGradient>> {
+        tree.root
+            .descendants()
+            .filter_map(|node| match *node.borrow() {
+                NodeKind::Path(ref path) => Some(path.clone()),
+                _ => None,
+            })
+            .flat_map(|path| {
+                let fill = path.fill.map(|f| f.paint);
+                let stroke = path.stroke.map(|s| s.paint);
+                fill.into_iter().chain(stroke)
+            })
+            .filter_map(|paint| match paint {
+                Paint::LinearGradient(lg) => Some(lg),
+                _ => None,
+            })
+            .collect()
+    }
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let tree2 = tree.deep_clone();
+
+    let orig = gradients(&tree);
+    let copy = gradients(&tree2);
+    assert_eq!(orig.len(), 3);
+    assert_eq!(copy.len(), 3);
+
+    // Sharing inside the tree must be preserved.
+    assert!(Rc::ptr_eq(&copy[0], &copy[1]));
+    assert!(!Rc::ptr_eq(&copy[1], &copy[2]));
+    for (a, b) in orig.iter().zip(&copy) {
+        assert!(!Rc::ptr_eq(a, b));
+    }
+
+    // Nodes must not be shared.
+    let first = tree2.root.first_child().unwrap();
+    first.detach();
+    assert_eq!(tree.root.children().count(), 2);
+    assert_eq!(tree2.root.children().count(), 1);
+
+    // Mutate a gradient stop in the copy.
+    drop(copy);
+    let path_node = tree2.root.last_child().unwrap();
+    if let NodeKind::Path(ref mut path) = *path_node.borrow_mut() {
+        match path.stroke.as_mut().unwrap().paint {
+            Paint::LinearGradient(ref mut lg) => {
+                let lg = Rc::get_mut(lg).unwrap();
+                lg.base.stops[0].offset = usvg_tree::StopOffset::new_clamped(0.5);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    assert_eq!(orig[2].stops[0].offset.get(), 0.0);
+    assert_eq!(gradients(&tree2)[1].stops[0].offset.get(), 0.5);
+}
+
+#[test]
+fn fe_drop_shadow() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1'>
+            <feDropShadow dx='2' dy='4' stdDeviation='3 5' flood-color='green' flood-opacity='0.5'/>
+        </filter>
+        <rect width='10' height='10' filter='url(#filter1)'/>
+    </svg>
+    ";
+
+    use usvg_tree::filter::{Input, Kind};
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let group = tree.root.first_child().unwrap();
+    let filter = match *group.borrow() {
+        usvg_tree::NodeKind::Group(ref g) => g.filters[0].clone(),
+        _ => unreachable!(),
+    };
+
+    assert_eq!(filter.primitives.len(), 1);
+    match filter.primitives[0].kind {
+        Kind::DropShadow(ref fe) => {
+            assert_eq!(fe.input, Input::SourceGraphic);
+            assert_eq!(fe.dx, 2.0);
+            assert_eq!(fe.dy, 4.0);
+            assert_eq!(fe.std_dev_x.get(), 3.0);
+            assert_eq!(fe.std_dev_y.get(), 5.0);
+            assert_eq!(fe.color, usvg_tree::Color::new_rgb(0, 128, 0));
+            assert_eq!(fe.opacity.get(), 0.5);
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn fe_image() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <rect id='rect1' width='5' height='5' fill='green'/>
+        <filter id='filter1'>
+            <feImage preserveAspectRatio='xMinYMax slice' xlink:href='data:image/webp;base64,UklGRhoAAABXRUJQVlA4TA0AAAAvAAAAEAcQERGIiP4HAA=='/>
+        </filter>
+        <filter id='filter2'>
+            <feImage xlink:href='#rect1'/>
+        </filter>
+        <rect width='10' height='10' filter='url(#filter1)'/>
+        <rect width='10' height='10' filter='url(#filter2)'/>
+    </svg>
+    ";
+
+    use usvg_tree::filter::{ImageKind, Kind};
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let images: Vec<_> = tree
+        .root
+        .children()
+        .filter_map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Group(ref g) if !g.filters.is_empty() => {
+                match g.filters[0].primitives[0].kind {
+                    Kind::Image(ref fe) => Some(fe.clone()),
+                    _ => unreachable!(),
+                }
+            }
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(images.len(), 2);
+
+    assert_eq!(images[0].aspect.align, usvg_tree::Align::XMinYMax);
+    assert!(images[0].aspect.slice);
+    assert!(matches!(
+        images[0].data,
+        ImageKind::Image(usvg_tree::ImageKind::WEBP(_))
+    ));
+
+    assert_eq!(images[1].aspect, usvg_tree::AspectRatio::default());
+    match images[1].data {
+        ImageKind::Use(ref node) => match *node.borrow() {
+            usvg_tree::NodeKind::Path(ref path) => assert!(path.fill.is_some()),
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn shape_blend_mode() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <path d='M 10 20 L 30 40' style='mix-blend-mode:screen'/>
+        <rect width='10' height='10' style='isolation:isolate'/>
+        <rect width='10' height='10' style='mix-blend-mode:normal'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let children: Vec<_> = tree.root.children().collect();
+    assert_eq!(children.len(), 3);
+
+    let is_path = |node: &usvg_tree::Node| matches!(*node.borrow(), usvg_tree::NodeKind::Path(_));
+
+    match *children[0].borrow() {
+        usvg_tree::NodeKind::Group(ref g) => {
+            assert_eq!(g.blend_mode, usvg_tree::BlendMode::Screen);
+            assert!(g.should_isolate());
+        }
+        _ => unreachable!(),
+    }
+    assert!(is_path(&children[0].first_child().unwrap()));
+
+    match *children[1].borrow() {
+        usvg_tree::NodeKind::Group(ref g) => {
+            assert_eq!(g.blend_mode, usvg_tree::BlendMode::Normal);
+            assert!(g.isolate);
+        }
+        _ => unreachable!(),
+    }
+    assert!(is_path(&children[1].first_child().unwrap()));
+
+    // A normal blend mode doesn't require a group.
+    assert!(is_path(&children[2]));
+}
+
+#[test]
+fn clip_path_children_clip_rule() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <clipPath id='clip1' clip-rule='nonzero'>
+            <path d='M 0 0 H 60 V 60 H 0 Z M 20 20 H 40 V 40 H 20 Z' clip-rule='evenodd'/>
+            <path d='M 40 40 H 100 V 100 H 40 Z M 60 60 H 80 V 80 H 60 Z'/>
+        </clipPath>
+        <rect width='100' height='100' clip-path='url(#clip1)' fill-rule='evenodd'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let clip_path = match *tree.root.first_child().unwrap().borrow() {
+        usvg_tree::NodeKind::Group(ref g) => g.clip_path.clone().unwrap(),
+        _ => unreachable!(),
+    };
+
+    let rules: Vec<_> = clip_path
+        .root
+        .children()
+        .map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Path(ref path) => path.fill.as_ref().unwrap().rule,
+            _ => unreachable!(),
+        })
+        .collect();
+
+    // `clip-rule` is resolved per child and `fill-rule` is ignored.
+    assert_eq!(
+        rules,
+        vec![usvg_tree::FillRule::EvenOdd, usvg_tree::FillRule::NonZero]
+    );
+}
+
+#[test]
+fn image_href_resolver_policies() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'
+         xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <image width='10' height='10' xlink:href='image.svg'/>
+        <image width='10' height='10' xlink:href='../images/image.svg'/>
+        <image width='10' height='10' xlink:href='../svg/a-alignment-baseline-001.svg'/>
+        <image width='10' height='10' xlink:href='data:image/webp;base64,UklGRhoAAABXRUJQVlA4TA0AAAAvAAAAEAcQERGIiP4HAA=='/>
+    </svg>
+    ";
+
+    let resources_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/images");
+
+    let image_kinds = |resolver: usvg_parser::ImageHrefResolver, dir: Option<&std::path::Path>| {
+        let opt = usvg_parser::Options {
+            resources_dir: dir.map(|d| d.to_path_buf()),
+            image_href_resolver: resolver,
+            ..usvg_parser::Options::default()
+        };
+
+        let tree = usvg_tree::Tree::from_str(svg, &opt).unwrap();
+        tree.root
+            .descendants()
+            .filter_map(|node| match *node.borrow() {
+                usvg_tree::NodeKind::Image(ref img) => Some(match img.kind {
+                    usvg_tree::ImageKind::SVG(_) => "svg",
+                    usvg_tree::ImageKind::WEBP(_) => "webp",
+                    _ => "other",
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        image_kinds(
+            usvg_parser::ImageHrefResolver::default(),
+            Some(&resources_dir)
+        ),
+        vec!["svg", "svg", "svg", "webp"]
+    );
+
+    assert_eq!(
+        image_kinds(
+            usvg_parser::ImageHrefResolver::data_uri_only(),
+            Some(&resources_dir)
+        ),
+        vec!["webp"]
+    );
+
+    // Only paths that stay inside the resources directory are allowed.
+    assert_eq!(
+        image_kinds(
+            usvg_parser::ImageHrefResolver::resources_dir_only(),
+            Some(&resources_dir)
+        ),
+        vec!["svg", "svg", "webp"]
+    );
+    assert_eq!(
+        image_kinds(usvg_parser::ImageHrefResolver::resources_dir_only(), None),
+        vec!["webp"]
+    );
+}
+
+#[test]
+fn image_href_resolver_nested_svg() {
+    // A data URL SVG, which references a local file via `feImage`,
+    // that is not removed from nested SVG images.
+    let image_path =
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/images/image.svg");
+    let nested_svg = format!(
+        "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg' \
+              xmlns:xlink='http://www.w3.org/1999/xlink'>\
+         <filter id='filter1'><feImage xlink:href='{}'/></filter>\
+         <rect width='10' height='10' filter='url(#filter1)'/>\
+         </svg>",
+        image_path.canonicalize().unwrap().display()
+    );
+    let svg = format!(
+        "<svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg' \
+              xmlns:xlink='http://www.w3.org/1999/xlink'>\
+         <image width='10' height='10' xlink:href=\"data:image/svg+xml,{}\"/>\
+         </svg>",
+        nested_svg
+            .replace('%', "%25")
+            .replace('<', "%3C")
+            .replace('>', "%3E")
+            .replace('#', "%23")
+    );
+
+    let has_fe_image_data = |resolver: usvg_parser::ImageHrefResolver| {
+        let opt = usvg_parser::Options {
+            image_href_resolver: resolver,
+            ..usvg_parser::Options::default()
+        };
+
+        let tree = usvg_tree::Tree::from_str(&svg, &opt).unwrap();
+        let image = tree.root.first_child().unwrap();
+        let nested_tree = match *image.borrow() {
+            usvg_tree::NodeKind::Image(ref img) => match img.kind {
+                usvg_tree::ImageKind::SVG(ref tree) => tree.clone(),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+
+        let path = nested_tree.root.first_child().unwrap();
+        let has_data = match *path.borrow() {
+            usvg_tree::NodeKind::Group(ref g) => g.filters.iter().any(|f| {
+                f.primitives.iter().any(|p| {
+                    matches!(
+                        p.kind,
+                        usvg_tree::filter::Kind::Image(usvg_tree::filter::Image {
+                            data: usvg_tree::filter::ImageKind::Image(_),
+                            ..
+                        })
+                    )
+                })
+            }),
+            _ => unreachable!(),
+        };
+        has_data
+    };
+
+    // Nested SVG images use the caller's policy.
+    assert!(has_fe_image_data(usvg_parser::ImageHrefResolver::default()));
+    assert!(!has_fe_image_data(
+        usvg_parser::ImageHrefResolver::data_uri_only()
+    ));
+
+    // Including a custom one.
+    let custom = usvg_parser::ImageHrefResolver {
+        resolve_string: std::sync::Arc::new(|_, _| {
+            Some(usvg_tree::ImageKind::PNG(std::sync::Arc::new(Vec::new())))
+        }),
+        ..usvg_parser::ImageHrefResolver::data_uri_only()
+    };
+    assert!(has_fe_image_data(custom));
+
+    let custom = usvg_parser::ImageHrefResolver {
+        resolve_string: std::sync::Arc::new(|_, _| None),
+        ..usvg_parser::ImageHrefResolver::default()
+    };
+    assert!(!has_fe_image_data(custom));
+}
+
+#[test]
+fn elements_limit() {
+    // Each level instantiates the previous one 10 times, i.e. 10^4 paths in total.
+    let mut svg = String::from(
+        "<svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg' \
+              xmlns:xlink='http://www.w3.org/1999/xlink'>\
+         <defs><path id='l0' d='M 10 20 L 30 40'/>",
+    );
+    for i in 1..5 {
+        svg.push_str(&format!("<g id='l{}'>", i));
+        for _ in 0..10 {
+            svg.push_str(&format!("<use xlink:href='#l{}'/>", i - 1));
+        }
+        svg.push_str("</g>");
+    }
+    svg.push_str("</defs><use xlink:href='#l4'/></svg>");
+
+    assert!(usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).is_ok());
+
+    let opt = usvg_parser::Options {
+        elements_limit: 1000,
+        ..usvg_parser::Options::default()
+    };
+    assert!(matches!(
+        usvg_tree::Tree::from_str(&svg, &opt),
+        Err(usvg_parser::Error::ElementsLimitReached)
+    ));
+
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='10' height='10'/>
+        <rect width='10' height='10'/>
+    </svg>
+    ";
+
+    let opt = usvg_parser::Options {
+        elements_limit: 3,
+        ..usvg_parser::Options::default()
+    };
+    assert!(usvg_tree::Tree::from_str(svg, &opt).is_ok());
+
+    let opt = usvg_parser::Options {
+        elements_limit: 2,
+        ..usvg_parser::Options::default()
+    };
+    assert!(matches!(
+        usvg_tree::Tree::from_str(svg, &opt),
+        Err(usvg_parser::Error::ElementsLimitReached)
+    ));
+}
+
+#[test]
+fn nested_svg_depth_limit() {
+    // An SVG image that references itself `count` times.
+    let recursive_svg = |name: &str, count: usize| {
+        let path = std::env::temp_dir().join(format!(
+            "usvg-recursive-{}-{}.svg",
+            name,
+            std::process::id()
+        ));
+        let mut svg = String::from(
+            "<svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg' \
+                  xmlns:xlink='http://www.w3.org/1999/xlink'>\
+             <rect width='10' height='10'/>",
+        );
+        for _ in 0..count {
+            svg.push_str(&format!(
+                "<image width='10' height='10' xlink:href='{}'/>",
+                path.display()
+            ));
+        }
+        svg.push_str("</svg>");
+        std::fs::write(&path, &svg).unwrap();
+        (path, svg)
+    };
+
+    let (path, svg) = recursive_svg("single", 1);
+
+    let opt = usvg_parser::Options::default();
+    assert!(matches!(
+        usvg_tree::Tree::from_str(&svg, &opt),
+        Err(usvg_parser::Error::NestedSvgDepthLimitReached)
+    ));
+
+    let opt = usvg_parser::Options {
+        nested_svg_depth_limit: 0,
+        ..usvg_parser::Options::default()
+    };
+    assert!(matches!(
+        usvg_tree::Tree::from_str(&svg, &opt),
+        Err(usvg_parser::Error::NestedSvgDepthLimitReached)
+    ));
+
+    std::fs::remove_file(&path).unwrap();
+
+    // Without limits, this one would be loaded 10^8 times.
+    let (path, svg) = recursive_svg("fan-out", 10);
+
+    let opt = usvg_parser::Options::default();
+    assert!(matches!(
+        usvg_tree::Tree::from_str(&svg, &opt),
+        Err(usvg_parser::Error::NestedSvgDepthLimitReached)
+    ));
+
+    // Each load is counted against the elements limit,
+    // even though nested images are removed afterwards.
+    let opt = usvg_parser::Options {
+        nested_svg_depth_limit: 100,
+        elements_limit: 1000,
+        ..usvg_parser::Options::default()
+    };
+    assert!(matches!(
+        usvg_tree::Tree::from_str(&svg, &opt),
+        Err(usvg_parser::Error::ElementsLimitReached)
+    ));
+
+    std::fs::remove_file(&path).unwrap();
+
+    // A non-recursive nested SVG image is fine.
+    let nested_svg = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>\
+                      <rect width='10' height='10'/></svg>"
+        .replace('<', "%3C")
+        .replace('>', "%3E");
+    let svg = format!(
+        "<svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg' \
+              xmlns:xlink='http://www.w3.org/1999/xlink'>\
+         <image width='10' height='10' xlink:href=\"data:image/svg+xml,{}\"/>\
+         </svg>",
+        nested_svg
+    );
+    let opt = usvg_parser::Options {
+        nested_svg_depth_limit: 1,
+        ..usvg_parser::Options::default()
+    };
+    assert!(usvg_tree::Tree::from_str(&svg, &opt).is_ok());
+}
+
+#[test]
+fn nested_svg_elements_limit() {
+    // Each nested SVG image has 50 elements and fits the limit by itself.
+    let mut nested_svg =
+        String::from("<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>");
+    for _ in 0..50 {
+        nested_svg.push_str("<rect width='10' height='10'/>");
+    }
+    nested_svg.push_str("</svg>");
+    let nested_svg = nested_svg.replace('<', "%3C").replace('>', "%3E");
+
+    let mut svg = String::from(
+        "<svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg' \
+              xmlns:xlink='http://www.w3.org/1999/xlink'>",
+    );
+    for _ in 0..10 {
+        svg.push_str(&format!(
+            "<image width='10' height='10' xlink:href=\"data:image/svg+xml,{}\"/>",
+            nested_svg
+        ));
+    }
+    svg.push_str("</svg>");
+
+    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    assert_eq!(tree.root.children().count(), 10);
+
+    // But not all of them together.
+    let opt = usvg_parser::Options {
+        elements_limit: 200,
+        ..usvg_parser::Options::default()
+    };
+    assert!(matches!(
+        usvg_tree::Tree::from_str(&svg, &opt),
+        Err(usvg_parser::Error::ElementsLimitReached)
+    ));
+
+    // The budget is reset after each document.
+    let opt = usvg_parser::Options {
+        elements_limit: 600,
+        ..usvg_parser::Options::default()
+    };
+    assert!(usvg_tree::Tree::from_str(&svg, &opt).is_ok());
+    assert!(usvg_tree::Tree::from_str(&svg, &opt).is_ok());
+}
+
+#[test]
+fn elements_limit_in_resolver() {
+    // A resolver that parses its own SVG with 50 elements,
+    // which must not be counted against the outer document's limit.
+    static CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let resolver = usvg_parser::ImageHrefResolver {
+        resolve_string: std::sync::Arc::new(|_, _| {
+            CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+
+            let mut svg =
+                String::from("<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>");
+            for _ in 0..50 {
+                svg.push_str("<rect width='10' height='10'/>");
+            }
+            svg.push_str("</svg>");
+
+            let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+            assert_eq!(tree.root.children().count(), 50);
+            Some(usvg_tree::ImageKind::PNG(std::sync::Arc::new(Vec::new())))
+        }),
+        ..usvg_parser::ImageHrefResolver::default()
+    };
+
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'
+         xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <image width='10' height='10' xlink:href='image1.png'/>
+        <image width='10' height='10' xlink:href='image2.png'/>
+    </svg>
+    ";
+
+    let opt = usvg_parser::Options {
+        image_href_resolver: resolver,
+        elements_limit: 10,
+        ..usvg_parser::Options::default()
+    };
+    assert!(usvg_tree::Tree::from_str(svg, &opt).is_ok());
+    assert_eq!(CALLS.load(std::sync::atomic::Ordering::SeqCst), 2);
+}
+
+#[test]
+fn fe_turbulence() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1'>
+            <feTurbulence baseFrequency='0.05 0.1' numOctaves='3' seed='-2.7'
+                          stitchTiles='stitch' type='fractalNoise'/>
+        </filter>
+        <filter id='filter2'>
+            <feTurbulence seed='1.9'/>
+        </filter>
+        <rect width='10' height='10' filter='url(#filter1)'/>
+        <rect width='10' height='10' filter='url(#filter2)'/>
+    </svg>
+    ";
+
+    use usvg_tree::filter::{Kind, TurbulenceKind};
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let fes: Vec<_> = tree
+        .root
+        .children()
+        .map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Group(ref g) => match g.filters[0].primitives[0].kind {
+                Kind::Turbulence(fe) => fe,
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        })
+        .collect();
+
+    assert_eq!(fes[0].base_frequency.x.get(), 0.05);
+    assert_eq!(fes[0].base_frequency.y.get(), 0.1);
+    assert_eq!(fes[0].num_octaves, 3);
+    // The seed must be truncated and not rounded.
+    assert_eq!(fes[0].seed, -2);
+    assert!(fes[0].stitch_tiles);
+    assert_eq!(fes[0].kind, TurbulenceKind::FractalNoise);
+
+    assert_eq!(fes[1].base_frequency.x.get(), 0.0);
+    assert_eq!(fes[1].num_octaves, 1);
+    assert_eq!(fes[1].seed, 1);
+    assert!(!fes[1].stitch_tiles);
+    assert_eq!(fes[1].kind, TurbulenceKind::Turbulence);
+}
+
+#[test]
+fn stop_color_current_color() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg' color='red'>
+        <linearGradient id='lg1'>
+            <stop offset='0' stop-color='currentColor'/>
+            <stop offset='0.5' style='stop-color:currentColor' stop-opacity='0.5'/>
+            <stop offset='1' stop-color='currentColor' color='rgba(0, 0, 255, 0.5)' stop-opacity='0.5'/>
+        </linearGradient>
+        <rect width='10' height='10' fill='url(#lg1)' color='green'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let lg = match *tree.root.first_child().unwrap().borrow() {
+        usvg_tree::NodeKind::Path(ref path) => match path.fill.as_ref().unwrap().paint {
+            usvg_tree::Paint::LinearGradient(ref lg) => lg.clone(),
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+
+    // `color` is inherited by the stop itself and not from the element that uses the gradient.
+    let red = usvg_tree::Color::new_rgb(255, 0, 0);
+    assert_eq!(lg.stops[0].color, red);
+    assert_eq!(lg.stops[0].opacity.get(), 1.0);
+    assert_eq!(lg.stops[1].color, red);
+    assert_eq!(lg.stops[1].opacity.get(), 0.5);
+
+    // The `color` alpha is folded into the `stop-opacity`.
+    assert_eq!(lg.stops[2].color, usvg_tree::Color::new_rgb(0, 0, 255));
+    assert!((lg.stops[2].opacity.get() - 0.25).abs() < 0.01);
+}
+
+#[test]
+fn text_baseline_shift() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg'>
+        <text x='10' y='20' font-size='20'>H<tspan baseline-shift='sub'>2</tspan>O</text>
+        <text x='10' y='40' font-size='20'>
+            <tspan baseline-shift='super'>a<tspan baseline-shift='50%'>b</tspan></tspan>
+            <tspan alignment-baseline='middle'>c</tspan>
+        </text>
+    </svg>
+    ";
+
+    use usvg_tree::{AlignmentBaseline, BaselineShift};
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let texts: Vec<_> = tree
+        .root
+        .children()
+        .map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Text(ref text) => text.chunks[0]
+                .spans
+                .iter()
+                .map(|span| (span.baseline_shift.clone(), span.alignment_baseline))
+                .collect::<Vec<_>>(),
+            _ => unreachable!(),
+        })
+        .collect();
+
+    // Only the `2` is shifted down.
+    assert_eq!(
+        texts[0],
+        vec![
+            (vec![], AlignmentBaseline::Auto),
+            (vec![BaselineShift::Subscript], AlignmentBaseline::Auto),
+            (vec![], AlignmentBaseline::Auto),
+        ]
+    );
+
+    // Nested shifts are accumulated, from the innermost `tspan` outwards.
+    assert_eq!(texts[1][0].0, vec![BaselineShift::Superscript]);
+    assert_eq!(
+        texts[1][1].0,
+        vec![BaselineShift::Number(10.0), BaselineShift::Superscript]
+    );
+    assert_eq!(texts[1].last().unwrap().1, AlignmentBaseline::Middle);
+}
+
+#[test]
+fn text_spacing() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg' font-size='20'>
+        <text letter-spacing='2' word-spacing='4'>Text</text>
+        <text letter-spacing='5%' word-spacing='0.5em'>Text</text>
+        <text letter-spacing='-2'>Te<tspan word-spacing='-3'>xt</tspan></text>
+        <text>Text</text>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let spacings: Vec<_> = tree
+        .root
+        .children()
+        .map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Text(ref text) => {
+                let span = text.chunks[0].spans.last().unwrap();
+                (span.letter_spacing, span.word_spacing)
+            }
+            _ => unreachable!(),
+        })
+        .collect();
+
+    // Percentages are relative to the viewport diagonal.
+    // Negative values are preserved and spacing is inherited.
+    assert_eq!(
+        spacings,
+        vec![(2.0, 4.0), (10.0, 10.0), (-2.0, -3.0), (0.0, 0.0)]
+    );
+}
+
+#[test]
+fn units_percentages() {
+    let svg = "
+    <svg viewBox='0 0 200 100' xmlns='http://www.w3.org/2000/svg'>
+        <linearGradient id='lg1' x1='25%' x2='50%' y2='50%'>
+            <stop offset='0' stop-color='white'/>
+            <stop offset='1' stop-color='black'/>
+        </linearGradient>
+        <linearGradient id='lg2' x1='25%' x2='50%' y2='50%' gradientUnits='userSpaceOnUse'>
+            <stop offset='0' stop-color='white'/>
+            <stop offset='1' stop-color='black'/>
+        </linearGradient>
+        <mask id='mask1' x='10%' y='10%' width='50%' height='50%'>
+            <rect width='10' height='10' fill='white'/>
+        </mask>
+        <mask id='mask2' x='10%' y='10%' width='50%' height='50%' maskUnits='userSpaceOnUse'>
+            <rect width='10' height='10' fill='white'/>
+        </mask>
+        <rect id='rect1' fill='url(#lg1)' mask='url(#mask1)' width='10' height='10'/>
+        <rect id='rect2' fill='url(#lg2)' mask='url(#mask2)' width='10' height='10'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+
+    let gradient = |id: &str| {
+        let node = tree.node_by_id(id).unwrap();
+        let kind = node.borrow();
+        match *kind {
+            usvg_tree::NodeKind::Path(ref path) => match path.fill.as_ref().unwrap().paint {
+                usvg_tree::Paint::LinearGradient(ref lg) => (lg.x1, lg.x2, lg.y2),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+    };
+
+    let mask_rect = |id: &str| {
+        let node = tree.node_by_id(id).unwrap();
+        let parent = node.parent().unwrap();
+        let kind = parent.borrow();
+        match *kind {
+            usvg_tree::NodeKind::Group(ref g) => g.mask.as_ref().unwrap().rect,
+            _ => unreachable!(),
+        }
+    };
+
+    // `objectBoundingBox` percentages are fractions of the bbox.
+    assert_eq!(gradient("rect1"), (0.25, 0.5, 0.5));
+    assert!(mask_rect("rect1").fuzzy_eq(&usvg_tree::Rect::new(0.1, 0.1, 0.5, 0.5).unwrap()));
+
+    // `userSpaceOnUse` percentages are relative to the viewport.
+    assert_eq!(gradient("rect2"), (50.0, 100.0, 50.0));
+    assert!(mask_rect("rect2").fuzzy_eq(&usvg_tree::Rect::new(20.0, 10.0, 100.0, 50.0).unwrap()));
+}
+
+#[test]
+fn keep_hidden_nodes() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <rect id='rect1' width='50' height='50' display='none'/>
+        <g id='g1' style='display:none'>
+            <rect id='rect2' width='100' height='100'/>
+        </g>
+        <rect id='rect3' x='10' y='10' width='10' height='10'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    assert_eq!(tree.root.descendants().count(), 2);
+    assert!(tree.node_by_id("rect1").is_none());
+
+    let opt = usvg_parser::Options {
+        keep_hidden_nodes: true,
+        ..usvg_parser::Options::default()
+    };
+    let tree = usvg_tree::Tree::from_str(svg, &opt).unwrap();
+
+    let is_hidden = |node: &usvg_tree::Node| match *node.borrow() {
+        usvg_tree::NodeKind::Group(ref g) => g.hidden,
+        _ => false,
+    };
+
+    // Hidden elements are wrapped into hidden groups.
+    for id in ["rect1", "g1", "rect2"] {
+        let node = tree.node_by_id(id).unwrap();
+        assert!(node.ancestors().skip(1).any(|n| is_hidden(&n)), "{}", id);
+    }
+
+    let rect3 = tree.node_by_id("rect3").unwrap();
+    assert!(!rect3.ancestors().any(|n| is_hidden(&n)));
+    assert_eq!(tree.root.children().filter(is_hidden).count(), 2);
+
+    // Hidden subtrees are not rendered.
+    let rendered: Vec<_> = tree.root.rendered_descendants().collect();
+    assert!(rendered.contains(&rect3));
+    for id in ["rect1", "g1", "rect2"] {
+        assert!(!rendered.contains(&tree.node_by_id(id).unwrap()), "{}", id);
+    }
+
+    // Hidden nodes do not affect the bbox.
+    assert!(tree
+        .bbox()
+        .unwrap()
+        .fuzzy_eq(&usvg_tree::Rect::new(10.0, 10.0, 10.0, 10.0).unwrap()));
+}
+
+#[test]
+fn image_rendering_keywords() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <image image-rendering='pixelated' xlink:href='data:image/webp;base64,UklGRhoAAABXRUJQVlA4TA0AAAAvAAAAEAcQERGIiP4HAA=='/>
+        <image style='image-rendering:crisp-edges' xlink:href='data:image/webp;base64,UklGRhoAAABXRUJQVlA4TA0AAAAvAAAAEAcQERGIiP4HAA=='/>
+        <image image-rendering='auto' xlink:href='data:image/webp;base64,UklGRhoAAABXRUJQVlA4TA0AAAAvAAAAEAcQERGIiP4HAA=='/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let modes: Vec<_> = tree
+        .root
+        .children()
+        .map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Image(ref image) => image.rendering_mode,
+            _ => unreachable!(),
+        })
+        .collect();
+
+    assert_eq!(
+        modes,
+        vec![
+            usvg_tree::ImageRendering::OptimizeSpeed,
+            usvg_tree::ImageRendering::OptimizeSpeed,
+            usvg_tree::ImageRendering::OptimizeQuality,
+        ]
+    );
+}
+
+#[test]
+fn rendering_hints_inheritance() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <g id='g1' shape-rendering='crispEdges' image-rendering='optimizeSpeed'>
+            <g opacity='0.5'>
+                <g style='opacity:0.5'>
+                    <rect id='rect1' width='10' height='10'/>
+                    <rect id='rect2' width='10' height='10' shape-rendering='geometricPrecision'/>
+                    <image id='image1' xlink:href='data:image/webp;base64,UklGRhoAAABXRUJQVlA4TA0AAAAvAAAAEAcQERGIiP4HAA=='/>
+                </g>
+            </g>
+        </g>
+        <g style='shape-rendering:optimizeSpeed'>
+            <use id='use1' xlink:href='#rect3'/>
+        </g>
+        <rect id='rect3' width='10' height='10'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+
+    let shape_rendering = |node: usvg_tree::Node| match *node.borrow() {
+        usvg_tree::NodeKind::Path(ref path) => path.rendering_mode,
+        _ => unreachable!(),
+    };
+
+    let rect1 = tree.node_by_id("rect1").unwrap();
+    assert_eq!(
+        shape_rendering(rect1),
+        usvg_tree::ShapeRendering::CrispEdges
+    );
+    let rect2 = tree.node_by_id("rect2").unwrap();
+    assert_eq!(
+        shape_rendering(rect2),
+        usvg_tree::ShapeRendering::GeometricPrecision
+    );
+    let rect3 = tree.node_by_id("rect3").unwrap();
+    assert_eq!(
+        shape_rendering(rect3),
+        usvg_tree::ShapeRendering::GeometricPrecision
+    );
+
+    // The `use` copy of `rect3` inherits from the `use` element's ancestors.
+    let use1 = tree.node_by_id("use1").unwrap();
+    let use_path = use1.first_child().unwrap();
+    assert_eq!(
+        shape_rendering(use_path),
+        usvg_tree::ShapeRendering::OptimizeSpeed
+    );
+
+    let image1 = tree.node_by_id("image1").unwrap();
+    match *image1.borrow() {
+        usvg_tree::NodeKind::Image(ref image) => {
+            assert_eq!(
+                image.rendering_mode,
+                usvg_tree::ImageRendering::OptimizeSpeed
+            )
+        }
+        _ => unreachable!(),
+    };
+}
+
+#[test]
+fn path_data_from_svg_string() {
+    let d = "m 10 20 h 30 q 10 10 0 20 a 10 5 30 1 0 -20 -5 s 5 5 10 0 t 5 5 z m 5 5 l 10 1 L 20";
+    let svg = format!(
+        "<svg xmlns='http://www.w3.org/2000/svg'>
+            <path d='{}'/>
+        </svg>",
+        d
+    );
+
+    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    let path = tree.root.first_child().unwrap();
+    let data = match *path.borrow() {
+        usvg_tree::NodeKind::Path(ref path) => path.data.clone(),
+        _ => unreachable!(),
+    };
+
+    // The `d` attribute is rendered up to the error.
+    assert!(usvg_tree::PathData::from_svg_string(d).is_err());
+    let (expected, err) = usvg_tree::PathData::from_svg_string_partial(d);
+    assert!(err.is_some());
+    assert_eq!(data.commands(), expected.commands());
+    assert_eq!(data.points(), expected.points());
+}
+
+fn serde_round_trip(tree: &usvg_tree::Tree) -> usvg_tree::Tree {
+    let json = serde_json::to_string(tree).unwrap();
+    let tree2: usvg_tree::Tree = serde_json::from_str(&json).unwrap();
+    // Trees are structurally identical when they serialize to the same output.
+    assert_eq!(json, serde_json::to_string(&tree2).unwrap());
+    tree2
+}
+
+#[test]
+fn serde_shared_paint_server() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 20 10'>
+        <linearGradient id='lg1'>
+            <stop offset='0' stop-color='red'/>
+            <stop offset='1' stop-color='blue' stop-opacity='0.5'/>
+        </linearGradient>
+        <rect width='10' height='10' fill='url(#lg1)'/>
+        <rect x='10' width='10' height='10' fill='url(#lg1)'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let tree = serde_round_trip(&tree);
+
+    let fill_paint = |node: usvg_tree::Node| match *node.borrow() {
+        usvg_tree::NodeKind::Path(ref path) => path.fill.as_ref().unwrap().paint.clone(),
+        _ => unreachable!(),
+    };
+
+    let paint1 = fill_paint(tree.root.first_child().unwrap());
+    let paint2 = fill_paint(tree.root.last_child().unwrap());
+    match (paint1, paint2) {
+        (usvg_tree::Paint::LinearGradient(lg1), usvg_tree::Paint::LinearGradient(lg2)) => {
+            assert!(std::rc::Rc::ptr_eq(&lg1, &lg2));
+            assert_eq!(lg1.stops.len(), 2);
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn serde_real_files() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/svg");
+    for name in [
+        "e-clipPath-037.svg",
+        "e-feImage-002.svg",
+        "e-filter-022.svg",
+        "e-mask-017.svg",
+        "e-pattern-012.svg",
+        "e-radialGradient-031.svg",
+        "e-textPath-029.svg",
+        "e-use-003.svg",
+    ] {
+        let data = std::fs::read(dir.join(name)).unwrap();
+        let tree = usvg_tree::Tree::from_data(&data, &usvg_parser::Options::default()).unwrap();
+        serde_round_trip(&tree);
+    }
+}
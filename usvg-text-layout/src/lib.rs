@@ -43,10 +43,22 @@ pub trait TreeTextToPath {
 
 impl TreeTextToPath for usvg_tree::Tree {
     fn convert_text(&mut self, fontdb: &fontdb::Database) {
-        convert_text(self.root.clone(), fontdb);
+        convert_tree_text_with_shaper(self, fontdb, &DefaultShaper);
     }
 }
 
+/// Converts text nodes into paths using a custom text shaper.
+///
+/// Same as [`TreeTextToPath::convert_text`], but the shaping is done by `shaper`.
+pub fn convert_tree_text_with_shaper(
+    tree: &mut Tree,
+    fontdb: &fontdb::Database,
+    shaper: &dyn TextShaper,
+) {
+    convert_text(tree.root.clone(), fontdb, shaper);
+    tree.invalidate_id_index();
+}
+
 /// A `usvg::Text` extension trait.
 pub trait TextToPath {
     /// Converts the text node into path(s).
@@ -57,52 +69,111 @@ pub trait TextToPath {
 
 impl TextToPath for Text {
     fn convert(&self, fontdb: &fontdb::Database, absolute_ts: Transform) -> Option<Node> {
-        let (new_paths, bbox) = text_to_paths(self, fontdb, absolute_ts);
-        if new_paths.is_empty() {
-            return None;
-        }
-
-        // Create a group will all paths that was created during text-to-path conversion.
-        let group = Node::new(NodeKind::Group(Group {
-            id: self.id.clone(),
-            transform: self.transform,
-            ..Group::default()
-        }));
-
-        let rendering_mode = resolve_rendering_mode(self);
-        for mut path in new_paths {
-            fix_obj_bounding_box(&mut path, bbox);
-            path.rendering_mode = rendering_mode;
-            group.append_kind(NodeKind::Path(path));
-        }
+        convert_text_with_shaper(self, fontdb, absolute_ts, &DefaultShaper)
+    }
+}
 
-        Some(group)
+/// Converts the text node into path(s) using a custom text shaper.
+///
+/// Same as [`TextToPath::convert`], but the shaping is done by `shaper`.
+pub fn convert_text_with_shaper(
+    text: &Text,
+    fontdb: &fontdb::Database,
+    absolute_ts: Transform,
+    shaper: &dyn TextShaper,
+) -> Option<Node> {
+    let (mut new_paths, bbox) = text_to_paths(text, fontdb, shaper, absolute_ts);
+    for path in &mut new_paths {
+        fix_obj_bounding_box(path, bbox);
     }
+
+    text.to_paths(new_paths)
+}
+
+/// A glyph produced by a [`TextShaper`].
+///
+/// All values are in font units.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ShapedGlyph {
+    /// The glyph ID in the font.
+    ///
+    /// `0` indicates a missing glyph.
+    pub id: u16,
+
+    /// Position in bytes of the glyph's cluster in the shaped text.
+    ///
+    /// Used to group glyphs into clusters and to match them with a `TextSpan`.
+    /// Must be at a char boundary.
+    pub cluster: usize,
+
+    /// The glyph offset along the X axis.
+    pub x_offset: i32,
+
+    /// The glyph offset along the Y axis.
+    pub y_offset: i32,
+
+    /// The glyph X-advance.
+    pub x_advance: i32,
 }
 
-fn convert_text(root: Node, fontdb: &fontdb::Database) {
+/// A text shaper.
+///
+/// usvg resolves fonts, does the font fallback and the text layout,
+/// while the shaping itself can be delegated to an external implementation,
+/// like HarfBuzz or a platform shaper.
+pub trait TextShaper {
+    /// Shapes a text using the specified font face.
+    ///
+    /// `text` is the whole `TextChunk::text`, even when the font was resolved
+    /// only for a single `span`. The span's `font_features`, `apply_kerning`
+    /// and `small_caps` should be honored.
+    ///
+    /// `direction` sets the paragraph embedding level. When `bidi_override` is set,
+    /// the whole text should be shaped as a single run in that direction.
+    /// Glyphs must be returned in the visual order.
+    ///
+    /// Missing glyphs will trigger the font fallback, in which case the text
+    /// will be shaped again using a different font.
+    ///
+    /// Returns `None` when shaping has failed.
+    fn shape(
+        &self,
+        text: &str,
+        font: ID,
+        span: &TextSpan,
+        direction: TextDirection,
+        bidi_override: bool,
+        fontdb: &fontdb::Database,
+    ) -> Option<Vec<ShapedGlyph>>;
+}
+
+/// The default text shaper, based on `rustybuzz`.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct DefaultShaper;
+
+fn convert_text(root: Node, fontdb: &fontdb::Database, shaper: &dyn TextShaper) {
     let mut text_nodes = Vec::new();
     // We have to update text nodes in clipPaths, masks and patterns as well.
     for node in root.descendants() {
         match *node.borrow() {
             NodeKind::Group(ref g) => {
                 if let Some(ref clip) = g.clip_path {
-                    convert_text(clip.root.clone(), fontdb);
+                    convert_text(clip.root.clone(), fontdb, shaper);
                 }
 
                 if let Some(ref mask) = g.mask {
-                    convert_text(mask.root.clone(), fontdb);
+                    convert_text(mask.root.clone(), fontdb, shaper);
                 }
             }
             NodeKind::Path(ref path) => {
                 if let Some(ref fill) = path.fill {
                     if let Paint::Pattern(ref p) = fill.paint {
-                        convert_text(p.root.clone(), fontdb);
+                        convert_text(p.root.clone(), fontdb, shaper);
                     }
                 }
                 if let Some(ref stroke) = path.stroke {
                     if let Paint::Pattern(ref p) = stroke.paint {
-                        convert_text(p.root.clone(), fontdb);
+                        convert_text(p.root.clone(), fontdb, shaper);
                     }
                 }
             }
@@ -114,12 +185,12 @@ fn convert_text(root: Node, fontdb: &fontdb::Database) {
                     for span in &chunk.spans {
                         if let Some(ref fill) = span.fill {
                             if let Paint::Pattern(ref p) = fill.paint {
-                                convert_text(p.root.clone(), fontdb);
+                                convert_text(p.root.clone(), fontdb, shaper);
                             }
                         }
                         if let Some(ref stroke) = span.stroke {
                             if let Paint::Pattern(ref p) = stroke.paint {
-                                convert_text(p.root.clone(), fontdb);
+                                convert_text(p.root.clone(), fontdb, shaper);
                             }
                         }
                     }
@@ -137,7 +208,7 @@ fn convert_text(root: Node, fontdb: &fontdb::Database) {
         if let NodeKind::Text(ref text) = *node.borrow() {
             let mut absolute_ts = node.parent().unwrap().abs_transform();
             absolute_ts.append(&text.transform);
-            new_node = text.convert(fontdb, absolute_ts);
+            new_node = convert_text_with_shaper(text, fontdb, absolute_ts, shaper);
         }
 
         if let Some(new_node) = new_node {
@@ -452,14 +523,6 @@ impl ByteIndex {
     }
 }
 
-fn resolve_rendering_mode(text: &Text) -> ShapeRendering {
-    match text.rendering_mode {
-        TextRendering::OptimizeSpeed => ShapeRendering::CrispEdges,
-        TextRendering::OptimizeLegibility => ShapeRendering::GeometricPrecision,
-        TextRendering::GeometricPrecision => ShapeRendering::GeometricPrecision,
-    }
-}
-
 fn chunk_span_at(chunk: &TextChunk, byte_offset: ByteIndex) -> Option<&TextSpan> {
     chunk
         .spans
@@ -501,6 +564,7 @@ type FontsCache = HashMap<Font, Rc<ResolvedFont>>;
 fn text_to_paths(
     text_node: &Text,
     fontdb: &fontdb::Database,
+    shaper: &dyn TextShaper,
     abs_ts: Transform,
 ) -> (Vec<Path>, PathBbox) {
     let mut fonts_cache: FontsCache = HashMap::new();
@@ -525,7 +589,14 @@ fn text_to_paths(
             TextFlow::Path(_) => (0.0, 0.0),
         };
 
-        let mut clusters = outline_chunk(chunk, &fonts_cache, fontdb);
+        let mut clusters = outline_chunk(
+            chunk,
+            text_node.direction,
+            text_node.bidi_override,
+            &fonts_cache,
+            shaper,
+            fontdb,
+        );
         if clusters.is_empty() {
             char_offset += chunk.text.chars().count();
             continue;
@@ -541,6 +612,7 @@ fn text_to_paths(
             &text_node.positions,
             &text_node.rotate,
             text_node.writing_mode,
+            text_node.direction,
             abs_ts,
             &fonts_cache,
             &mut clusters,
@@ -750,7 +822,9 @@ fn convert_span(
         fill,
         stroke: span.stroke.clone(),
         paint_order: span.paint_order,
+        raw_paint_order: None,
         rendering_mode: ShapeRendering::default(),
+        vector_effect: VectorEffect::default(),
         text_bbox: bboxes_data.bbox().and_then(|r| r.to_rect()),
         data: Rc::new(path_data),
     };
@@ -864,7 +938,7 @@ fn paint_server_to_user_space_on_use(paint: Paint, bbox: PathBbox) -> Option<Pai
     // If not, the `convert` module will remove unused defs anyway.
 
     // Update id, transform and units.
-    let ts = Transform::from_bbox(bbox.to_rect()?);
+    let ts = Units::ObjectBoundingBox.bbox_transform(bbox.to_rect())?;
     let paint = match paint {
         Paint::Color(_) => paint,
         Paint::LinearGradient(ref lg) => {
@@ -880,6 +954,7 @@ fn paint_server_to_user_space_on_use(paint: Paint, bbox: PathBbox) -> Option<Pai
                     units: Units::UserSpaceOnUse,
                     transform,
                     spread_method: lg.spread_method,
+                    color_interpolation: lg.color_interpolation,
                     stops: lg.stops.clone(),
                 },
             }))
@@ -898,10 +973,28 @@ fn paint_server_to_user_space_on_use(paint: Paint, bbox: PathBbox) -> Option<Pai
                     units: Units::UserSpaceOnUse,
                     transform,
                     spread_method: rg.spread_method,
+                    color_interpolation: rg.color_interpolation,
                     stops: rg.stops.clone(),
                 },
             }))
         }
+        Paint::ConicGradient(ref cg) => {
+            let mut transform = cg.transform;
+            transform.prepend(&ts);
+            Paint::ConicGradient(Rc::new(ConicGradient {
+                id: String::new(),
+                cx: cg.cx,
+                cy: cg.cy,
+                angle: cg.angle,
+                base: BaseGradient {
+                    units: Units::UserSpaceOnUse,
+                    transform,
+                    spread_method: cg.spread_method,
+                    color_interpolation: cg.color_interpolation,
+                    stops: cg.stops.clone(),
+                },
+            }))
+        }
         Paint::Pattern(ref patt) => {
             let mut transform = patt.transform;
             transform.prepend(&ts);
@@ -912,6 +1005,7 @@ fn paint_server_to_user_space_on_use(paint: Paint, bbox: PathBbox) -> Option<Pai
                 transform,
                 rect: patt.rect,
                 view_box: patt.view_box,
+                overflow: patt.overflow,
                 root: patt.root.clone().make_deep_copy(),
             }))
         }
@@ -1071,7 +1165,10 @@ impl<'a> Iterator for GlyphClusters<'a> {
 /// but not the text layouting. So all clusters are in the 0x0 position.
 fn outline_chunk(
     chunk: &TextChunk,
+    direction: TextDirection,
+    bidi_override: bool,
     fonts_cache: &FontsCache,
+    shaper: &dyn TextShaper,
     fontdb: &fontdb::Database,
 ) -> Vec<OutlinedCluster> {
     let mut glyphs = Vec::new();
@@ -1084,8 +1181,10 @@ fn outline_chunk(
         let tmp_glyphs = shape_text(
             &chunk.text,
             font,
-            span.small_caps,
-            span.apply_kerning,
+            span,
+            direction,
+            bidi_override,
+            shaper,
             fontdb,
         );
 
@@ -1130,12 +1229,22 @@ fn outline_chunk(
 fn shape_text(
     text: &str,
     font: Rc<ResolvedFont>,
-    small_caps: bool,
-    apply_kerning: bool,
+    span: &TextSpan,
+    direction: TextDirection,
+    bidi_override: bool,
+    shaper: &dyn TextShaper,
     fontdb: &fontdb::Database,
 ) -> Vec<Glyph> {
-    let mut glyphs = shape_text_with_font(text, font.clone(), small_caps, apply_kerning, fontdb)
-        .unwrap_or_default();
+    let mut glyphs = shape_text_with_font(
+        text,
+        font.clone(),
+        span,
+        direction,
+        bidi_override,
+        shaper,
+        fontdb,
+    )
+    .unwrap_or_default();
 
     // Remember all fonts used for shaping.
     let mut used_fonts = vec![font.id];
@@ -1160,8 +1269,10 @@ fn shape_text(
             let fallback_glyphs = shape_text_with_font(
                 text,
                 fallback_font.clone(),
-                small_caps,
-                apply_kerning,
+                span,
+                direction,
+                bidi_override,
+                shaper,
                 fontdb,
             )
             .unwrap_or_default();
@@ -1211,81 +1322,136 @@ fn shape_text(
     glyphs
 }
 
-/// Converts a text into a list of glyph IDs.
-///
-/// This function will do the BIDI reordering and text shaping.
+/// Converts a text into a list of glyphs using the specified shaper.
 fn shape_text_with_font(
     text: &str,
     font: Rc<ResolvedFont>,
-    small_caps: bool,
-    apply_kerning: bool,
+    span: &TextSpan,
+    direction: TextDirection,
+    bidi_override: bool,
+    shaper: &dyn TextShaper,
     fontdb: &fontdb::Database,
 ) -> Option<Vec<Glyph>> {
-    fontdb.with_face_data(font.id, |font_data, face_index| -> Option<Vec<Glyph>> {
-        let rb_font = rustybuzz::Face::from_slice(font_data, face_index)?;
+    let shaped = shaper.shape(text, font.id, span, direction, bidi_override, fontdb)?;
 
-        let bidi_info = unicode_bidi::BidiInfo::new(text, Some(unicode_bidi::Level::ltr()));
-        let paragraph = &bidi_info.paragraphs[0];
-        let line = paragraph.range.clone();
+    let mut glyphs = Vec::with_capacity(shaped.len());
+    for glyph in shaped {
+        // External shapers are not trusted to produce valid clusters.
+        if glyph.cluster >= text.len() || !text.is_char_boundary(glyph.cluster) {
+            log::warn!("Text shaping produced an invalid cluster.");
+            return None;
+        }
 
-        let mut glyphs = Vec::new();
+        glyphs.push(Glyph {
+            byte_idx: ByteIndex::new(glyph.cluster),
+            id: GlyphId(glyph.id),
+            dx: glyph.x_offset,
+            dy: glyph.y_offset,
+            width: glyph.x_advance,
+            font: font.clone(),
+        });
+    }
 
-        let (levels, runs) = bidi_info.visual_runs(paragraph, line);
-        for run in runs.iter() {
-            let sub_text = &text[run.clone()];
-            if sub_text.is_empty() {
-                continue;
-            }
+    Some(glyphs)
+}
+
+impl TextShaper for DefaultShaper {
+    /// Converts a text into a list of glyph IDs.
+    ///
+    /// This function will do the BIDI reordering and text shaping.
+    fn shape(
+        &self,
+        text: &str,
+        font: ID,
+        span: &TextSpan,
+        direction: TextDirection,
+        bidi_override: bool,
+        fontdb: &fontdb::Database,
+    ) -> Option<Vec<ShapedGlyph>> {
+        fontdb.with_face_data(font, |font_data, face_index| -> Option<Vec<ShapedGlyph>> {
+            let rb_font = rustybuzz::Face::from_slice(font_data, face_index)?;
+
+            let base_level = match direction {
+                TextDirection::LeftToRight => unicode_bidi::Level::ltr(),
+                TextDirection::RightToLeft => unicode_bidi::Level::rtl(),
+            };
 
-            let hb_direction = if levels[run.start].is_rtl() {
-                rustybuzz::Direction::RightToLeft
+            let (levels, runs) = if bidi_override {
+                let runs = std::iter::once(0..text.len()).collect();
+                (vec![base_level; text.len()], runs)
             } else {
-                rustybuzz::Direction::LeftToRight
+                let bidi_info = unicode_bidi::BidiInfo::new(text, Some(base_level));
+                let paragraph = &bidi_info.paragraphs[0];
+                let line = paragraph.range.clone();
+                bidi_info.visual_runs(paragraph, line)
             };
 
-            let mut buffer = rustybuzz::UnicodeBuffer::new();
-            buffer.push_str(sub_text);
-            buffer.set_direction(hb_direction);
-
-            let mut features = Vec::new();
-            if small_caps {
-                features.push(rustybuzz::Feature::new(
-                    rustybuzz::Tag::from_bytes(b"smcp"),
-                    1,
-                    ..,
-                ));
-            }
+            let mut glyphs = Vec::new();
 
-            if !apply_kerning {
-                features.push(rustybuzz::Feature::new(
-                    rustybuzz::Tag::from_bytes(b"kern"),
-                    0,
-                    ..,
-                ));
-            }
+            for run in runs.iter() {
+                let sub_text = &text[run.clone()];
+                if sub_text.is_empty() {
+                    continue;
+                }
 
-            let output = rustybuzz::shape(&rb_font, &features, buffer);
+                let hb_direction = if levels[run.start].is_rtl() {
+                    rustybuzz::Direction::RightToLeft
+                } else {
+                    rustybuzz::Direction::LeftToRight
+                };
+
+                let mut buffer = rustybuzz::UnicodeBuffer::new();
+                buffer.push_str(sub_text);
+                buffer.set_direction(hb_direction);
+
+                let mut features = Vec::new();
+                if span.small_caps {
+                    features.push(rustybuzz::Feature::new(
+                        rustybuzz::Tag::from_bytes(b"smcp"),
+                        1,
+                        ..,
+                    ));
+                }
+
+                if !span.apply_kerning {
+                    features.push(rustybuzz::Feature::new(
+                        rustybuzz::Tag::from_bytes(b"kern"),
+                        0,
+                        ..,
+                    ));
+                }
 
-            let positions = output.glyph_positions();
-            let infos = output.glyph_infos();
+                // Explicit features go last, so they can override the ones above.
+                for feature in &span.font_features {
+                    features.push(rustybuzz::Feature::new(
+                        rustybuzz::Tag::from_bytes(&feature.tag),
+                        feature.value,
+                        ..,
+                    ));
+                }
+
+                let output = rustybuzz::shape(&rb_font, &features, buffer);
+
+                let positions = output.glyph_positions();
+                let infos = output.glyph_infos();
 
-            for (pos, info) in positions.iter().zip(infos) {
-                let idx = run.start + info.cluster as usize;
-                debug_assert!(text.get(idx..).is_some());
+                for (pos, info) in positions.iter().zip(infos) {
+                    let idx = run.start + info.cluster as usize;
+                    debug_assert!(text.get(idx..).is_some());
 
-                glyphs.push(Glyph {
-                    byte_idx: ByteIndex::new(idx),
-                    id: GlyphId(info.glyph_id as u16),
-                    dx: pos.x_offset,
-                    dy: pos.y_offset,
-                    width: pos.x_advance,
-                    font: font.clone(),
-                });
+                    glyphs.push(ShapedGlyph {
+                        id: info.glyph_id as u16,
+                        cluster: idx,
+                        x_offset: pos.x_offset,
+                        y_offset: pos.y_offset,
+                        x_advance: pos.x_advance,
+                    });
+                }
             }
-        }
 
-        Some(glyphs)
-    })?
+            Some(glyphs)
+        })?
+    }
 }
 
 /// Outlines a glyph cluster.
@@ -1413,6 +1579,7 @@ fn resolve_clusters_positions(
     pos_list: &[CharacterPosition],
     rotate_list: &[f64],
     writing_mode: WritingMode,
+    direction: TextDirection,
     ts: Transform,
     fonts_cache: &FontsCache,
     clusters: &mut [OutlinedCluster],
@@ -1424,6 +1591,7 @@ fn resolve_clusters_positions(
             pos_list,
             rotate_list,
             writing_mode,
+            direction,
             clusters,
         ),
         TextFlow::Path(ref path) => resolve_clusters_positions_path(
@@ -1433,6 +1601,7 @@ fn resolve_clusters_positions(
             pos_list,
             rotate_list,
             writing_mode,
+            direction,
             ts,
             fonts_cache,
             clusters,
@@ -1446,9 +1615,15 @@ fn resolve_clusters_positions_horizontal(
     pos_list: &[CharacterPosition],
     rotate_list: &[f64],
     writing_mode: WritingMode,
+    direction: TextDirection,
     clusters: &mut [OutlinedCluster],
 ) -> (f64, f64) {
-    let mut x = process_anchor(chunk.anchor, clusters_length(clusters));
+    let mut x = process_anchor(
+        chunk.anchor,
+        writing_mode,
+        direction,
+        clusters_length(clusters),
+    );
     let mut y = 0.0;
 
     for cluster in clusters {
@@ -1486,6 +1661,7 @@ fn resolve_clusters_positions_path(
     pos_list: &[CharacterPosition],
     rotate_list: &[f64],
     writing_mode: WritingMode,
+    direction: TextDirection,
     ts: Transform,
     fonts_cache: &FontsCache,
     clusters: &mut [OutlinedCluster],
@@ -1502,8 +1678,14 @@ fn resolve_clusters_positions_path(
         WritingMode::TopToBottom => chunk.y.unwrap_or(0.0),
     };
 
-    let start_offset =
-        chunk_offset + path.start_offset + process_anchor(chunk.anchor, clusters_length(clusters));
+    let start_offset = chunk_offset
+        + path.start_offset
+        + process_anchor(
+            chunk.anchor,
+            writing_mode,
+            direction,
+            clusters_length(clusters),
+        );
 
     let normals = collect_normals(
         chunk,
@@ -1577,7 +1759,26 @@ fn clusters_length(clusters: &[OutlinedCluster]) -> f64 {
     clusters.iter().fold(0.0, |w, cluster| w + cluster.advance)
 }
 
-fn process_anchor(a: TextAnchor, text_width: f64) -> f64 {
+fn process_anchor(
+    a: TextAnchor,
+    writing_mode: WritingMode,
+    direction: TextDirection,
+    text_width: f64,
+) -> f64 {
+    // In the right-to-left text, the start is on the right side.
+    // `direction` in the vertical writing mode is undefined,
+    // so we keep the top-to-bottom order there.
+    // See https://github.com/w3c/svgwg/issues/618
+    let a = match (a, writing_mode, direction) {
+        (TextAnchor::Start, WritingMode::LeftToRight, TextDirection::RightToLeft) => {
+            TextAnchor::End
+        }
+        (TextAnchor::End, WritingMode::LeftToRight, TextDirection::RightToLeft) => {
+            TextAnchor::Start
+        }
+        _ => a,
+    };
+
     match a {
         TextAnchor::Start => 0.0, // Nothing.
         TextAnchor::Middle => -text_width / 2.0,
@@ -7,12 +7,16 @@
 use strict_num::PositiveF64;
 use svgtypes::AspectRatio;
 
-use crate::{BlendMode, Color, ImageRendering, Node, NonZeroF64, Opacity, Point, Rect, Units};
+pub use crate::ColorInterpolation;
+use crate::{
+    BlendMode, Color, ImageRendering, Node, NonZeroF64, Opacity, Point, Rect, Transform, Units,
+};
 
 /// A filter element.
 ///
 /// `filter` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Filter {
     /// Element's ID.
     ///
@@ -35,12 +39,101 @@ pub struct Filter {
     /// `x`, `y`, `width` and `height` in the SVG.
     pub rect: Rect,
 
+    /// Color interpolation mode of the `filter` element.
+    ///
+    /// Each primitive has its own resolved mode, which should be used for rendering.
+    ///
+    /// `color-interpolation-filters` in the SVG.
+    pub color_interpolation: ColorInterpolation,
+
     /// A list of filter primitives.
     pub primitives: Vec<Primitive>,
 }
 
+impl Filter {
+    /// Returns the filter region in the coordinate system defined by `ts`.
+    ///
+    /// `bbox` is the object bounding box of the filtered element.
+    /// When `units` is `ObjectBoundingBox` and `bbox` is `None`
+    /// (e.g. the element has a zero width or height), the filter is invalid
+    /// and `None` will be returned.
+    ///
+    /// The default `-10% -10% 120% 120%` region is already resolved by the parser
+    /// and stored in `rect`.
+    pub fn region(&self, bbox: Option<Rect>, ts: Transform) -> Option<Rect> {
+        let mut region_ts = ts;
+        region_ts.append(&self.units.bbox_transform(bbox)?);
+
+        self.rect.transform(&region_ts)
+    }
+
+    /// Returns primitives in the evaluation order, with resolved inputs.
+    ///
+    /// A `Reference` input is resolved to the closest preceding primitive
+    /// with the same `result`. Like in SVG, forward and self references are not allowed
+    /// and will be returned as `ResolvedInput::Unresolved`, therefore the result
+    /// is always acyclic and primitives can be evaluated in the returned order.
+    pub fn primitives_in_topological_order(&self) -> Vec<ResolvedPrimitive<'_>> {
+        let mut list = Vec::with_capacity(self.primitives.len());
+        for (index, primitive) in self.primitives.iter().enumerate() {
+            let inputs = primitive
+                .kind
+                .inputs()
+                .into_iter()
+                .map(|input| match input {
+                    Input::Reference(ref name) => self.primitives[..index]
+                        .iter()
+                        .rposition(|p| p.result == *name)
+                        .map(ResolvedInput::Primitive)
+                        .unwrap_or(ResolvedInput::Unresolved(name)),
+                    _ => ResolvedInput::Source(input),
+                })
+                .collect();
+
+            list.push(ResolvedPrimitive {
+                index,
+                primitive,
+                inputs,
+            });
+        }
+
+        list
+    }
+}
+
+/// A filter primitive with resolved inputs.
+///
+/// Created by [`Filter::primitives_in_topological_order`].
+#[derive(Clone, Debug)]
+pub struct ResolvedPrimitive<'a> {
+    /// Primitive index in `Filter::primitives`.
+    pub index: usize,
+
+    /// The primitive itself.
+    pub primitive: &'a Primitive,
+
+    /// Resolved inputs, in the same order as returned by `Kind::inputs`.
+    pub inputs: Vec<ResolvedInput<'a>>,
+}
+
+/// A resolved filter primitive input.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ResolvedInput<'a> {
+    /// A non-reference input, like `SourceGraphic`.
+    Source(&'a Input),
+
+    /// A result of a primitive with the specified index in `Filter::primitives`.
+    Primitive(usize),
+
+    /// A reference to an unknown or not yet evaluated result.
+    ///
+    /// Renderers usually fallback to `SourceGraphic` in this case.
+    Unresolved(&'a str),
+}
+
 /// A filter primitive element.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Primitive {
     /// `x` coordinate of the filter subregion.
     pub x: Option<f64>,
@@ -71,6 +164,7 @@ pub struct Primitive {
 /// A filter kind.
 #[allow(missing_docs)]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Kind {
     Blend(Blend),
     ColorMatrix(ColorMatrix),
@@ -92,6 +186,31 @@ pub enum Kind {
 }
 
 impl Kind {
+    /// Returns all inputs of the filter primitive.
+    ///
+    /// `Blend`, `Composite` and `DisplacementMap` return `in` and `in2`, in that order.
+    pub fn inputs(&self) -> Vec<&Input> {
+        match self {
+            Kind::Blend(ref fe) => vec![&fe.input1, &fe.input2],
+            Kind::ColorMatrix(ref fe) => vec![&fe.input],
+            Kind::ComponentTransfer(ref fe) => vec![&fe.input],
+            Kind::Composite(ref fe) => vec![&fe.input1, &fe.input2],
+            Kind::ConvolveMatrix(ref fe) => vec![&fe.input],
+            Kind::DiffuseLighting(ref fe) => vec![&fe.input],
+            Kind::DisplacementMap(ref fe) => vec![&fe.input1, &fe.input2],
+            Kind::DropShadow(ref fe) => vec![&fe.input],
+            Kind::Flood(_) => Vec::new(),
+            Kind::GaussianBlur(ref fe) => vec![&fe.input],
+            Kind::Image(_) => Vec::new(),
+            Kind::Merge(ref fe) => fe.inputs.iter().collect(),
+            Kind::Morphology(ref fe) => vec![&fe.input],
+            Kind::Offset(ref fe) => vec![&fe.input],
+            Kind::SpecularLighting(ref fe) => vec![&fe.input],
+            Kind::Tile(ref fe) => vec![&fe.input],
+            Kind::Turbulence(_) => Vec::new(),
+        }
+    }
+
     /// Checks that `FilterKind` has a specific input.
     pub fn has_input(&self, input: &Input) -> bool {
         match self {
@@ -119,6 +238,7 @@ impl Kind {
 /// Identifies input for a filter primitive.
 #[allow(missing_docs)]
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Input {
     SourceGraphic,
     SourceAlpha,
@@ -129,24 +249,11 @@ pub enum Input {
     Reference(String),
 }
 
-/// A color interpolation mode.
-#[allow(missing_docs)]
-#[derive(Clone, Copy, PartialEq, Debug)]
-pub enum ColorInterpolation {
-    SRGB,
-    LinearRGB,
-}
-
-impl Default for ColorInterpolation {
-    fn default() -> Self {
-        ColorInterpolation::LinearRGB
-    }
-}
-
 /// A blend filter primitive.
 ///
 /// `feBlend` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Blend {
     /// Identifies input for the given filter primitive.
     ///
@@ -168,6 +275,7 @@ pub struct Blend {
 ///
 /// `feColorMatrix` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColorMatrix {
     /// Identifies input for the given filter primitive.
     ///
@@ -182,9 +290,11 @@ pub struct ColorMatrix {
 
 /// A color matrix filter primitive kind.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum ColorMatrixKind {
     Matrix(Vec<f64>), // Guarantee to have 20 numbers.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::positive_f64"))]
     Saturate(PositiveF64),
     HueRotate(f64),
     LuminanceToAlpha,
@@ -203,6 +313,7 @@ impl Default for ColorMatrixKind {
 ///
 /// `feComponentTransfer` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComponentTransfer {
     /// Identifies input for the given filter primitive.
     ///
@@ -226,6 +337,7 @@ pub struct ComponentTransfer {
 ///
 /// <https://www.w3.org/TR/SVG11/filters.html#transferFuncElements>
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TransferFunction {
     /// Keeps a component as is.
     Identity,
@@ -257,6 +369,7 @@ pub enum TransferFunction {
 ///
 /// `feComposite` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Composite {
     /// Identifies input for the given filter primitive.
     ///
@@ -277,6 +390,7 @@ pub struct Composite {
 /// An images compositing operation.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CompositeOperator {
     Over,
     In,
@@ -290,6 +404,7 @@ pub enum CompositeOperator {
 ///
 /// `feConvolveMatrix` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConvolveMatrix {
     /// Identifies input for the given filter primitive.
     ///
@@ -324,6 +439,7 @@ pub struct ConvolveMatrix {
 ///
 /// Used primarily by [`ConvolveMatrix`].
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConvolveMatrixData {
     /// Returns a matrix's X target.
     ///
@@ -381,6 +497,7 @@ impl ConvolveMatrixData {
 /// An edges processing mode.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EdgeMode {
     None,
     Duplicate,
@@ -391,6 +508,7 @@ pub enum EdgeMode {
 ///
 /// `feDisplacementMap` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DisplacementMap {
     /// Identifies input for the given filter primitive.
     ///
@@ -421,6 +539,7 @@ pub struct DisplacementMap {
 /// A color channel.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ColorChannel {
     R,
     G,
@@ -434,6 +553,7 @@ pub enum ColorChannel {
 ///
 /// `feDropShadow` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DropShadow {
     /// Identifies input for the given filter primitive.
     ///
@@ -449,11 +569,13 @@ pub struct DropShadow {
     /// A standard deviation along the X-axis.
     ///
     /// `stdDeviation` in the SVG.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::positive_f64"))]
     pub std_dev_x: PositiveF64,
 
     /// A standard deviation along the Y-axis.
     ///
     /// `stdDeviation` in the SVG.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::positive_f64"))]
     pub std_dev_y: PositiveF64,
 
     /// A flood color.
@@ -464,13 +586,33 @@ pub struct DropShadow {
     /// A flood opacity.
     ///
     /// `flood-opacity` in the SVG.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::normalized_f64"))]
     pub opacity: Opacity,
 }
 
+impl DropShadow {
+    /// Returns shadow color with `opacity` folded in as a straight (non-premultiplied) alpha.
+    ///
+    /// Uses the same conversion as [`Stop::rgba`](crate::Stop::rgba).
+    #[inline]
+    pub fn straight_rgba(&self) -> [u8; 4] {
+        crate::straight_rgba(self.color, self.opacity)
+    }
+
+    /// Returns shadow color with `opacity` folded in and premultiplied.
+    ///
+    /// Uses the same conversion as [`Stop::premultiplied_rgba`](crate::Stop::premultiplied_rgba).
+    #[inline]
+    pub fn premultiplied_rgba(&self) -> [u8; 4] {
+        crate::premultiplied_rgba(self.color, self.opacity)
+    }
+}
+
 /// A flood filter primitive.
 ///
 /// `feFlood` element in the SVG.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Flood {
     /// A flood color.
     ///
@@ -480,13 +622,33 @@ pub struct Flood {
     /// A flood opacity.
     ///
     /// `flood-opacity` in the SVG.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::normalized_f64"))]
     pub opacity: Opacity,
 }
 
+impl Flood {
+    /// Returns flood color with `opacity` folded in as a straight (non-premultiplied) alpha.
+    ///
+    /// Uses the same conversion as [`Stop::rgba`](crate::Stop::rgba).
+    #[inline]
+    pub fn straight_rgba(&self) -> [u8; 4] {
+        crate::straight_rgba(self.color, self.opacity)
+    }
+
+    /// Returns flood color with `opacity` folded in and premultiplied.
+    ///
+    /// Uses the same conversion as [`Stop::premultiplied_rgba`](crate::Stop::premultiplied_rgba).
+    #[inline]
+    pub fn premultiplied_rgba(&self) -> [u8; 4] {
+        crate::premultiplied_rgba(self.color, self.opacity)
+    }
+}
+
 /// A Gaussian blur filter primitive.
 ///
 /// `feGaussianBlur` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GaussianBlur {
     /// Identifies input for the given filter primitive.
     ///
@@ -496,11 +658,13 @@ pub struct GaussianBlur {
     /// A standard deviation along the X-axis.
     ///
     /// `stdDeviation` in the SVG.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::positive_f64"))]
     pub std_dev_x: PositiveF64,
 
     /// A standard deviation along the Y-axis.
     ///
     /// `stdDeviation` in the SVG.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::positive_f64"))]
     pub std_dev_y: PositiveF64,
 }
 
@@ -508,8 +672,10 @@ pub struct GaussianBlur {
 ///
 /// `feImage` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Image {
     /// Value of the `preserveAspectRatio` attribute.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::AspectRatioDef"))]
     pub aspect: AspectRatio,
 
     /// Rendering method.
@@ -523,6 +689,7 @@ pub struct Image {
 
 /// Kind of the `feImage` data.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ImageKind {
     /// An image data.
     Image(crate::ImageKind),
@@ -531,6 +698,7 @@ pub enum ImageKind {
     ///
     /// Isn't inside a dummy group like clip, mask and pattern because
     /// `feImage` can reference only a single element.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::node"))]
     Use(Node),
 }
 
@@ -538,6 +706,7 @@ pub enum ImageKind {
 ///
 /// `feDiffuseLighting` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DiffuseLighting {
     /// Identifies input for the given filter primitive.
     ///
@@ -567,6 +736,7 @@ pub struct DiffuseLighting {
 ///
 /// `feSpecularLighting` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpecularLighting {
     /// Identifies input for the given filter primitive.
     ///
@@ -602,6 +772,7 @@ pub struct SpecularLighting {
 /// A light source kind.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LightSource {
     DistantLight(DistantLight),
     PointLight(PointLight),
@@ -612,6 +783,7 @@ pub enum LightSource {
 ///
 /// `feDistantLight` element in the SVG.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DistantLight {
     /// Direction angle for the light source on the XY plane (clockwise),
     /// in degrees from the x axis.
@@ -629,6 +801,7 @@ pub struct DistantLight {
 ///
 /// `fePointLight` element in the SVG.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PointLight {
     /// X location for the light source.
     ///
@@ -650,6 +823,7 @@ pub struct PointLight {
 ///
 /// `feSpotLight` element in the SVG.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpotLight {
     /// X location for the light source.
     ///
@@ -684,6 +858,7 @@ pub struct SpotLight {
     /// Exponent value controlling the focus for the light source.
     ///
     /// `specularExponent` in the SVG.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::positive_f64"))]
     pub specular_exponent: PositiveF64,
 
     /// A limiting cone which restricts the region where the light is projected.
@@ -696,6 +871,7 @@ pub struct SpotLight {
 ///
 /// `feMerge` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Merge {
     /// List of input layers that should be merged.
     ///
@@ -707,6 +883,7 @@ pub struct Merge {
 ///
 /// `feMorphology` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Morphology {
     /// Identifies input for the given filter primitive.
     ///
@@ -723,6 +900,7 @@ pub struct Morphology {
     /// A value of zero disables the effect of the given filter primitive.
     ///
     /// `radius` in the SVG.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::positive_f64"))]
     pub radius_x: PositiveF64,
 
     /// A filter radius along the Y-axis.
@@ -730,12 +908,14 @@ pub struct Morphology {
     /// A value of zero disables the effect of the given filter primitive.
     ///
     /// `radius` in the SVG.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::positive_f64"))]
     pub radius_y: PositiveF64,
 }
 
 /// A morphology operation.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MorphologyOperator {
     Erode,
     Dilate,
@@ -745,6 +925,7 @@ pub enum MorphologyOperator {
 ///
 /// `feOffset` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Offset {
     /// Identifies input for the given filter primitive.
     ///
@@ -762,6 +943,7 @@ pub struct Offset {
 ///
 /// `feTile` element in the SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tile {
     /// Identifies input for the given filter primitive.
     ///
@@ -773,10 +955,12 @@ pub struct Tile {
 ///
 /// `feTurbulence` element in the SVG.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Turbulence {
     /// Identifies the base frequency for the noise function.
     ///
     /// `baseFrequency` in the SVG.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::positive_point"))]
     pub base_frequency: Point<PositiveF64>,
 
     /// Identifies the number of octaves for the noise function.
@@ -803,7 +987,128 @@ pub struct Turbulence {
 /// A turbulence kind for the `feTurbulence` filter.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TurbulenceKind {
     FractalNoise,
     Turbulence,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FuzzyEq;
+
+    fn filter(units: Units, rect: Rect) -> Filter {
+        Filter {
+            id: String::new(),
+            units,
+            primitive_units: Units::UserSpaceOnUse,
+            rect,
+            color_interpolation: ColorInterpolation::LinearRGB,
+            primitives: Vec::new(),
+        }
+    }
+
+    fn offset(input: Input, result: &str) -> Primitive {
+        Primitive {
+            x: None,
+            y: None,
+            width: None,
+            height: None,
+            color_interpolation: ColorInterpolation::LinearRGB,
+            result: result.to_string(),
+            kind: Kind::Offset(Offset {
+                input,
+                dx: 0.0,
+                dy: 0.0,
+            }),
+        }
+    }
+
+    #[test]
+    fn primitives_in_topological_order() {
+        let reference = |name: &str| Input::Reference(name.to_string());
+
+        let mut f = filter(
+            Units::UserSpaceOnUse,
+            Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+        );
+        f.primitives = vec![
+            offset(Input::SourceAlpha, "a"),
+            offset(reference("a"), "b"),
+            offset(reference("a"), "a"),
+            offset(reference("c"), "c"),
+            offset(reference("a"), "d"),
+            offset(reference("d"), "e"),
+        ];
+        f.primitives.push(Primitive {
+            kind: Kind::Merge(Merge {
+                inputs: vec![reference("b"), Input::SourceGraphic, reference("e")],
+            }),
+            ..offset(Input::SourceGraphic, "f")
+        });
+
+        let list = f.primitives_in_topological_order();
+        let indexes: Vec<_> = list.iter().map(|p| p.index).collect();
+        assert_eq!(indexes, vec![0, 1, 2, 3, 4, 5, 6]);
+
+        let inputs: Vec<_> = list.iter().map(|p| p.inputs.clone()).collect();
+        assert_eq!(
+            inputs,
+            vec![
+                vec![ResolvedInput::Source(&Input::SourceAlpha)],
+                vec![ResolvedInput::Primitive(0)],
+                vec![ResolvedInput::Primitive(0)],
+                // A self reference.
+                vec![ResolvedInput::Unresolved("c")],
+                // The closest `a`.
+                vec![ResolvedInput::Primitive(2)],
+                vec![ResolvedInput::Primitive(4)],
+                vec![
+                    ResolvedInput::Primitive(1),
+                    ResolvedInput::Source(&Input::SourceGraphic),
+                    ResolvedInput::Primitive(5),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn region() {
+        let bbox = Rect::new(10.0, 20.0, 100.0, 50.0).unwrap();
+        let ts = Transform::new_scale(2.0, 2.0);
+
+        let f = filter(
+            Units::ObjectBoundingBox,
+            Rect::new(-0.1, -0.1, 1.2, 1.2).unwrap(),
+        );
+        let r = f.region(Some(bbox), ts).unwrap();
+        assert!(r.fuzzy_eq(&Rect::new(0.0, 30.0, 240.0, 120.0).unwrap()));
+        assert!(f.region(None, ts).is_none());
+
+        let f = filter(
+            Units::UserSpaceOnUse,
+            Rect::new(5.0, 5.0, 10.0, 20.0).unwrap(),
+        );
+        let r = f.region(None, ts).unwrap();
+        assert!(r.fuzzy_eq(&Rect::new(10.0, 10.0, 20.0, 40.0).unwrap()));
+    }
+
+    #[test]
+    fn flood_rgba() {
+        let fe = Flood {
+            color: Color::new_rgb(255, 128, 0),
+            opacity: Opacity::new_clamped(0.5),
+        };
+        assert_eq!(fe.straight_rgba(), [255, 128, 0, 128]);
+        assert_eq!(fe.premultiplied_rgba(), [128, 64, 0, 128]);
+
+        let stop = crate::Stop {
+            offset: crate::StopOffset::new_clamped(0.0),
+            color: fe.color,
+            opacity: fe.opacity,
+        };
+        assert_eq!(fe.straight_rgba(), stop.rgba());
+        assert_eq!(fe.premultiplied_rgba(), stop.premultiplied_rgba());
+    }
+}
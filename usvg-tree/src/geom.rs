@@ -440,14 +440,23 @@ impl PathBbox {
         if !ts.is_default() {
             // TODO: remove allocation
             let path = PathData::from_rect(self.to_rect()?);
-            path.bbox_with_transform(*ts, None)
+            path.fill_bbox(*ts)
         } else {
             Some(*self)
         }
     }
 
     /// Converts into a [`Rect`].
+    ///
+    /// Unlike `Rect`, a `PathBbox` can have a zero width or height,
+    /// so this method returns `None` for bboxes of horizontal and vertical lines.
+    /// It also returns `None` for an unchanged [`PathBbox::new_bbox`] sentinel,
+    /// which doesn't represent any real area.
     pub fn to_rect(&self) -> Option<Rect> {
+        if self.fuzzy_eq(&PathBbox::new_bbox()) {
+            return None;
+        }
+
         Rect::new(self.x, self.y, self.width, self.height)
     }
 }
@@ -589,6 +598,29 @@ impl Rect {
         }
     }
 
+    /// Inflates the rect by `dx` on the left and right and by `dy` on the top and bottom.
+    ///
+    /// Negative values shrink the rect.
+    ///
+    /// Returns `None` when the rect was shrunk to a zero or negative width or height.
+    #[inline]
+    pub fn inflate(&self, dx: f64, dy: f64) -> Option<Self> {
+        Rect::new(
+            self.x - dx,
+            self.y - dy,
+            self.width + dx * 2.0,
+            self.height + dy * 2.0,
+        )
+    }
+
+    /// Inflates the rect by `margin` on each side.
+    ///
+    /// Shorthand for `Rect::inflate(margin, margin)`.
+    #[inline]
+    pub fn with_margin(&self, margin: f64) -> Option<Self> {
+        self.inflate(margin, margin)
+    }
+
     /// Checks that the rect contains a point.
     #[inline]
     pub fn contains(&self, x: f64, y: f64) -> bool {
@@ -603,6 +635,36 @@ impl Rect {
         true
     }
 
+    /// Checks that the rect fully contains another rect.
+    ///
+    /// Edges are compared using [`FuzzyEq`], so a rect contains itself.
+    #[inline]
+    pub fn contains_rect(&self, other: Rect) -> bool {
+        let ge = |a: f64, b: f64| a > b || a.fuzzy_eq(&b);
+        ge(other.left(), self.left())
+            && ge(other.top(), self.top())
+            && ge(self.right(), other.right())
+            && ge(self.bottom(), other.bottom())
+    }
+
+    /// Returns an intersection of two rects.
+    ///
+    /// Returns `None` when rects are disjoint or only touch each other,
+    /// since the resulting rect would have a zero width or height.
+    /// Edges are compared using [`FuzzyEq`].
+    pub fn intersection(&self, other: Rect) -> Option<Rect> {
+        let x1 = self.left().max(other.left());
+        let y1 = self.top().max(other.top());
+        let x2 = self.right().min(other.right());
+        let y2 = self.bottom().min(other.bottom());
+
+        if x1.fuzzy_eq(&x2) || y1.fuzzy_eq(&y2) {
+            return None;
+        }
+
+        Rect::new(x1, y1, x2 - x1, y2 - y1)
+    }
+
     /// Expands the `Rect` to the provided size.
     #[inline]
     pub fn expand(&self, r: Rect) -> Self {
@@ -636,8 +698,7 @@ impl Rect {
 
         if !ts.is_default() {
             let path = PathData::from_rect(*self);
-            path.bbox_with_transform(*ts, None)
-                .and_then(|r| r.to_rect())
+            path.fill_bbox(*ts).and_then(|r| r.to_rect())
         } else {
             Some(*self)
         }
@@ -870,8 +931,21 @@ impl std::fmt::Display for ScreenRect {
 
 /// Representation of the [`<transform>`] type.
 ///
+/// The components follow the SVG `matrix(a b c d e f)` order, which maps a point as:
+///
+/// ```text
+/// | a c e |   | x |
+/// | b d f | * | y |
+/// | 0 0 1 |   | 1 |
+/// ```
+///
+/// I.e. `a`, `b` are the first column and `e`, `f` are the translation.
+/// This is the same order used by `tiny-skia` (`sx ky kx sy tx ty`),
+/// `kurbo::Affine::new` and `lyon`'s `Transform::new` (`m11 m12 m21 m22 m31 m32`).
+///
 /// [`<transform>`]: https://www.w3.org/TR/SVG2/coords.html#InterfaceSVGTransform
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub struct Transform {
     pub a: f64,
@@ -888,6 +962,14 @@ impl From<svgtypes::Transform> for Transform {
     }
 }
 
+impl From<[f64; 6]> for Transform {
+    /// Constructs a transform from an `[a b c d e f]` array.
+    #[inline]
+    fn from(v: [f64; 6]) -> Self {
+        Transform::new(v[0], v[1], v[2], v[3], v[4], v[5])
+    }
+}
+
 impl Transform {
     /// Constructs a new transform.
     #[inline]
@@ -895,6 +977,20 @@ impl Transform {
         Transform { a, b, c, d, e, f }
     }
 
+    /// Returns transform components as an `[a b c d e f]` array.
+    ///
+    /// The inverse of `Transform::from([f64; 6])`.
+    #[inline]
+    pub fn to_array(&self) -> [f64; 6] {
+        [self.a, self.b, self.c, self.d, self.e, self.f]
+    }
+
+    /// Returns transform components as an `(a, b, c, d, e, f)` tuple.
+    #[inline]
+    pub fn as_tuple(&self) -> (f64, f64, f64, f64, f64, f64) {
+        (self.a, self.b, self.c, self.d, self.e, self.f)
+    }
+
     /// Constructs a new translate transform.
     #[inline]
     pub fn new_translate(x: f64, y: f64) -> Self {
@@ -1006,6 +1102,90 @@ impl Transform {
         (new_x, new_y)
     }
 
+    /// Applies transform to a point.
+    #[inline]
+    pub fn map_point(&self, p: Point<f64>) -> Point<f64> {
+        let (x, y) = self.apply(p.x, p.y);
+        Point::new(x, y)
+    }
+
+    /// Applies transform to a rectangle and returns a bounding box of the result.
+    ///
+    /// All four corners are transformed, therefore rotation and skew are handled correctly.
+    /// Unlike `Rect::transform`, doesn't require path bbox calculation.
+    ///
+    /// Returns `None` when the transformed rectangle has a zero width or height.
+    pub fn map_rect(&self, r: Rect) -> Option<Rect> {
+        let points = [
+            self.apply(r.left(), r.top()),
+            self.apply(r.right(), r.top()),
+            self.apply(r.right(), r.bottom()),
+            self.apply(r.left(), r.bottom()),
+        ];
+
+        let mut x1 = f64::MAX;
+        let mut y1 = f64::MAX;
+        let mut x2 = f64::MIN;
+        let mut y2 = f64::MIN;
+        for (x, y) in points {
+            x1 = x1.min(x);
+            y1 = y1.min(y);
+            x2 = x2.max(x);
+            y2 = y2.max(y);
+        }
+
+        Rect::new(x1, y1, x2 - x1, y2 - y1)
+    }
+
+    /// Returns an inverted transform.
+    ///
+    /// Returns `None` when the transform is not invertible,
+    /// i.e. when the absolute value of its determinant is smaller than `f64::EPSILON`.
+    pub fn invert(&self) -> Option<Transform> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.abs() < f64::EPSILON {
+            return None;
+        }
+
+        let inv_det = 1.0 / det;
+        Some(Transform {
+            a: self.d * inv_det,
+            b: -self.b * inv_det,
+            c: -self.c * inv_det,
+            d: self.a * inv_det,
+            e: (self.c * self.f - self.d * self.e) * inv_det,
+            f: (self.b * self.e - self.a * self.f) * inv_det,
+        })
+    }
+
+    /// Decomposes the transform into translation, rotation, skew and scale.
+    ///
+    /// See [`DecomposedTransform`] for the order of operations.
+    ///
+    /// Returns `None` when the transform is not invertible,
+    /// i.e. when the absolute value of its determinant is smaller than `f64::EPSILON`.
+    pub fn decompose(&self) -> Option<DecomposedTransform> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.abs() < f64::EPSILON {
+            return None;
+        }
+
+        let scale_x = (self.a * self.a + self.b * self.b).sqrt();
+        let cos = self.a / scale_x;
+        let sin = self.b / scale_x;
+        let scale_y = det / scale_x;
+        let skew = (cos * self.c + sin * self.d) / scale_y;
+
+        Some(DecomposedTransform {
+            translate_x: self.e,
+            translate_y: self.f,
+            rotation: sin.atan2(cos).to_degrees(),
+            skew_x: skew.atan().to_degrees(),
+            scale_x,
+            scale_y,
+        })
+    }
+
     /// Applies transform to selected coordinates.
     #[inline]
     pub fn apply_to(&self, x: &mut f64, y: &mut f64) {
@@ -1016,6 +1196,42 @@ impl Transform {
     }
 }
 
+/// A transform decomposed into separate operations.
+///
+/// The original transform can be restored by applying operations in the following order:
+/// `translate(translate_x translate_y) rotate(rotation) skewX(skew_x) scale(scale_x scale_y)`.
+///
+/// Angles are in degrees.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub struct DecomposedTransform {
+    pub translate_x: f64,
+    pub translate_y: f64,
+    pub rotation: f64,
+    pub skew_x: f64,
+    pub scale_x: f64,
+    pub scale_y: f64,
+}
+
+impl DecomposedTransform {
+    /// Composes operations back into a `Transform`.
+    pub fn to_transform(&self) -> Transform {
+        let mut ts = Transform::new_translate(self.translate_x, self.translate_y);
+        ts.rotate(self.rotation);
+        ts.append(&Transform::new(
+            1.0,
+            0.0,
+            self.skew_x.to_radians().tan(),
+            1.0,
+            0.0,
+            0.0,
+        ));
+        ts.scale(self.scale_x, self.scale_y);
+        ts
+    }
+}
+
 #[inline(never)]
 fn multiply(ts1: &Transform, ts2: &Transform) -> Transform {
     Transform {
@@ -1048,18 +1264,151 @@ impl FuzzyEq for Transform {
 
 /// View box.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ViewBox {
     /// Value of the `viewBox` attribute.
     pub rect: Rect,
 
     /// Value of the `preserveAspectRatio` attribute.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::AspectRatioDef"))]
     pub aspect: AspectRatio,
 }
 
+impl ViewBox {
+    /// Returns a transform that maps this view box onto a viewport of `img_size`.
+    ///
+    /// Honors `preserveAspectRatio`: uniform scaling for `meet`/`slice`
+    /// and non-uniform scaling when align is `none`.
+    pub fn to_transform(&self, img_size: Size) -> Transform {
+        crate::utils::view_box_to_transform(self.rect, self.aspect, img_size)
+    }
+
+    /// Pads the view box by at least `padding` on each side, while keeping its aspect ratio.
+    ///
+    /// The rect is scaled around its center, so the shorter side is padded by exactly `padding`
+    /// and the longer one proportionally more. Negative values shrink the view box.
+    ///
+    /// Returns `None` when the view box was shrunk to a zero or negative size.
+    pub fn with_padding(&self, padding: f64) -> Option<Self> {
+        let r = self.rect;
+        let scale = 1.0 + padding * 2.0 / r.width().min(r.height());
+        let dx = r.width() * (scale - 1.0) / 2.0;
+        let dy = r.height() * (scale - 1.0) / 2.0;
+        Some(ViewBox {
+            rect: r.inflate(dx, dy)?,
+            aspect: self.aspect,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn ts_approx_eq(ts1: Transform, ts2: Transform) -> bool {
+        let eq = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        eq(ts1.a, ts2.a)
+            && eq(ts1.b, ts2.b)
+            && eq(ts1.c, ts2.c)
+            && eq(ts1.d, ts2.d)
+            && eq(ts1.e, ts2.e)
+            && eq(ts1.f, ts2.f)
+    }
+
+    #[test]
+    fn map_point() {
+        let mut ts = Transform::new_translate(10.0, 20.0);
+        ts.scale(2.0, 3.0);
+        let p = ts.map_point(Point::new(1.0, 1.0));
+        assert!(p.x.fuzzy_eq(&12.0) && p.y.fuzzy_eq(&23.0));
+    }
+
+    #[test]
+    fn map_rect() {
+        let r = Rect::new(0.0, 0.0, 10.0, 20.0).unwrap();
+
+        let ts = Transform::new_translate(5.0, 5.0);
+        assert!(ts
+            .map_rect(r)
+            .unwrap()
+            .fuzzy_eq(&Rect::new(5.0, 5.0, 10.0, 20.0).unwrap()));
+
+        let ts = Transform::new_rotate(90.0);
+        let r2 = ts.map_rect(r).unwrap();
+        assert!((r2.x() + 20.0).abs() < 1e-9);
+        assert!(r2.y().abs() < 1e-9);
+        assert!((r2.width() - 20.0).abs() < 1e-9);
+        assert!((r2.height() - 10.0).abs() < 1e-9);
+
+        let ts = Transform::new(1.0, 0.0, 1.0, 1.0, 0.0, 0.0);
+        assert!(ts
+            .map_rect(r)
+            .unwrap()
+            .fuzzy_eq(&Rect::new(0.0, 0.0, 30.0, 20.0).unwrap()));
+
+        assert!(Transform::new_scale(0.0, 1.0).map_rect(r).is_none());
+    }
+
+    #[test]
+    fn transform_array() {
+        let ts = Transform::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0);
+        assert_eq!(ts.to_array(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(ts.as_tuple(), (1.0, 2.0, 3.0, 4.0, 5.0, 6.0));
+        assert_eq!(Transform::from(ts.to_array()), ts);
+
+        // `e` and `f` are the translation.
+        let ts = Transform::from([1.0, 0.0, 0.0, 1.0, 10.0, 20.0]);
+        assert_eq!(ts, Transform::new_translate(10.0, 20.0));
+    }
+
+    #[test]
+    fn invert_transform() {
+        let mut ts = Transform::new_translate(10.0, -20.0);
+        ts.rotate(30.0);
+        ts.scale(2.0, 0.5);
+        ts.append(&Transform::new(1.0, 0.0, 0.3, 1.0, 0.0, 0.0));
+
+        let inv = ts.invert().unwrap();
+
+        let mut ts1 = ts;
+        ts1.append(&inv);
+        assert!(ts_approx_eq(ts1, Transform::default()));
+
+        let mut ts2 = inv;
+        ts2.append(&ts);
+        assert!(ts_approx_eq(ts2, Transform::default()));
+    }
+
+    #[test]
+    fn decompose_transform() {
+        let mut ts = Transform::new_translate(10.0, -20.0);
+        ts.rotate(30.0);
+        ts.append(&Transform::new(1.0, 0.0, 0.5, 1.0, 0.0, 0.0));
+        ts.scale(2.0, -0.5);
+
+        let dts = ts.decompose().unwrap();
+        assert!((dts.translate_x - 10.0).abs() < 1e-9);
+        assert!((dts.translate_y + 20.0).abs() < 1e-9);
+        assert!((dts.rotation - 30.0).abs() < 1e-9);
+        assert!((dts.skew_x - 0.5f64.atan().to_degrees()).abs() < 1e-9);
+        assert!((dts.scale_x - 2.0).abs() < 1e-9);
+        assert!((dts.scale_y + 0.5).abs() < 1e-9);
+        assert!(ts_approx_eq(dts.to_transform(), ts));
+    }
+
+    #[test]
+    fn decompose_singular_transform() {
+        assert!(Transform::new_scale(1.0, 0.0).decompose().is_none());
+    }
+
+    #[test]
+    fn invert_singular_transform() {
+        assert!(Transform::new_scale(0.0, 1.0).invert().is_none());
+        assert!(Transform::new(1.0, 2.0, 2.0, 4.0, 5.0, 6.0)
+            .invert()
+            .is_none());
+    }
+
     #[test]
     fn bbox_transform_1() {
         let r = Rect::new(10.0, 20.0, 30.0, 40.0).unwrap();
@@ -1067,4 +1416,126 @@ mod tests {
             .bbox_transform(Rect::new(0.2, 0.3, 0.4, 0.5).unwrap())
             .fuzzy_eq(&Rect::new(4.2, 10.3, 12.0, 20.0).unwrap()));
     }
+
+    #[test]
+    fn view_box_to_transform() {
+        let size = Size::new(200.0, 100.0).unwrap();
+        let mut vb = ViewBox {
+            rect: Rect::new(10.0, 10.0, 100.0, 100.0).unwrap(),
+            aspect: AspectRatio::default(),
+        };
+        assert!(ts_approx_eq(
+            vb.to_transform(size),
+            Transform::new(1.0, 0.0, 0.0, 1.0, 40.0, -10.0)
+        ));
+
+        vb.aspect.slice = true;
+        assert!(ts_approx_eq(
+            vb.to_transform(size),
+            Transform::new(2.0, 0.0, 0.0, 2.0, -20.0, -70.0)
+        ));
+
+        vb.aspect = AspectRatio {
+            defer: false,
+            align: Align::XMinYMin,
+            slice: false,
+        };
+        assert!(ts_approx_eq(
+            vb.to_transform(size),
+            Transform::new(1.0, 0.0, 0.0, 1.0, -10.0, -10.0)
+        ));
+
+        vb.aspect.align = Align::None;
+        assert!(ts_approx_eq(
+            vb.to_transform(size),
+            Transform::new(2.0, 0.0, 0.0, 1.0, -20.0, -10.0)
+        ));
+    }
+
+    #[test]
+    fn rect_intersection() {
+        let r1 = Rect::new(0.0, 0.0, 10.0, 10.0).unwrap();
+        let r2 = Rect::new(5.0, 2.0, 10.0, 4.0).unwrap();
+        let r = r1.intersection(r2).unwrap();
+        assert!(r.fuzzy_eq(&Rect::new(5.0, 2.0, 5.0, 4.0).unwrap()));
+        assert!(r.fuzzy_eq(&r2.intersection(r1).unwrap()));
+
+        // Touching.
+        let r3 = Rect::new(10.0, 0.0, 5.0, 5.0).unwrap();
+        assert!(r1.intersection(r3).is_none());
+        let r4 = Rect::new(0.1 + 0.2, 0.0, 1.0, 1.0).unwrap();
+        let r5 = Rect::new(-1.0, 0.0, 1.3, 1.0).unwrap();
+        assert!(r4.intersection(r5).is_none());
+
+        // Disjoint.
+        let r6 = Rect::new(20.0, 20.0, 5.0, 5.0).unwrap();
+        assert!(r1.intersection(r6).is_none());
+    }
+
+    #[test]
+    fn rect_contains_rect() {
+        let r1 = Rect::new(0.0, 0.0, 10.0, 10.0).unwrap();
+        assert!(r1.contains_rect(r1));
+        assert!(r1.contains_rect(Rect::new(2.0, 2.0, 8.0, 3.0).unwrap()));
+        assert!(!r1.contains_rect(Rect::new(2.0, 2.0, 9.0, 3.0).unwrap()));
+        assert!(!r1.contains_rect(Rect::new(-1.0, 2.0, 5.0, 3.0).unwrap()));
+        assert!(!Rect::new(2.0, 2.0, 8.0, 3.0).unwrap().contains_rect(r1));
+    }
+
+    #[test]
+    fn rect_inflate() {
+        let r = Rect::new(10.0, 10.0, 20.0, 10.0).unwrap();
+        assert!(r
+            .inflate(2.0, 1.0)
+            .unwrap()
+            .fuzzy_eq(&Rect::new(8.0, 9.0, 24.0, 12.0).unwrap()));
+        assert!(r
+            .with_margin(-2.0)
+            .unwrap()
+            .fuzzy_eq(&Rect::new(12.0, 12.0, 16.0, 6.0).unwrap()));
+
+        // Collapsed.
+        assert!(r.with_margin(-5.0).is_none());
+        assert!(r.inflate(-20.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn view_box_with_padding() {
+        let vb = ViewBox {
+            rect: Rect::new(0.0, 0.0, 200.0, 100.0).unwrap(),
+            aspect: AspectRatio::default(),
+        };
+
+        let padded = vb.with_padding(10.0).unwrap();
+        assert!(padded
+            .rect
+            .fuzzy_eq(&Rect::new(-20.0, -10.0, 240.0, 120.0).unwrap()));
+        assert_eq!(padded.aspect, vb.aspect);
+
+        let shrunk = vb.with_padding(-10.0).unwrap();
+        assert!(shrunk
+            .rect
+            .fuzzy_eq(&Rect::new(20.0, 10.0, 160.0, 80.0).unwrap()));
+
+        assert!(vb.with_padding(-50.0).is_none());
+    }
+
+    #[test]
+    fn path_bbox_to_rect() {
+        let bbox = PathBbox::new(1.0, 2.0, 3.0, 4.0).unwrap();
+        assert!(bbox
+            .to_rect()
+            .unwrap()
+            .fuzzy_eq(&Rect::new(1.0, 2.0, 3.0, 4.0).unwrap()));
+
+        assert!(PathBbox::new(1.0, 2.0, 3.0, 0.0)
+            .unwrap()
+            .to_rect()
+            .is_none());
+        assert!(PathBbox::new(1.0, 2.0, 0.0, 4.0)
+            .unwrap()
+            .to_rect()
+            .is_none());
+        assert!(PathBbox::new_bbox().to_rect().is_none());
+    }
 }
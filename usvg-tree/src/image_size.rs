@@ -0,0 +1,161 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Raster image size detection.
+//!
+//! Only image headers are read. The actual image data is never decoded.
+
+use std::convert::TryInto;
+
+pub(crate) fn png_size(data: &[u8]) -> Option<(u32, u32)> {
+    if !data.starts_with(b"\x89PNG\r\n\x1a\n") || data.get(12..16)? != b"IHDR" {
+        return None;
+    }
+
+    non_zero(read_u32_be(data, 16)?, read_u32_be(data, 20)?)
+}
+
+pub(crate) fn gif_size(data: &[u8]) -> Option<(u32, u32)> {
+    if !data.starts_with(b"GIF87a") && !data.starts_with(b"GIF89a") {
+        return None;
+    }
+
+    non_zero(read_u16_le(data, 6)? as u32, read_u16_le(data, 8)? as u32)
+}
+
+pub(crate) fn jpeg_size(data: &[u8]) -> Option<(u32, u32)> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+
+    let mut pos = 2;
+    loop {
+        if *data.get(pos)? != 0xFF {
+            return None;
+        }
+
+        // Markers can be prefixed by any number of 0xFF fill bytes.
+        let mut marker = *data.get(pos + 1)?;
+        pos += 1;
+        while marker == 0xFF {
+            pos += 1;
+            marker = *data.get(pos)?;
+        }
+        pos += 1;
+
+        match marker {
+            // Standalone markers without a length.
+            0x01 | 0xD0..=0xD7 => continue,
+            // Start of scan or end of image before any frame header.
+            0xD9 | 0xDA => return None,
+            // Start of frame, excluding DHT, JPG and DAC.
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                let height = read_u16_be(data, pos + 3)?;
+                let width = read_u16_be(data, pos + 5)?;
+                return non_zero(width as u32, height as u32);
+            }
+            _ => {
+                let len = read_u16_be(data, pos)? as usize;
+                if len < 2 {
+                    return None;
+                }
+
+                pos += len;
+            }
+        }
+    }
+}
+
+pub(crate) fn webp_size(data: &[u8]) -> Option<(u32, u32)> {
+    if data.get(0..4)? != b"RIFF" || data.get(8..12)? != b"WEBP" {
+        return None;
+    }
+
+    match data.get(12..16)? {
+        b"VP8 " => {
+            let width = read_u16_le(data, 26)? & 0x3FFF;
+            let height = read_u16_le(data, 28)? & 0x3FFF;
+            non_zero(width as u32, height as u32)
+        }
+        b"VP8L" => {
+            if *data.get(20)? != 0x2F {
+                return None;
+            }
+
+            let bits = u32::from_le_bytes(data.get(21..25)?.try_into().ok()?);
+            non_zero((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1)
+        }
+        b"VP8X" => {
+            let width = read_u24_le(data, 24)? + 1;
+            let height = read_u24_le(data, 27)? + 1;
+            non_zero(width, height)
+        }
+        _ => None,
+    }
+}
+
+fn non_zero(width: u32, height: u32) -> Option<(u32, u32)> {
+    if width != 0 && height != 0 {
+        Some((width, height))
+    } else {
+        None
+    }
+}
+
+fn read_u16_be(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn read_u16_le(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn read_u24_le(data: &[u8], pos: usize) -> Option<u32> {
+    let d = data.get(pos..pos + 3)?;
+    Some(d[0] as u32 | (d[1] as u32) << 8 | (d[2] as u32) << 16)
+}
+
+fn read_u32_be(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn png() {
+        let mut data = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        data.extend_from_slice(&[0, 0, 1, 0x2C, 0, 0, 0, 200]);
+        assert_eq!(png_size(&data), Some((300, 200)));
+        assert_eq!(png_size(&data[..20]), None);
+    }
+
+    #[test]
+    fn gif() {
+        assert_eq!(gif_size(b"GIF89a\x10\x00\x20\x00"), Some((16, 32)));
+        assert_eq!(gif_size(b"GIF89a\x10\x00"), None);
+        assert_eq!(gif_size(b"GIF90a\x10\x00\x20\x00"), None);
+    }
+
+    #[test]
+    fn jpeg() {
+        let data = [
+            0xFF, 0xD8, // SOI
+            0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, // APP0
+            0xFF, 0xFF, 0xC2, 0x00, 0x11, 0x08, 0x00, 0x40, 0x00, 0x80, // SOF2
+        ];
+        assert_eq!(jpeg_size(&data), Some((128, 64)));
+        assert_eq!(jpeg_size(&data[..16]), None);
+        assert_eq!(jpeg_size(&[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x00]), None);
+        assert_eq!(jpeg_size(&[0xFF, 0xD8, 0xFF, 0xD9]), None);
+    }
+
+    #[test]
+    fn webp() {
+        let data = b"RIFF\x1a\x00\x00\x00WEBPVP8L\x0d\x00\x00\x00\x2f\x00\x00\x00\x10";
+        assert_eq!(webp_size(data), Some((1, 1)));
+        assert_eq!(webp_size(&data[..22]), None);
+    }
+}
@@ -18,12 +18,20 @@
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::derivable_impls)]
 
+mod colors;
 pub mod filter;
 mod geom;
+mod image_size;
+pub mod passes;
 mod pathdata;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod stroker;
 mod text;
 pub mod utils;
 
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -64,6 +72,7 @@ impl NonZeroF64 {
 /// An element units.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Units {
     UserSpaceOnUse,
     ObjectBoundingBox,
@@ -71,11 +80,33 @@ pub enum Units {
 
 // `Units` cannot have a default value, because it changes depending on an element.
 
+impl Units {
+    /// Returns a transform from this coordinate system to the user space.
+    ///
+    /// `ObjectBoundingBox` maps the unit square onto `bbox`,
+    /// while `UserSpaceOnUse` ignores `bbox` and returns an identity transform.
+    ///
+    /// Returns `None` when `ObjectBoundingBox` is used and `bbox` is not set,
+    /// i.e. the element has no bounding box and the bbox-relative value is invalid.
+    pub fn bbox_transform(self, bbox: Option<Rect>) -> Option<Transform> {
+        match self {
+            Units::UserSpaceOnUse => Some(Transform::default()),
+            Units::ObjectBoundingBox => bbox.map(Transform::from_bbox),
+        }
+    }
+}
+
 /// A visibility property.
 ///
 /// `visibility` attribute in the SVG.
+///
+/// For graphics elements `Collapse` has the same meaning as `Hidden`:
+/// the element is not rendered, but still remains in the tree.
+/// Unlike CSS tables, SVG doesn't remove collapsed elements from the layout,
+/// so a hidden or collapsed text span still occupies its space.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Visibility {
     Visible,
     Hidden,
@@ -92,6 +123,7 @@ impl Default for Visibility {
 ///
 /// `shape-rendering` attribute in the SVG.
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum ShapeRendering {
     OptimizeSpeed,
@@ -135,6 +167,7 @@ impl std::str::FromStr for ShapeRendering {
 /// `text-rendering` attribute in the SVG.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextRendering {
     OptimizeSpeed,
     OptimizeLegibility,
@@ -163,8 +196,12 @@ impl std::str::FromStr for TextRendering {
 /// An image rendering method.
 ///
 /// `image-rendering` attribute in the SVG.
+///
+/// The CSS `pixelated` and `crisp-edges` keywords are mapped onto `OptimizeSpeed`,
+/// which renderers are expected to draw using nearest-neighbor sampling.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ImageRendering {
     OptimizeQuality,
     OptimizeSpeed,
@@ -182,7 +219,7 @@ impl std::str::FromStr for ImageRendering {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "optimizeQuality" => Ok(ImageRendering::OptimizeQuality),
-            "optimizeSpeed" => Ok(ImageRendering::OptimizeSpeed),
+            "optimizeSpeed" | "pixelated" | "crisp-edges" => Ok(ImageRendering::OptimizeSpeed),
             _ => Err("invalid"),
         }
     }
@@ -193,6 +230,7 @@ impl std::str::FromStr for ImageRendering {
 /// `mix-blend-mode` attribute in the SVG.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BlendMode {
     Normal,
     Multiply,
@@ -218,11 +256,62 @@ impl Default for BlendMode {
     }
 }
 
+impl BlendMode {
+    /// Returns a `mix-blend-mode` value name.
+    pub fn to_svg_name(&self) -> &'static str {
+        match self {
+            BlendMode::Normal => "normal",
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+            BlendMode::Overlay => "overlay",
+            BlendMode::Darken => "darken",
+            BlendMode::Lighten => "lighten",
+            BlendMode::ColorDodge => "color-dodge",
+            BlendMode::ColorBurn => "color-burn",
+            BlendMode::HardLight => "hard-light",
+            BlendMode::SoftLight => "soft-light",
+            BlendMode::Difference => "difference",
+            BlendMode::Exclusion => "exclusion",
+            BlendMode::Hue => "hue",
+            BlendMode::Saturation => "saturation",
+            BlendMode::Color => "color",
+            BlendMode::Luminosity => "luminosity",
+        }
+    }
+}
+
+impl std::str::FromStr for BlendMode {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(BlendMode::Normal),
+            "multiply" => Ok(BlendMode::Multiply),
+            "screen" => Ok(BlendMode::Screen),
+            "overlay" => Ok(BlendMode::Overlay),
+            "darken" => Ok(BlendMode::Darken),
+            "lighten" => Ok(BlendMode::Lighten),
+            "color-dodge" => Ok(BlendMode::ColorDodge),
+            "color-burn" => Ok(BlendMode::ColorBurn),
+            "hard-light" => Ok(BlendMode::HardLight),
+            "soft-light" => Ok(BlendMode::SoftLight),
+            "difference" => Ok(BlendMode::Difference),
+            "exclusion" => Ok(BlendMode::Exclusion),
+            "hue" => Ok(BlendMode::Hue),
+            "saturation" => Ok(BlendMode::Saturation),
+            "color" => Ok(BlendMode::Color),
+            "luminosity" => Ok(BlendMode::Luminosity),
+            _ => Err("invalid"),
+        }
+    }
+}
+
 /// A spread method.
 ///
 /// `spreadMethod` attribute in the SVG.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SpreadMethod {
     Pad,
     Reflect,
@@ -235,8 +324,29 @@ impl Default for SpreadMethod {
     }
 }
 
+/// A color interpolation mode.
+///
+/// `color-interpolation` and `color-interpolation-filters` attributes in the SVG.
+///
+/// The default is `LinearRGB`, which is the default for filters.
+/// Gradients use `SRGB` by default.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorInterpolation {
+    SRGB,
+    LinearRGB,
+}
+
+impl Default for ColorInterpolation {
+    fn default() -> Self {
+        ColorInterpolation::LinearRGB
+    }
+}
+
 /// A generic gradient.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BaseGradient {
     /// Coordinate system units.
     ///
@@ -253,15 +363,95 @@ pub struct BaseGradient {
     /// `spreadMethod` in SVG.
     pub spread_method: SpreadMethod,
 
+    /// Color space in which stops should be interpolated.
+    ///
+    /// `color-interpolation` in SVG.
+    pub color_interpolation: ColorInterpolation,
+
     /// A list of `stop` elements.
     pub stops: Vec<Stop>,
 }
 
+impl BaseGradient {
+    /// Returns gradient color and opacity at the specified offset.
+    ///
+    /// Offsets outside the `0..1` range are resolved using `spread_method`.
+    /// Color and opacity are interpolated separately, without premultiplication.
+    /// Stops with the same offset produce a hard transition.
+    ///
+    /// Returns a transparent black when gradient has no stops.
+    pub fn color_at(&self, t: f64) -> (Color, Opacity) {
+        let t = if t.is_finite() { t } else { 0.0 };
+        let t = match self.spread_method {
+            SpreadMethod::Pad => t.clamp(0.0, 1.0),
+            SpreadMethod::Repeat => t - t.floor(),
+            SpreadMethod::Reflect => {
+                let t = t.rem_euclid(2.0);
+                if t > 1.0 {
+                    2.0 - t
+                } else {
+                    t
+                }
+            }
+        };
+
+        let idx = match self.stops.iter().position(|stop| stop.offset.get() > t) {
+            Some(idx) => idx,
+            None => {
+                return match self.stops.last() {
+                    Some(stop) => (stop.color, stop.opacity),
+                    None => (Color::black(), Opacity::ZERO),
+                };
+            }
+        };
+
+        let next = self.stops[idx];
+        let prev = match idx.checked_sub(1) {
+            Some(prev_idx) => self.stops[prev_idx],
+            None => return (next.color, next.opacity),
+        };
+
+        let k = (t - prev.offset.get()) / (next.offset.get() - prev.offset.get());
+        let color = prev.color.lerp(next.color, k);
+        let opacity = prev.opacity.get() + (next.opacity.get() - prev.opacity.get()) * k;
+
+        (color, Opacity::new_clamped(opacity))
+    }
+
+    /// Normalizes gradient stops.
+    ///
+    /// Each stop offset is raised to at least the previous one, as required by the SVG spec.
+    /// Offsets are already in the `0..1` range, since `StopOffset` is normalized.
+    /// Adjacent stops with the same offset, color and opacity are collapsed into one.
+    pub fn normalize(&mut self) {
+        let mut prev_offset = StopOffset::ZERO;
+        for stop in &mut self.stops {
+            if stop.offset < prev_offset {
+                stop.offset = prev_offset;
+            }
+
+            prev_offset = stop.offset;
+        }
+
+        self.stops.dedup_by(|stop, prev| {
+            stop.offset == prev.offset && stop.color == prev.color && stop.opacity == prev.opacity
+        });
+    }
+
+    /// Returns stop colors as premultiplied RGBA.
+    ///
+    /// See [`Stop::premultiplied_rgba`] for details.
+    pub fn stops_premultiplied(&self) -> Vec<[u8; 4]> {
+        self.stops.iter().map(|s| s.premultiplied_rgba()).collect()
+    }
+}
+
 /// A linear gradient.
 ///
 /// `linearGradient` element in SVG.
 #[allow(missing_docs)]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinearGradient {
     /// Element's ID.
     ///
@@ -291,6 +481,7 @@ impl std::ops::Deref for LinearGradient {
 /// `radialGradient` element in SVG.
 #[allow(missing_docs)]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RadialGradient {
     /// Element's ID.
     ///
@@ -300,6 +491,7 @@ pub struct RadialGradient {
 
     pub cx: f64,
     pub cy: f64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::positive_f64"))]
     pub r: PositiveF64,
     pub fx: f64,
     pub fy: f64,
@@ -316,6 +508,41 @@ impl std::ops::Deref for RadialGradient {
     }
 }
 
+/// A conic gradient.
+///
+/// Also known as a sweep gradient. SVG has no such element,
+/// therefore it can be created only manually.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConicGradient {
+    /// Element's ID.
+    ///
+    /// Can't be empty.
+    pub id: String,
+
+    /// Gradient center X coordinate.
+    pub cx: f64,
+
+    /// Gradient center Y coordinate.
+    pub cy: f64,
+
+    /// Gradient start angle in degrees.
+    ///
+    /// Zero angle points up and the gradient goes clockwise.
+    pub angle: f64,
+
+    /// Base gradient data.
+    pub base: BaseGradient,
+}
+
+impl std::ops::Deref for ConicGradient {
+    type Target = BaseGradient;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
 /// An alias to `NormalizedF64`.
 pub type StopOffset = NormalizedF64;
 
@@ -323,10 +550,12 @@ pub type StopOffset = NormalizedF64;
 ///
 /// `stop` element in SVG.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stop {
     /// Gradient stop offset.
     ///
     /// `offset` in SVG.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::normalized_f64"))]
     pub offset: StopOffset,
 
     /// Gradient stop color.
@@ -337,13 +566,70 @@ pub struct Stop {
     /// Gradient stop opacity.
     ///
     /// `stop-opacity` in SVG.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::normalized_f64"))]
     pub opacity: Opacity,
 }
 
+impl Stop {
+    /// Returns stop color with `opacity` folded in as a straight (non-premultiplied) alpha.
+    ///
+    /// Opacity is converted via `NormalizedF64::to_u8`, i.e. `opacity * 255`
+    /// rounded half up. This is the same conversion `resvg` uses.
+    #[inline]
+    pub fn rgba(&self) -> [u8; 4] {
+        straight_rgba(self.color, self.opacity)
+    }
+
+    /// Returns stop color with `opacity` folded in and premultiplied.
+    ///
+    /// Premultiplication is done in floating point and each channel
+    /// is rounded only once at the end.
+    #[inline]
+    pub fn premultiplied_rgba(&self) -> [u8; 4] {
+        premultiplied_rgba(self.color, self.opacity)
+    }
+}
+
+/// Folds `opacity` into `color` as a straight (non-premultiplied) alpha.
+pub(crate) fn straight_rgba(color: Color, opacity: Opacity) -> [u8; 4] {
+    [color.red, color.green, color.blue, opacity.to_u8()]
+}
+
+/// Folds `opacity` into `color` and premultiplies.
+///
+/// Shared by gradient stops and flood-like filter primitives,
+/// so they all round the same way.
+pub(crate) fn premultiplied_rgba(color: Color, opacity: Opacity) -> [u8; 4] {
+    let a = opacity.get();
+    let c = |v: u8| (v as f64 * a).round() as u8;
+    [c(color.red), c(color.green), c(color.blue), c(255)]
+}
+
+/// An `overflow` value.
+///
+/// `visible` and `auto` are represented as `Visible`,
+/// while `hidden` and `scroll` as `Hidden`.
+///
+/// `overflow` attribute in the SVG.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum Overflow {
+    Visible,
+    Hidden,
+}
+
+impl Default for Overflow {
+    fn default() -> Self {
+        Self::Hidden
+    }
+}
+
 /// A pattern element.
 ///
 /// `pattern` element in SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pattern {
     /// Element's ID.
     ///
@@ -375,12 +661,57 @@ pub struct Pattern {
     /// Pattern viewbox.
     pub view_box: Option<ViewBox>,
 
+    /// Content overflow.
+    ///
+    /// Content is clipped by the pattern tile when `Hidden`, which is the default.
+    /// Renderers decide how to handle `Visible`.
+    ///
+    /// `overflow` in SVG.
+    pub overflow: Overflow,
+
     /// Pattern children.
     ///
     /// The root node is always `Group`.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::node"))]
     pub root: Node,
 }
 
+impl Pattern {
+    /// Returns the first pattern tile in the user space of the referencing element,
+    /// without `transform`.
+    ///
+    /// `bbox` is the object bounding box of the referencing element.
+    /// Returns `None` when `units` is `ObjectBoundingBox` and `bbox` is not set.
+    pub fn tile_rect(&self, bbox: Option<Rect>) -> Option<Rect> {
+        self.rect.transform(&self.units.bbox_transform(bbox)?)
+    }
+
+    /// Returns a transform that maps pattern content to the first tile
+    /// in the user space of the referencing element.
+    ///
+    /// Combines `transform`, the tile position and either `view_box`
+    /// or `content_units`. As per SVG spec, `content_units` has no effect
+    /// when `view_box` is set.
+    ///
+    /// Returns `None` when `bbox` is required, but not set.
+    pub fn effective_tile_transform(&self, bbox: Option<Rect>) -> Option<Transform> {
+        let r = self.tile_rect(bbox)?;
+
+        let mut ts = self.transform;
+        ts.translate(r.x(), r.y());
+        if let Some(view_box) = self.view_box {
+            ts.append(&view_box.to_transform(r.size()));
+        } else if self.content_units == Units::ObjectBoundingBox {
+            // Unlike `Transform::from_bbox`, bbox `x` and `y` are ignored,
+            // since the tile is already positioned.
+            let bbox = bbox?;
+            ts.scale(bbox.width(), bbox.height());
+        }
+
+        Some(ts)
+    }
+}
+
 /// An alias to `NonZeroPositiveF64`.
 pub type StrokeWidth = NonZeroPositiveF64;
 
@@ -402,6 +733,19 @@ impl StrokeMiterlimit {
         StrokeMiterlimit(n)
     }
 
+    /// Creates a new `StrokeMiterlimit` value, clamping it to be >= 1.0.
+    ///
+    /// Unlike `new`, doesn't expect a valid value. Values below 1.0,
+    /// as well as NaN and infinite values, are replaced with 1.0.
+    #[inline]
+    pub fn clamp_min_1(n: f64) -> Self {
+        if n.is_finite() && n >= 1.0 {
+            StrokeMiterlimit(n)
+        } else {
+            StrokeMiterlimit(1.0)
+        }
+    }
+
     /// Returns an underlying value.
     #[inline]
     pub fn get(&self) -> f64 {
@@ -430,11 +774,24 @@ impl PartialEq for StrokeMiterlimit {
     }
 }
 
+impl PartialOrd for StrokeMiterlimit {
+    /// Compares values using the same fuzzy equality as `PartialEq`.
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if self == other {
+            Some(std::cmp::Ordering::Equal)
+        } else {
+            self.0.partial_cmp(&other.0)
+        }
+    }
+}
+
 /// A line cap.
 ///
 /// `stroke-linecap` attribute in the SVG.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LineCap {
     Butt,
     Round,
@@ -452,6 +809,7 @@ impl Default for LineCap {
 /// `stroke-linejoin` attribute in the SVG.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LineJoin {
     Miter,
     Round,
@@ -467,12 +825,18 @@ impl Default for LineJoin {
 /// A stroke style.
 #[allow(missing_docs)]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stroke {
     pub paint: Paint,
     pub dasharray: Option<Vec<f64>>,
     pub dashoffset: f32, // f32 and not f64 to reduce the struct size.
     pub miterlimit: StrokeMiterlimit,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::normalized_f64"))]
     pub opacity: Opacity,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_impl::non_zero_positive_f64")
+    )]
     pub width: StrokeWidth,
     pub linecap: LineCap,
     pub linejoin: LineJoin,
@@ -495,11 +859,42 @@ impl Default for Stroke {
     }
 }
 
+impl Stroke {
+    /// Converts the stroke of a path into a fillable outline.
+    ///
+    /// Takes width, caps, joins, miterlimit and dasharray into account.
+    /// Dashes are applied first, and the dashed path is stroked afterwards.
+    ///
+    /// Curves are flattened, so the outline consists of lines, except round joins and caps.
+    /// Paint and opacity are ignored.
+    ///
+    /// The returned path may self-intersect and must be filled using the `nonzero` rule.
+    pub fn to_path_outline(&self, path: &PathData) -> PathData {
+        stroker::stroke_to_path(path, self)
+    }
+
+    /// Returns the stroke width after applying the transform.
+    ///
+    /// The width is scaled by the geometric mean of the transform's scale factors,
+    /// i.e. by `sqrt(|a * d - b * c|)`, which is exact for translation, rotation
+    /// and uniform scale.
+    ///
+    /// A non-uniformly scaled or skewed stroke doesn't have a single width,
+    /// so the result is only an approximation in this case. E.g. for `scale(4 1)`
+    /// the actual width varies between `1x` and `4x` depending on the segment direction,
+    /// while `2x` will be returned.
+    pub fn effective_width(&self, ts: Transform) -> f64 {
+        let det = ts.a * ts.d - ts.b * ts.c;
+        self.width.get() * det.abs().sqrt()
+    }
+}
+
 /// A fill rule.
 ///
 /// `fill-rule` attribute in the SVG.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FillRule {
     NonZero,
     EvenOdd,
@@ -514,8 +909,10 @@ impl Default for FillRule {
 /// A fill style.
 #[allow(missing_docs)]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Fill {
     pub paint: Paint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::normalized_f64"))]
     pub opacity: Opacity,
     pub rule: FillRule,
 }
@@ -544,6 +941,7 @@ impl Default for Fill {
 
 /// A 8-bit RGB color.
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub struct Color {
     pub red: u8,
@@ -569,6 +967,168 @@ impl Color {
     pub fn white() -> Color {
         Color::new_rgb(255, 255, 255)
     }
+
+    /// Parses a color from a `#rgb` or `#rrggbb` hex string.
+    ///
+    /// The leading `#` is optional.
+    ///
+    /// Returns `None` on any other length, including `#rrggbbaa`,
+    /// since `Color` has no alpha channel.
+    pub fn from_hex(text: &str) -> Option<Color> {
+        let text = text.strip_prefix('#').unwrap_or(text);
+        if !text.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let hex = |i: usize, len: usize| u8::from_str_radix(&text[i..i + len], 16).ok();
+        match text.len() {
+            3 => {
+                // `#fff` is a short form of `#ffffff`.
+                let (r, g, b) = (hex(0, 1)?, hex(1, 1)?, hex(2, 1)?);
+                Some(Color::new_rgb(r * 17, g * 17, b * 17))
+            }
+            6 => Some(Color::new_rgb(hex(0, 2)?, hex(2, 2)?, hex(4, 2)?)),
+            _ => None,
+        }
+    }
+
+    /// Linearly interpolates between two colors.
+    ///
+    /// Each channel is interpolated separately in floating point and rounded.
+    /// `t` is clamped to the `0..1` range, where `0` is `self` and `1` is `other`.
+    pub fn lerp(&self, other: Color, t: f64) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Color::new_rgb(
+            lerp(self.red, other.red),
+            lerp(self.green, other.green),
+            lerp(self.blue, other.blue),
+        )
+    }
+
+    /// Moves the color toward white by `amount`.
+    ///
+    /// `amount` is clamped to the `0..1` range, where `1` produces white.
+    #[inline]
+    pub fn lighten(&self, amount: f64) -> Color {
+        self.lerp(Color::white(), amount)
+    }
+
+    /// Moves the color toward black by `amount`.
+    ///
+    /// `amount` is clamped to the `0..1` range, where `1` produces black.
+    #[inline]
+    pub fn darken(&self, amount: f64) -> Color {
+        self.lerp(Color::black(), amount)
+    }
+
+    /// Returns a perceptual distance between two colors.
+    ///
+    /// Uses the "redmean" weighted Euclidean distance in sRGB, where channel weights
+    /// depend on the average red value. It's much closer to the perceived difference
+    /// than a plain Euclidean distance, while still being cheap and not requiring
+    /// a conversion into a different color space, like Lab.
+    ///
+    /// Returns a value in the `0..~765` range, where `0` means equal colors.
+    pub fn distance(&self, other: Color) -> f64 {
+        let r_mean = (self.red as f64 + other.red as f64) / 2.0;
+        let dr = self.red as f64 - other.red as f64;
+        let dg = self.green as f64 - other.green as f64;
+        let db = self.blue as f64 - other.blue as f64;
+
+        ((2.0 + r_mean / 256.0) * dr * dr
+            + 4.0 * dg * dg
+            + (2.0 + (255.0 - r_mean) / 256.0) * db * db)
+            .sqrt()
+    }
+
+    /// Checks that two colors are within the `threshold` distance.
+    ///
+    /// See `Color::distance` for details.
+    #[inline]
+    pub fn approx_eq_within(&self, other: Color, threshold: f64) -> bool {
+        self.distance(other) <= threshold
+    }
+
+    /// Converts the color into linear RGB components in the `0..1` range.
+    ///
+    /// Uses the piecewise sRGB transfer function and not a gamma 2.2 approximation.
+    pub fn to_linear(&self) -> [f32; 3] {
+        fn to_linear(c: u8) -> f32 {
+            let c = c as f32 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        }
+
+        [
+            to_linear(self.red),
+            to_linear(self.green),
+            to_linear(self.blue),
+        ]
+    }
+
+    /// Constructs a new `Color` from linear RGB components.
+    ///
+    /// An inverse of `to_linear`. Components are clamped to the `0..1` range.
+    pub fn from_linear(rgb: [f32; 3]) -> Color {
+        fn from_linear(c: f32) -> u8 {
+            let c = if c.is_finite() {
+                c.clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let c = if c <= 0.0031308 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            };
+
+            (c * 255.0).round() as u8
+        }
+
+        Color::new_rgb(
+            from_linear(rgb[0]),
+            from_linear(rgb[1]),
+            from_linear(rgb[2]),
+        )
+    }
+
+    /// Returns the color as a lowercase `#rrggbb` string.
+    pub fn to_hex_string(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+
+    /// Constructs a new `Color` from an SVG color keyword, like `red`.
+    ///
+    /// Keywords are case-insensitive.
+    /// Returns `None` for unknown keywords and non-keyword values like `#ff0000`.
+    pub fn from_keyword(name: &str) -> Option<Color> {
+        if !colors::KEYWORDS
+            .iter()
+            .any(|k| k.eq_ignore_ascii_case(name))
+        {
+            return None;
+        }
+
+        let c: svgtypes::Color = name.parse().ok()?;
+        Some(Color::new_rgb(c.red, c.green, c.blue))
+    }
+
+    /// Returns an SVG color keyword that matches this color exactly.
+    ///
+    /// Some keywords are aliases, like `aqua` and `cyan` or `gray` and `grey`.
+    /// In this case, the alphabetically first one will be returned.
+    ///
+    /// This is a linear lookup.
+    pub fn to_keyword(&self) -> Option<&'static str> {
+        colors::KEYWORDS
+            .iter()
+            .find(|k| Color::from_keyword(k) == Some(*self))
+            .copied()
+    }
 }
 
 /// A paint style.
@@ -576,10 +1136,16 @@ impl Color {
 /// `paint` value type in the SVG.
 #[allow(missing_docs)]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Paint {
     Color(Color),
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared"))]
     LinearGradient(Rc<LinearGradient>),
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared"))]
     RadialGradient(Rc<RadialGradient>),
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared"))]
+    ConicGradient(Rc<ConicGradient>),
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared"))]
     Pattern(Rc<Pattern>),
 }
 
@@ -593,9 +1159,59 @@ impl Paint {
             Self::Color(_) => None,
             Self::LinearGradient(ref lg) => Some(lg.units),
             Self::RadialGradient(ref rg) => Some(rg.units),
+            Self::ConicGradient(ref cg) => Some(cg.units),
             Self::Pattern(ref patt) => Some(patt.units),
         }
     }
+
+    /// Checks that paints are equal by value.
+    ///
+    /// Unlike `PartialEq`, which compares paint servers by reference, compares gradients
+    /// coordinates, units, transform, spread method and stops,
+    /// as well as patterns attributes and content. IDs are ignored.
+    ///
+    /// Pattern content is compared conservatively. Groups with clip paths, masks or filters,
+    /// as well as images and text, are equal only when they are shared.
+    pub fn content_eq(&self, other: &Paint) -> bool {
+        match (self, other) {
+            (Self::Color(c1), Self::Color(c2)) => c1 == c2,
+            (Self::LinearGradient(ref lg1), Self::LinearGradient(ref lg2)) => {
+                Rc::ptr_eq(lg1, lg2)
+                    || (lg1.x1.fuzzy_eq(&lg2.x1)
+                        && lg1.y1.fuzzy_eq(&lg2.y1)
+                        && lg1.x2.fuzzy_eq(&lg2.x2)
+                        && lg1.y2.fuzzy_eq(&lg2.y2)
+                        && base_gradient_eq(&lg1.base, &lg2.base))
+            }
+            (Self::RadialGradient(ref rg1), Self::RadialGradient(ref rg2)) => {
+                Rc::ptr_eq(rg1, rg2)
+                    || (rg1.cx.fuzzy_eq(&rg2.cx)
+                        && rg1.cy.fuzzy_eq(&rg2.cy)
+                        && rg1.r.get().fuzzy_eq(&rg2.r.get())
+                        && rg1.fx.fuzzy_eq(&rg2.fx)
+                        && rg1.fy.fuzzy_eq(&rg2.fy)
+                        && base_gradient_eq(&rg1.base, &rg2.base))
+            }
+            (Self::ConicGradient(ref cg1), Self::ConicGradient(ref cg2)) => {
+                Rc::ptr_eq(cg1, cg2)
+                    || (cg1.cx.fuzzy_eq(&cg2.cx)
+                        && cg1.cy.fuzzy_eq(&cg2.cy)
+                        && cg1.angle.fuzzy_eq(&cg2.angle)
+                        && base_gradient_eq(&cg1.base, &cg2.base))
+            }
+            (Self::Pattern(ref p1), Self::Pattern(ref p2)) => {
+                Rc::ptr_eq(p1, p2)
+                    || (p1.units == p2.units
+                        && p1.content_units == p2.content_units
+                        && p1.transform.fuzzy_eq(&p2.transform)
+                        && p1.rect.fuzzy_eq(&p2.rect)
+                        && view_box_eq(p1.view_box, p2.view_box)
+                        && p1.overflow == p2.overflow
+                        && node_content_eq(&p1.root, &p2.root))
+            }
+            _ => false,
+        }
+    }
 }
 
 impl PartialEq for Paint {
@@ -605,16 +1221,133 @@ impl PartialEq for Paint {
             (Self::Color(lc), Self::Color(rc)) => lc == rc,
             (Self::LinearGradient(ref lg1), Self::LinearGradient(ref lg2)) => Rc::ptr_eq(lg1, lg2),
             (Self::RadialGradient(ref rg1), Self::RadialGradient(ref rg2)) => Rc::ptr_eq(rg1, rg2),
+            (Self::ConicGradient(ref cg1), Self::ConicGradient(ref cg2)) => Rc::ptr_eq(cg1, cg2),
             (Self::Pattern(ref p1), Self::Pattern(ref p2)) => Rc::ptr_eq(p1, p2),
             _ => false,
         }
     }
 }
 
+fn base_gradient_eq(g1: &BaseGradient, g2: &BaseGradient) -> bool {
+    g1.units == g2.units
+        && g1.transform.fuzzy_eq(&g2.transform)
+        && g1.spread_method == g2.spread_method
+        && g1.color_interpolation == g2.color_interpolation
+        && g1.stops.len() == g2.stops.len()
+        && g1.stops.iter().zip(&g2.stops).all(|(s1, s2)| {
+            s1.offset == s2.offset && s1.color == s2.color && s1.opacity == s2.opacity
+        })
+}
+
+fn view_box_eq(vb1: Option<ViewBox>, vb2: Option<ViewBox>) -> bool {
+    match (vb1, vb2) {
+        (Some(vb1), Some(vb2)) => vb1.rect.fuzzy_eq(&vb2.rect) && vb1.aspect == vb2.aspect,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn node_content_eq(node1: &Node, node2: &Node) -> bool {
+    if node1 == node2 {
+        return true;
+    }
+
+    let is_eq = match (&*node1.borrow(), &*node2.borrow()) {
+        (NodeKind::Group(ref g1), NodeKind::Group(ref g2)) => group_content_eq(g1, g2),
+        (NodeKind::Path(ref p1), NodeKind::Path(ref p2)) => path_content_eq(p1, p2),
+        _ => false,
+    };
+
+    is_eq
+        && node1.children().count() == node2.children().count()
+        && node1
+            .children()
+            .zip(node2.children())
+            .all(|(c1, c2)| node_content_eq(&c1, &c2))
+}
+
+fn group_content_eq(g1: &Group, g2: &Group) -> bool {
+    fn shared_eq<T>(v1: &Option<Rc<T>>, v2: &Option<Rc<T>>) -> bool {
+        match (v1, v2) {
+            (Some(v1), Some(v2)) => Rc::ptr_eq(v1, v2),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    fn paint_eq(p1: &Option<Paint>, p2: &Option<Paint>) -> bool {
+        match (p1, p2) {
+            (Some(p1), Some(p2)) => p1.content_eq(p2),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    let enable_background_eq = match (g1.enable_background, g2.enable_background) {
+        (Some(EnableBackground(Some(r1))), Some(EnableBackground(Some(r2)))) => r1.fuzzy_eq(&r2),
+        (Some(EnableBackground(None)), Some(EnableBackground(None))) => true,
+        (None, None) => true,
+        _ => false,
+    };
+
+    g1.transform.fuzzy_eq(&g2.transform)
+        && g1.opacity == g2.opacity
+        && g1.blend_mode == g2.blend_mode
+        && g1.isolate == g2.isolate
+        && g1.hidden == g2.hidden
+        && shared_eq(&g1.clip_path, &g2.clip_path)
+        && shared_eq(&g1.mask, &g2.mask)
+        && g1.filters.len() == g2.filters.len()
+        && g1
+            .filters
+            .iter()
+            .zip(&g2.filters)
+            .all(|(f1, f2)| Rc::ptr_eq(f1, f2))
+        && paint_eq(&g1.filter_fill, &g2.filter_fill)
+        && paint_eq(&g1.filter_stroke, &g2.filter_stroke)
+        && enable_background_eq
+}
+
+fn path_content_eq(p1: &Path, p2: &Path) -> bool {
+    let fill_eq = match (&p1.fill, &p2.fill) {
+        (Some(f1), Some(f2)) => {
+            f1.paint.content_eq(&f2.paint) && f1.opacity == f2.opacity && f1.rule == f2.rule
+        }
+        (None, None) => true,
+        _ => false,
+    };
+
+    let stroke_eq = match (&p1.stroke, &p2.stroke) {
+        (Some(s1), Some(s2)) => {
+            s1.paint.content_eq(&s2.paint)
+                && s1.dasharray == s2.dasharray
+                && s1.dashoffset == s2.dashoffset
+                && s1.miterlimit == s2.miterlimit
+                && s1.opacity == s2.opacity
+                && s1.width == s2.width
+                && s1.linecap == s2.linecap
+                && s1.linejoin == s2.linejoin
+        }
+        (None, None) => true,
+        _ => false,
+    };
+
+    fill_eq
+        && stroke_eq
+        && p1.transform.fuzzy_eq(&p2.transform)
+        && p1.visibility == p2.visibility
+        && p1.paint_order == p2.paint_order
+        && p1.rendering_mode == p2.rendering_mode
+        && p1.vector_effect == p2.vector_effect
+        && p1.data.commands() == p2.data.commands()
+        && p1.data.points() == p2.data.points()
+}
+
 /// A clip-path element.
 ///
 /// `clipPath` element in SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClipPath {
     /// Element's ID.
     ///
@@ -635,11 +1368,13 @@ pub struct ClipPath {
     /// Additional clip path.
     ///
     /// `clip-path` in SVG.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared_opt"))]
     pub clip_path: Option<Rc<Self>>,
 
     /// Clip path children.
     ///
     /// The root node is always `Group`.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::node"))]
     pub root: Node,
 }
 
@@ -655,10 +1390,28 @@ impl Default for ClipPath {
     }
 }
 
+/// A mask type.
+///
+/// `mask-type` property in SVG.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MaskType {
+    Luminance,
+    Alpha,
+}
+
+impl Default for MaskType {
+    fn default() -> Self {
+        Self::Luminance
+    }
+}
+
 /// A mask element.
 ///
 /// `mask` element in SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mask {
     /// Element's ID.
     ///
@@ -681,25 +1434,103 @@ pub struct Mask {
     /// `x`, `y`, `width` and `height` in SVG.
     pub rect: Rect,
 
+    /// Mask type.
+    ///
+    /// `mask-type` in SVG.
+    pub kind: MaskType,
+
+    /// Content overflow.
+    ///
+    /// Content is clipped by `rect` when `Hidden`, which is the default.
+    /// Renderers decide how to handle `Visible`.
+    ///
+    /// `overflow` in SVG.
+    pub overflow: Overflow,
+
     /// Additional mask.
     ///
     /// `mask` in SVG.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared_opt"))]
     pub mask: Option<Rc<Self>>,
 
     /// Clip path children.
     ///
     /// The root node is always `Group`.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::node"))]
     pub root: Node,
 }
 
-/// Node's kind.
-#[allow(missing_docs)]
-#[derive(Clone, Debug)]
-pub enum NodeKind {
-    Group(Group),
-    Path(Path),
-    Image(Image),
-    Text(Text),
+impl Mask {
+    /// Returns the mask region in the user space of the masked element.
+    ///
+    /// `bbox` is the object bounding box of the masked element.
+    /// Returns `None` when `units` is `ObjectBoundingBox` and `bbox` is not set.
+    pub fn region(&self, bbox: Option<Rect>) -> Option<Rect> {
+        self.rect.transform(&self.units.bbox_transform(bbox)?)
+    }
+
+    /// Returns a transform that maps mask content to the user space of the masked element.
+    ///
+    /// Returns `None` when `content_units` is `ObjectBoundingBox` and `bbox` is not set.
+    pub fn content_transform(&self, bbox: Option<Rect>) -> Option<Transform> {
+        self.content_units.bbox_transform(bbox)
+    }
+
+    /// Returns this mask, followed by the masks it references via `mask`, recursively.
+    ///
+    /// All masks are applied to the same element, so they share the same `bbox`.
+    /// Since masks are multiplied, the order affects only the rendering performance.
+    ///
+    /// Returns `None` when any of the masks requires `bbox`, but it's not set.
+    /// In which case the masked element should not be rendered at all.
+    /// Stops on a mask that was already visited.
+    pub fn mask_chain(&self, bbox: Option<Rect>) -> Option<Vec<ResolvedMask<'_>>> {
+        let mut visited = HashSet::new();
+        let mut chain = Vec::new();
+        let mut mask = Some(self);
+        while let Some(m) = mask {
+            if !visited.insert(m as *const Mask as usize) {
+                break;
+            }
+
+            chain.push(ResolvedMask {
+                mask: m,
+                region: m.region(bbox)?,
+                content_transform: m.content_transform(bbox)?,
+            });
+            mask = m.mask.as_deref();
+        }
+
+        Some(chain)
+    }
+}
+
+/// A mask with resolved units.
+///
+/// Created by `Mask::mask_chain`.
+#[derive(Clone, Copy, Debug)]
+pub struct ResolvedMask<'a> {
+    /// The mask itself.
+    pub mask: &'a Mask,
+
+    /// The mask region in the user space of the masked element.
+    ///
+    /// Content outside of it is masked out.
+    pub region: Rect,
+
+    /// A transform from mask content to the user space of the masked element.
+    pub content_transform: Transform,
+}
+
+/// Node's kind.
+#[allow(missing_docs)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NodeKind {
+    Group(Group),
+    Path(Path),
+    Image(Image),
+    Text(Text),
 }
 
 impl NodeKind {
@@ -722,12 +1553,44 @@ impl NodeKind {
             NodeKind::Text(ref e) => e.transform,
         }
     }
+
+    /// Checks that the node is a renderable leaf, i.e. a path, an image or a text.
+    #[inline]
+    pub fn is_renderable_leaf(&self) -> bool {
+        match self {
+            NodeKind::Group(_) => false,
+            NodeKind::Path(_) | NodeKind::Image(_) | NodeKind::Text(_) => true,
+        }
+    }
+
+    /// Checks that the node can have children, i.e. is a group.
+    #[inline]
+    pub fn is_container(&self) -> bool {
+        match self {
+            NodeKind::Group(_) => true,
+            NodeKind::Path(_) | NodeKind::Image(_) | NodeKind::Text(_) => false,
+        }
+    }
+
+    /// Checks that the node is taken into account by `NodeExt::calculate_bbox`.
+    ///
+    /// Text nodes are ignored, since their bbox is unknown until converted into paths.
+    /// Hidden groups are ignored as well, since they are not rendered.
+    #[inline]
+    pub fn contributes_to_bbox(&self) -> bool {
+        match self {
+            NodeKind::Group(ref g) => !g.hidden,
+            NodeKind::Path(_) | NodeKind::Image(_) => true,
+            NodeKind::Text(_) => false,
+        }
+    }
 }
 
 /// An `enable-background`.
 ///
 /// Contains only the `new [ <x> <y> <width> <height> ]` value.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub struct EnableBackground(pub Option<Rect>);
 
@@ -738,6 +1601,7 @@ pub struct EnableBackground(pub Option<Rect>);
 ///
 /// `g` element in SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Group {
     /// Element's ID.
     ///
@@ -753,6 +1617,7 @@ pub struct Group {
     ///
     /// After the group is rendered we should combine
     /// it with a parent group using the specified opacity.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::normalized_f64"))]
     pub opacity: Opacity,
 
     /// Group blend mode.
@@ -766,12 +1631,15 @@ pub struct Group {
     pub isolate: bool,
 
     /// Element's clip path.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared_opt"))]
     pub clip_path: Option<Rc<ClipPath>>,
 
     /// Element's mask.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared_opt"))]
     pub mask: Option<Rc<Mask>>,
 
     /// Element's filters.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared_vec"))]
     pub filters: Vec<Rc<filter::Filter>>,
 
     /// Contains a fill color or paint server used by `FilterInput::FillPaint`.
@@ -788,6 +1656,15 @@ pub struct Group {
     ///
     /// `None` indicates an `accumulate` value.
     pub enable_background: Option<EnableBackground>,
+
+    /// Indicates that the group and all its descendants should not be rendered.
+    ///
+    /// Elements with `display:none` are removed during parsing,
+    /// unless `Options::keep_hidden_nodes` is set. In which case they will be
+    /// placed inside a hidden group.
+    ///
+    /// Hidden groups are ignored during bbox calculation.
+    pub hidden: bool,
 }
 
 impl Default for Group {
@@ -804,6 +1681,7 @@ impl Default for Group {
             filter_fill: None,
             filter_stroke: None,
             enable_background: None,
+            hidden: false,
         }
     }
 }
@@ -827,6 +1705,7 @@ impl Group {
 ///
 /// [`paint-order`]: https://www.w3.org/TR/SVG2/painting.html#PaintOrder
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum PaintOrder {
     FillAndStroke,
@@ -839,8 +1718,45 @@ impl Default for PaintOrder {
     }
 }
 
+/// A [`paint-order`] layer.
+///
+/// Unlike [`PaintOrder`], includes `markers`. Used only to preserve
+/// the original `paint-order` value.
+///
+/// [`paint-order`]: https://www.w3.org/TR/SVG2/painting.html#PaintOrder
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum PaintOrderKind {
+    Fill,
+    Stroke,
+    Markers,
+}
+
+/// A vector effect.
+///
+/// `vector-effect` attribute in the SVG.
+///
+/// When set to `NonScalingStroke`, renderers must apply the stroke in the user space
+/// of the viewport (i.e. after the path transform), so the stroke width is not affected
+/// by the element's transform.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum VectorEffect {
+    None,
+    NonScalingStroke,
+}
+
+impl Default for VectorEffect {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
 /// A path element.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Path {
     /// Element's ID.
     ///
@@ -869,11 +1785,25 @@ pub struct Path {
     /// `paint-order` in SVG.
     pub paint_order: PaintOrder,
 
+    /// The original `paint-order` layers order, including `markers`.
+    ///
+    /// Not used for rendering, since markers are already resolved and
+    /// `paint_order` should be used instead. Useful for re-emitting
+    /// the original `paint-order` value.
+    ///
+    /// `None` when `paint-order` wasn't set.
+    pub raw_paint_order: Option<[PaintOrderKind; 3]>,
+
     /// Rendering mode.
     ///
     /// `shape-rendering` in SVG.
     pub rendering_mode: ShapeRendering,
 
+    /// Vector effect.
+    ///
+    /// `vector-effect` in SVG.
+    pub vector_effect: VectorEffect,
+
     /// Contains a text bbox.
     ///
     /// Text bbox is different from path bbox. The later one contains a tight path bbox,
@@ -889,9 +1819,22 @@ pub struct Path {
     /// Segments list.
     ///
     /// All segments are in absolute coordinates.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared"))]
     pub data: Rc<PathData>,
 }
 
+impl Path {
+    /// Returns path's bounding box without stroke and transform.
+    ///
+    /// Same as `path.data.bbox()`, which is calculated on the first call and cached
+    /// inside `PathData` afterwards. The cache is reset by any `PathData` modification,
+    /// therefore replacing `data` or modifying it in place via `Rc::get_mut`
+    /// doesn't require any manual invalidation.
+    pub fn bbox(&self) -> Option<PathBbox> {
+        self.data.bbox()
+    }
+}
+
 impl Default for Path {
     fn default() -> Self {
         Path {
@@ -901,7 +1844,9 @@ impl Default for Path {
             fill: None,
             stroke: None,
             paint_order: PaintOrder::default(),
+            raw_paint_order: None,
             rendering_mode: ShapeRendering::default(),
+            vector_effect: VectorEffect::default(),
             text_bbox: None,
             data: Rc::new(PathData::default()),
         }
@@ -910,23 +1855,52 @@ impl Default for Path {
 
 /// An embedded image kind.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ImageKind {
     /// A reference to raw JPEG data. Should be decoded by the caller.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared"))]
     JPEG(Arc<Vec<u8>>),
     /// A reference to raw PNG data. Should be decoded by the caller.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared"))]
     PNG(Arc<Vec<u8>>),
     /// A reference to raw GIF data. Should be decoded by the caller.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared"))]
     GIF(Arc<Vec<u8>>),
+    /// A reference to raw WebP data. Should be decoded by the caller.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared"))]
+    WEBP(Arc<Vec<u8>>),
     /// A preprocessed SVG tree. Can be rendered as is.
     SVG(crate::Tree),
 }
 
+impl ImageKind {
+    /// Returns image's intrinsic size in pixels.
+    ///
+    /// For raster images, only the image header is read and the data is not decoded.
+    /// For SVG images, the tree size is rounded to the nearest integer.
+    ///
+    /// Returns `None` when the image header is malformed.
+    pub fn dimensions(&self) -> Option<(u32, u32)> {
+        match self {
+            ImageKind::JPEG(ref data) => image_size::jpeg_size(data),
+            ImageKind::PNG(ref data) => image_size::png_size(data),
+            ImageKind::GIF(ref data) => image_size::gif_size(data),
+            ImageKind::WEBP(ref data) => image_size::webp_size(data),
+            ImageKind::SVG(ref tree) => {
+                let size = tree.size.to_screen_size();
+                Some((size.width(), size.height()))
+            }
+        }
+    }
+}
+
 impl std::fmt::Debug for ImageKind {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             ImageKind::JPEG(_) => f.write_str("ImageKind::JPEG(..)"),
             ImageKind::PNG(_) => f.write_str("ImageKind::PNG(..)"),
             ImageKind::GIF(_) => f.write_str("ImageKind::GIF(..)"),
+            ImageKind::WEBP(_) => f.write_str("ImageKind::WEBP(..)"),
             ImageKind::SVG(_) => f.write_str("ImageKind::SVG(..)"),
         }
     }
@@ -936,6 +1910,7 @@ impl std::fmt::Debug for ImageKind {
 ///
 /// `image` element in SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Image {
     /// Element's ID.
     ///
@@ -961,15 +1936,122 @@ pub struct Image {
     /// `image-rendering` in SVG.
     pub rendering_mode: ImageRendering,
 
+    /// Content overflow.
+    ///
+    /// Image content is clipped by `view_box.rect` when `Hidden`, which is the default.
+    /// Only affects images with the `slice` aspect ratio.
+    /// Renderers decide how to handle `Visible`.
+    ///
+    /// `overflow` in SVG.
+    pub overflow: Overflow,
+
     /// Image data.
     pub kind: ImageKind,
 }
 
+impl Image {
+    /// Returns image's intrinsic size.
+    ///
+    /// Uses [`ImageKind::dimensions`] for raster images and the tree size for SVG images.
+    ///
+    /// Returns `None` when the image header is malformed.
+    pub fn decoded_size(&self) -> Option<Size> {
+        match self.kind {
+            ImageKind::SVG(ref tree) => Some(tree.size),
+            _ => {
+                let (w, h) = self.kind.dimensions()?;
+                Size::new(w as f64, h as f64)
+            }
+        }
+    }
+
+    /// Returns a rect in which the image content should be rendered.
+    ///
+    /// The intrinsic image size is fitted into `view_box.rect` according to `view_box.aspect`.
+    /// With `slice`, the returned rect can be larger than `view_box.rect`
+    /// and should be clipped by it, unless `overflow` is `Visible`.
+    ///
+    /// Returns `None` when the image size cannot be determined.
+    pub fn rendered_rect(&self) -> Option<Rect> {
+        let view_box = &self.view_box;
+        let new_size = self.decoded_size()?.fit_view_box(view_box);
+        let (x, y) = utils::aligned_pos(
+            view_box.aspect.align,
+            view_box.rect.x(),
+            view_box.rect.y(),
+            view_box.rect.width() - new_size.width(),
+            view_box.rect.height() - new_size.height(),
+        );
+
+        Some(new_size.to_rect(x, y))
+    }
+
+    /// Returns a transform from a nested SVG image coordinates to the image's parent coordinates.
+    ///
+    /// Composes two mappings: the outer `view_box` fitting of the tree size
+    /// into `view_box.rect`, followed by the inner `Tree::view_box` mapping
+    /// into the tree size. Both honor their own `preserveAspectRatio`.
+    ///
+    /// Content should still be clipped by `view_box.rect` when `overflow` is `Hidden`.
+    ///
+    /// Returns `None` for raster images.
+    pub fn svg_transform(&self) -> Option<Transform> {
+        let tree = match self.kind {
+            ImageKind::SVG(ref tree) => tree,
+            _ => return None,
+        };
+
+        let r = self.rendered_rect()?;
+        let mut ts = Transform::new_translate(r.x(), r.y());
+        ts.scale(
+            r.width() / tree.size.width(),
+            r.height() / tree.size.height(),
+        );
+        ts.append(&tree.view_box.to_transform(tree.size));
+        Some(ts)
+    }
+}
+
+/// A way to fit an image into a target size.
+///
+/// Used by `Tree::fit_transform`.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FitMode {
+    /// Scale uniformly to match the target width.
+    Width,
+    /// Scale uniformly to match the target height.
+    Height,
+    /// Scale uniformly so the whole image fits inside the target.
+    Contain,
+    /// Scale uniformly so the image covers the whole target.
+    Cover,
+}
+
+impl Default for FitMode {
+    fn default() -> Self {
+        FitMode::Contain
+    }
+}
+
 /// Alias for `rctree::Node<NodeKind>`.
 pub type Node = rctree::Node<NodeKind>;
 
 // TODO: impl a Debug
 /// A nodes tree container.
+///
+/// # Thread safety
+///
+/// `Tree` is neither `Send` nor `Sync`. Nodes are `rctree::Node`s, which are
+/// `Rc<RefCell>` internally, and paint servers, clip paths, masks and filters
+/// are shared via `Rc`. Switching the latter to `Arc` alone would not help,
+/// since the nodes themselves would still be `!Send`. A thread-safe tree requires
+/// a different node type, like an arena with indices, and therefore a new API.
+///
+/// To render a tree on multiple threads, parse the SVG on each thread instead.
+/// With the `serde` feature, a tree can also be serialized and deserialized
+/// on the target thread.
 #[allow(missing_debug_implementations)]
 #[derive(Clone)]
 pub struct Tree {
@@ -991,26 +2073,446 @@ pub struct Tree {
     ///
     /// The root node is always `Group`.
     pub root: Node,
+
+    /// An ID to node index used by `Tree::node_by_id`.
+    ///
+    /// Built on the first lookup.
+    id_index: RefCell<Option<HashMap<String, Node>>>,
 }
 
 impl Tree {
+    /// Creates a new tree.
+    pub fn new(size: Size, view_box: ViewBox, root: Node) -> Self {
+        Tree {
+            size,
+            view_box,
+            root,
+            id_index: RefCell::new(None),
+        }
+    }
+
     // TODO: remove
     /// Returns renderable node by ID.
     ///
     /// If an empty ID is provided, than this method will always return `None`.
     /// Even if tree has nodes with empty ID.
+    /// When multiple nodes share the same ID, the first one in the tree order is returned.
+    ///
+    /// The first call builds an ID to node index, which makes subsequent lookups
+    /// constant time. The index is invalidated by `Tree` methods that take `&mut self`
+    /// and by `Tree::invalidate_id_index`. Since nodes can be modified directly,
+    /// the found node is checked to still have the same ID and to be a part of the tree,
+    /// otherwise the index is rebuilt. Nodes with new IDs are not found until
+    /// the index is invalidated.
     pub fn node_by_id(&self, id: &str) -> Option<Node> {
         if id.is_empty() {
             return None;
         }
 
-        self.root.descendants().find(|node| &*node.id() == id)
+        if let Some(node) = self.indexed_node_by_id(id) {
+            if &*node.id() == id && node.ancestors().last().as_ref() == Some(&self.root) {
+                return Some(node);
+            }
+
+            // The index is outdated.
+            self.id_index.replace(None);
+            return self.indexed_node_by_id(id);
+        }
+
+        None
+    }
+
+    fn indexed_node_by_id(&self, id: &str) -> Option<Node> {
+        let mut index = self.id_index.borrow_mut();
+        let index = index.get_or_insert_with(|| {
+            let mut index = HashMap::new();
+            for node in self.root.descendants() {
+                let id = node.id();
+                if !id.is_empty() && !index.contains_key(&*id) {
+                    index.insert(id.to_string(), node.clone());
+                }
+            }
+
+            index
+        });
+        index.get(id).cloned()
+    }
+
+    /// Invalidates the ID to node index used by `Tree::node_by_id`.
+    ///
+    /// Must be called after nodes were added or their IDs were changed directly.
+    pub fn invalidate_id_index(&mut self) {
+        self.id_index.replace(None);
     }
 
     /// Checks if the current tree has any text nodes.
     pub fn has_text_nodes(&self) -> bool {
         has_text_nodes(&self.root)
     }
+
+    /// Calculates a tight bounding box of the whole document.
+    ///
+    /// The bbox is in the root coordinate system, i.e. before the `view_box` transform,
+    /// and includes strokes. Text nodes are excluded, unless they were converted
+    /// into paths first.
+    ///
+    /// Returns `None` when the tree has no renderable content,
+    /// or when the content has a zero width or height.
+    pub fn bbox(&self) -> Option<Rect> {
+        self.root.calculate_bbox()?.to_rect()
+    }
+
+    /// Returns a transform that fits the tree into the `target` size.
+    ///
+    /// Includes the `view_box` transform, so it can be used directly
+    /// as a root transform during rendering.
+    ///
+    /// `FitMode::Contain` and `FitMode::Cover` center the image inside the `target`,
+    /// while `FitMode::Width` and `FitMode::Height` keep it at the origin.
+    pub fn fit_transform(&self, target: Size, mode: FitMode) -> Transform {
+        let mut ts = match mode {
+            FitMode::Width => {
+                let s = target.width() / self.size.width();
+                Transform::new_scale(s, s)
+            }
+            FitMode::Height => {
+                let s = target.height() / self.size.height();
+                Transform::new_scale(s, s)
+            }
+            FitMode::Contain | FitMode::Cover => {
+                let aspect = AspectRatio {
+                    defer: false,
+                    align: Align::XMidYMid,
+                    slice: mode == FitMode::Cover,
+                };
+                utils::view_box_to_transform(self.size.to_rect(0.0, 0.0), aspect, target)
+            }
+        };
+
+        ts.append(&self.view_box.to_transform(self.size));
+        ts
+    }
+
+    /// Returns all fill and stroke paints used by the tree.
+    ///
+    /// Includes paths, text spans and text decorations paints,
+    /// including the ones inside clip paths, masks and patterns.
+    ///
+    /// A paint is returned for each usage, so the same paint can be returned multiple times.
+    /// While the content of a shared clip path, mask or pattern is visited only once.
+    pub fn paints(&self) -> impl Iterator<Item = Paint> {
+        let mut paints = Vec::new();
+        collect_paints(&self.root, &mut HashSet::new(), &mut paints);
+        paints.into_iter()
+    }
+
+    /// Returns single precision points of all paths in the tree, in document order.
+    ///
+    /// Each path node is returned alongside the result of `PathData::to_f32_points`,
+    /// so the node's commands and transforms can be used for rendering.
+    /// Points are in the node's own coordinate system. See `PathData::to_f32_points`
+    /// for precision and clamping details.
+    ///
+    /// Paths inside clip paths, masks and patterns are not included.
+    /// Text nodes are ignored, unless they were converted into paths first.
+    pub fn to_f32_paths(&self) -> Vec<(Node, Vec<f32>)> {
+        let mut paths = Vec::new();
+        for node in self.root.descendants() {
+            if let NodeKind::Path(ref path) = *node.borrow() {
+                paths.push((node.clone(), path.data.to_f32_points()));
+            }
+        }
+
+        paths
+    }
+
+    /// Calls `f` for each node in the tree, in document order.
+    ///
+    /// Nodes inside clip paths, masks and patterns are visited as well,
+    /// right after the node that references them. A shared clip path, mask or pattern
+    /// is visited only once. References changed by `f` are followed,
+    /// so a newly assigned pattern will be visited too.
+    ///
+    /// Each node is mutably borrowed while `f` is running. Therefore `f` must not
+    /// borrow any other tree nodes or change the tree structure, like appending
+    /// or detaching nodes, otherwise it will panic. Such changes should be made
+    /// after the walk instead.
+    pub fn for_each_node_mut<F: FnMut(&mut NodeKind)>(&mut self, mut f: F) {
+        self.invalidate_id_index();
+        for_each_node_mut(&self.root, &mut HashSet::new(), &mut f);
+    }
+
+    /// Removes groups that do not impact rendering.
+    ///
+    /// Removes groups without children and ungroups groups with a single child,
+    /// in which case the group transform is applied to the child.
+    /// Isolated groups, groups with `enable-background` and groups with a non-empty ID
+    /// are preserved, since they can affect rendering or be referenced by ID.
+    ///
+    /// Nested groups are processed from the innermost one, so a chain of nested groups
+    /// will be collapsed completely. Clip paths, masks and patterns content is processed as well.
+    pub fn remove_empty_groups(&mut self) {
+        self.invalidate_id_index();
+        remove_empty_groups(&self.root, &mut HashSet::new());
+    }
+
+    /// Folds opacity of groups with a single child into this child.
+    ///
+    /// A group that has only an `opacity` requires an offscreen layer during rendering.
+    /// When such group contains a single path with either a fill or a stroke,
+    /// the opacity is multiplied into the paint opacity instead. When it contains a single
+    /// group with a normal blend mode, the opacity is multiplied into the child group opacity.
+    /// Either way the result is the same, but with fewer layers.
+    ///
+    /// Groups with other properties that require isolation, paths with both fill and stroke,
+    /// since they can overlap, and text are left untouched.
+    ///
+    /// Groups left without properties are not removed.
+    /// Use [`Tree::remove_empty_groups`] afterwards.
+    pub fn fold_group_opacity(&mut self) {
+        self.invalidate_id_index();
+        fold_group_opacity(&self.root, &mut HashSet::new());
+    }
+
+    /// Returns the number of groups that require isolation.
+    ///
+    /// I.e. groups for which [`Group::should_isolate`] returns `true`,
+    /// which are usually rendered via an offscreen layer.
+    /// Groups inside clip paths, masks and patterns are counted as well,
+    /// but a shared clip path, mask or pattern is counted only once.
+    pub fn count_isolated_groups(&self) -> usize {
+        count_isolated_groups(&self.root, &mut HashSet::new())
+    }
+
+    /// Returns the maximum nesting depth of groups that require isolation.
+    ///
+    /// I.e. the maximum number of offscreen layers a renderer has to keep at the same time.
+    /// Clip paths, masks and patterns content is treated as nested into the element
+    /// that references them, since it's rendered while the element's layer is still alive.
+    /// Hidden groups and recursive references are ignored.
+    ///
+    /// Returns `0` when no groups require isolation.
+    pub fn max_isolated_groups_depth(&self) -> usize {
+        max_isolated_groups_depth(&self.root, &HashSet::new())
+    }
+
+    /// Runs the provided passes on the tree, in order.
+    ///
+    /// See the [`passes`] module for built-in passes.
+    pub fn run_passes(&mut self, passes: &[Box<dyn passes::TreePass>]) {
+        self.invalidate_id_index();
+        for pass in passes {
+            pass.run(self);
+        }
+    }
+
+    /// Returns all solid colors used by fill and stroke paints.
+    ///
+    /// Gradient stops are not included. See [`Tree::paints`] for details.
+    pub fn colors(&self) -> impl Iterator<Item = Color> {
+        self.paints().filter_map(|paint| match paint {
+            Paint::Color(c) => Some(c),
+            _ => None,
+        })
+    }
+
+    /// Returns a fully independent copy of the tree.
+    ///
+    /// Unlike `clone`, which shares nodes with the original tree, copies all nodes
+    /// and creates new paint servers, clip paths, masks and filters.
+    /// Objects shared inside the original tree will be shared inside the new one as well.
+    ///
+    /// Path data, text paths and raster images are immutable and stay shared.
+    pub fn deep_clone(&self) -> Tree {
+        Tree::new(
+            self.size,
+            self.view_box,
+            deep_clone_node(&self.root, &mut HashMap::new()),
+        )
+    }
+
+    /// Creates a standalone tree from a node and its descendants.
+    ///
+    /// The node is copied the same way as in `deep_clone`, so the original tree
+    /// is not affected and the node stays attached to it.
+    ///
+    /// The copy is placed under a new root group, which has the node's parent absolute
+    /// transform, so the node is rendered exactly like in the original tree.
+    /// `size` and `view_box` are set to the node's bounding box,
+    /// as calculated by `NodeExt::calculate_bbox`.
+    ///
+    /// Useful for rendering a single element, like an icon from a sprite sheet.
+    ///
+    /// Returns `None` when the node has no bounding box or when it has a zero width or height.
+    pub fn from_node(node: &Node) -> Option<Tree> {
+        let rect = node.calculate_bbox()?.to_rect()?;
+
+        let root = Node::new(NodeKind::Group(Group {
+            transform: node.parent_transform(),
+            ..Group::default()
+        }));
+        root.append(deep_clone_node(node, &mut HashMap::new()));
+
+        Some(Tree::new(
+            rect.size(),
+            ViewBox {
+                rect,
+                aspect: AspectRatio::default(),
+            },
+            root,
+        ))
+    }
+}
+
+type DeepCloneCache = HashMap<usize, Rc<dyn std::any::Any>>;
+
+fn deep_clone_node(node: &Node, cache: &mut DeepCloneCache) -> Node {
+    let new_node = node.make_deep_copy();
+    for node in new_node.descendants() {
+        deep_clone_node_kind(&mut node.borrow_mut(), cache);
+    }
+
+    new_node
+}
+
+fn deep_clone_node_kind(kind: &mut NodeKind, cache: &mut DeepCloneCache) {
+    match kind {
+        NodeKind::Group(ref mut g) => {
+            if let Some(ref mut clip) = g.clip_path {
+                *clip = deep_clone_clip_path(clip, cache);
+            }
+
+            if let Some(ref mut mask) = g.mask {
+                *mask = deep_clone_mask(mask, cache);
+            }
+
+            for filter in &mut g.filters {
+                *filter = deep_clone_filter(filter, cache);
+            }
+
+            for paint in g.filter_fill.iter_mut().chain(g.filter_stroke.iter_mut()) {
+                deep_clone_paint(paint, cache);
+            }
+        }
+        NodeKind::Path(ref mut path) => {
+            deep_clone_style(path.fill.as_mut(), path.stroke.as_mut(), cache);
+        }
+        NodeKind::Image(ref mut img) => {
+            if let ImageKind::SVG(ref mut tree) = img.kind {
+                *tree = tree.deep_clone();
+            }
+        }
+        NodeKind::Text(ref mut text) => {
+            for span in text.chunks.iter_mut().flat_map(|c| &mut c.spans) {
+                deep_clone_style(span.fill.as_mut(), span.stroke.as_mut(), cache);
+
+                let decoration = &mut span.decoration;
+                for style in [
+                    &mut decoration.underline,
+                    &mut decoration.overline,
+                    &mut decoration.line_through,
+                ]
+                .iter_mut()
+                .filter_map(|d| d.as_mut())
+                {
+                    deep_clone_style(style.fill.as_mut(), style.stroke.as_mut(), cache);
+                }
+            }
+        }
+    }
+}
+
+fn deep_clone_style(
+    fill: Option<&mut Fill>,
+    stroke: Option<&mut Stroke>,
+    cache: &mut DeepCloneCache,
+) {
+    let fill = fill.map(|v| &mut v.paint);
+    let stroke = stroke.map(|v| &mut v.paint);
+    for paint in fill.into_iter().chain(stroke) {
+        deep_clone_paint(paint, cache);
+    }
+}
+
+fn deep_clone_paint(paint: &mut Paint, cache: &mut DeepCloneCache) {
+    match paint {
+        Paint::Color(_) => {}
+        Paint::LinearGradient(ref mut lg) => {
+            *lg = deep_clone_rc(lg, cache, |lg, _| lg.clone());
+        }
+        Paint::RadialGradient(ref mut rg) => {
+            *rg = deep_clone_rc(rg, cache, |rg, _| rg.clone());
+        }
+        Paint::ConicGradient(ref mut cg) => {
+            *cg = deep_clone_rc(cg, cache, |cg, _| cg.clone());
+        }
+        Paint::Pattern(ref mut patt) => {
+            *patt = deep_clone_rc(patt, cache, |patt, cache| Pattern {
+                root: deep_clone_node(&patt.root, cache),
+                ..patt.clone()
+            });
+        }
+    }
+}
+
+fn deep_clone_clip_path(clip: &Rc<ClipPath>, cache: &mut DeepCloneCache) -> Rc<ClipPath> {
+    deep_clone_rc(clip, cache, |clip, cache| ClipPath {
+        root: deep_clone_node(&clip.root, cache),
+        clip_path: clip
+            .clip_path
+            .as_ref()
+            .map(|c| deep_clone_clip_path(c, cache)),
+        ..clip.clone()
+    })
+}
+
+fn deep_clone_mask(mask: &Rc<Mask>, cache: &mut DeepCloneCache) -> Rc<Mask> {
+    deep_clone_rc(mask, cache, |mask, cache| Mask {
+        root: deep_clone_node(&mask.root, cache),
+        mask: mask.mask.as_ref().map(|m| deep_clone_mask(m, cache)),
+        ..mask.clone()
+    })
+}
+
+fn deep_clone_filter(
+    filter: &Rc<filter::Filter>,
+    cache: &mut DeepCloneCache,
+) -> Rc<filter::Filter> {
+    deep_clone_rc(filter, cache, |filter, cache| {
+        let mut filter = filter.clone();
+        for primitive in &mut filter.primitives {
+            if let filter::Kind::Image(ref mut img) = primitive.kind {
+                match img.data {
+                    filter::ImageKind::Use(ref mut node) => {
+                        *node = deep_clone_node(node, cache);
+                    }
+                    filter::ImageKind::Image(ImageKind::SVG(ref mut tree)) => {
+                        *tree = tree.deep_clone();
+                    }
+                    filter::ImageKind::Image(_) => {}
+                }
+            }
+        }
+
+        filter
+    })
+}
+
+/// Returns a copy of `rc` data created by `f`, or an already created one
+/// when `rc` was copied before.
+fn deep_clone_rc<T: 'static>(
+    rc: &Rc<T>,
+    cache: &mut DeepCloneCache,
+    f: impl FnOnce(&T, &mut DeepCloneCache) -> T,
+) -> Rc<T> {
+    let key = Rc::as_ptr(rc) as usize;
+    if let Some(Ok(v)) = cache.get(&key).map(|v| v.clone().downcast::<T>()) {
+        return v;
+    }
+
+    let new = Rc::new(f(rc, cache));
+    cache.insert(key, new.clone());
+    new
 }
 
 fn has_text_nodes(root: &Node) -> bool {
@@ -1056,18 +2558,332 @@ fn has_text_nodes(root: &Node) -> bool {
     false
 }
 
-/// Additional `Node` methods.
-pub trait NodeExt {
-    /// Returns node's ID.
-    ///
-    /// If a current node doesn't support ID - an empty string
-    /// will be returned.
-    fn id(&self) -> std::cell::Ref<str>;
+fn collect_paints(root: &Node, visited: &mut HashSet<usize>, paints: &mut Vec<Paint>) {
+    for node in root.descendants() {
+        match *node.borrow() {
+            NodeKind::Group(ref g) => {
+                if let Some(ref clip) = g.clip_path {
+                    collect_clip_path_paints(clip, visited, paints);
+                }
 
-    /// Returns node's transform.
-    ///
-    /// If a current node doesn't support transformation - a default
-    /// transform will be returned.
+                if let Some(ref mask) = g.mask {
+                    collect_mask_paints(mask, visited, paints);
+                }
+            }
+            NodeKind::Path(ref path) => {
+                collect_style_paints(path.fill.as_ref(), path.stroke.as_ref(), visited, paints);
+            }
+            NodeKind::Image(_) => {}
+            NodeKind::Text(ref text) => {
+                for span in text.chunks.iter().flat_map(|chunk| &chunk.spans) {
+                    collect_style_paints(span.fill.as_ref(), span.stroke.as_ref(), visited, paints);
+
+                    let decoration = &span.decoration;
+                    for style in [
+                        &decoration.underline,
+                        &decoration.overline,
+                        &decoration.line_through,
+                    ]
+                    .iter()
+                    .filter_map(|d| d.as_ref())
+                    {
+                        collect_style_paints(
+                            style.fill.as_ref(),
+                            style.stroke.as_ref(),
+                            visited,
+                            paints,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn for_each_node_mut(root: &Node, visited: &mut HashSet<usize>, f: &mut dyn FnMut(&mut NodeKind)) {
+    for node in root.descendants() {
+        let mut subroots = Vec::new();
+        {
+            let mut kind = node.borrow_mut();
+            f(&mut kind);
+            collect_subroots(&kind, visited, &mut subroots);
+        }
+
+        for subroot in subroots {
+            for_each_node_mut(&subroot, visited, f);
+        }
+    }
+}
+
+fn remove_empty_groups(parent: &Node, visited: &mut HashSet<usize>) {
+    let mut curr_node = parent.first_child();
+    while let Some(node) = curr_node {
+        curr_node = node.next_sibling();
+
+        remove_empty_groups(&node, visited);
+
+        let mut subroots = Vec::new();
+        collect_subroots(&node.borrow(), visited, &mut subroots);
+        for subroot in subroots {
+            remove_empty_groups(&subroot, visited);
+        }
+
+        // Filters, clip paths and masks can affect rendering even without children.
+        let transform = match *node.borrow() {
+            NodeKind::Group(ref g)
+                if g.id.is_empty()
+                    && !g.should_isolate()
+                    && g.enable_background.is_none()
+                    && !g.hidden =>
+            {
+                g.transform
+            }
+            _ => continue,
+        };
+
+        if node.is_empty_group() {
+            node.detach();
+            continue;
+        }
+
+        let child = match node.first_child() {
+            Some(child) if child.next_sibling().is_none() => child,
+            _ => continue,
+        };
+
+        let mut ts = transform;
+        ts.append(&child.transform());
+        match *child.borrow_mut() {
+            NodeKind::Group(ref mut g) => g.transform = ts,
+            NodeKind::Path(ref mut path) => path.transform = ts,
+            NodeKind::Image(ref mut img) => img.transform = ts,
+            NodeKind::Text(ref mut text) => text.transform = ts,
+        }
+
+        child.detach();
+        node.insert_before(child);
+        node.detach();
+    }
+}
+
+fn fold_group_opacity(parent: &Node, visited: &mut HashSet<usize>) {
+    for node in parent.children() {
+        let mut subroots = Vec::new();
+        collect_subroots(&node.borrow(), visited, &mut subroots);
+        for subroot in subroots {
+            fold_group_opacity(&subroot, visited);
+        }
+
+        // Fold outer groups first, so nested opacities will be accumulated
+        // and pushed down to the leaf.
+        fold_group_opacity_into_child(&node);
+        fold_group_opacity(&node, visited);
+    }
+}
+
+fn fold_group_opacity_into_child(node: &Node) {
+    let opacity = match *node.borrow() {
+        NodeKind::Group(ref g)
+            if g.opacity != Opacity::ONE
+                && !g.isolate
+                && g.clip_path.is_none()
+                && g.mask.is_none()
+                && g.filters.is_empty()
+                && g.blend_mode == BlendMode::Normal
+                && g.enable_background.is_none() =>
+        {
+            g.opacity
+        }
+        _ => return,
+    };
+
+    let child = match node.first_child() {
+        Some(child) if child.next_sibling().is_none() => child,
+        _ => return,
+    };
+
+    let folded = match *child.borrow_mut() {
+        // A child group must not blend with anything except the backdrop
+        // of the parent group, which is empty.
+        NodeKind::Group(ref mut g) if g.blend_mode == BlendMode::Normal => {
+            g.opacity = g.opacity * opacity;
+            true
+        }
+        // Fill and stroke can overlap, so we can fold only when one of them is set.
+        NodeKind::Path(ref mut path) => match (path.fill.as_mut(), path.stroke.as_mut()) {
+            (Some(fill), None) => {
+                fill.opacity = fill.opacity * opacity;
+                true
+            }
+            (None, Some(stroke)) => {
+                stroke.opacity = stroke.opacity * opacity;
+                true
+            }
+            _ => false,
+        },
+        _ => false,
+    };
+
+    if folded {
+        if let NodeKind::Group(ref mut g) = *node.borrow_mut() {
+            g.opacity = Opacity::ONE;
+        }
+    }
+}
+
+fn is_isolated_group(node: &Node) -> bool {
+    match *node.borrow() {
+        NodeKind::Group(ref g) => g.should_isolate(),
+        _ => false,
+    }
+}
+
+fn count_isolated_groups(root: &Node, visited: &mut HashSet<usize>) -> usize {
+    let mut count = 0;
+    for node in root.descendants() {
+        if is_isolated_group(&node) {
+            count += 1;
+        }
+
+        let mut subroots = Vec::new();
+        collect_subroots(&node.borrow(), visited, &mut subroots);
+        for subroot in subroots {
+            count += count_isolated_groups(&subroot, visited);
+        }
+    }
+
+    count
+}
+
+/// `ancestors` contains clip paths, masks and patterns we're currently inside of.
+fn max_isolated_groups_depth(node: &Node, ancestors: &HashSet<usize>) -> usize {
+    if let NodeKind::Group(ref g) = *node.borrow() {
+        if g.hidden {
+            return 0;
+        }
+    }
+
+    // Unlike the count, shared content must be checked in each context,
+    // therefore only the references that are currently being processed are skipped.
+    let mut nested_ancestors = ancestors.clone();
+    let mut subroots = Vec::new();
+    collect_subroots(&node.borrow(), &mut nested_ancestors, &mut subroots);
+
+    let subroots_depth = subroots
+        .iter()
+        .map(|n| max_isolated_groups_depth(n, &nested_ancestors));
+    let depth = node
+        .children()
+        .map(|n| max_isolated_groups_depth(&n, ancestors))
+        .chain(subroots_depth)
+        .max()
+        .unwrap_or(0);
+
+    if is_isolated_group(node) {
+        depth + 1
+    } else {
+        depth
+    }
+}
+
+/// Collects roots of clip paths, masks and patterns referenced by the node
+/// that were not visited yet.
+fn collect_subroots(kind: &NodeKind, visited: &mut HashSet<usize>, subroots: &mut Vec<Node>) {
+    let mut push_pattern = |paint: Option<&Paint>, subroots: &mut Vec<Node>| {
+        if let Some(Paint::Pattern(ref patt)) = paint {
+            if visited.insert(Rc::as_ptr(patt) as usize) {
+                subroots.push(patt.root.clone());
+            }
+        }
+    };
+
+    match kind {
+        NodeKind::Group(ref g) => {
+            let mut clip_path = g.clip_path.as_ref();
+            while let Some(clip) = clip_path {
+                if visited.insert(Rc::as_ptr(clip) as usize) {
+                    subroots.push(clip.root.clone());
+                }
+                clip_path = clip.clip_path.as_ref();
+            }
+
+            let mut mask = g.mask.as_ref();
+            while let Some(m) = mask {
+                if visited.insert(Rc::as_ptr(m) as usize) {
+                    subroots.push(m.root.clone());
+                }
+                mask = m.mask.as_ref();
+            }
+        }
+        NodeKind::Path(ref path) => {
+            push_pattern(path.fill.as_ref().map(|f| &f.paint), subroots);
+            push_pattern(path.stroke.as_ref().map(|s| &s.paint), subroots);
+        }
+        NodeKind::Image(_) => {}
+        NodeKind::Text(ref text) => {
+            for span in text.chunks.iter().flat_map(|chunk| &chunk.spans) {
+                push_pattern(span.fill.as_ref().map(|f| &f.paint), subroots);
+                push_pattern(span.stroke.as_ref().map(|s| &s.paint), subroots);
+            }
+        }
+    }
+}
+
+fn collect_style_paints(
+    fill: Option<&Fill>,
+    stroke: Option<&Stroke>,
+    visited: &mut HashSet<usize>,
+    paints: &mut Vec<Paint>,
+) {
+    let fill = fill.map(|f| &f.paint);
+    let stroke = stroke.map(|s| &s.paint);
+    for paint in fill.into_iter().chain(stroke) {
+        paints.push(paint.clone());
+
+        if let Paint::Pattern(ref patt) = paint {
+            if visited.insert(Rc::as_ptr(patt) as usize) {
+                collect_paints(&patt.root, visited, paints);
+            }
+        }
+    }
+}
+
+fn collect_clip_path_paints(
+    clip: &Rc<ClipPath>,
+    visited: &mut HashSet<usize>,
+    paints: &mut Vec<Paint>,
+) {
+    if visited.insert(Rc::as_ptr(clip) as usize) {
+        collect_paints(&clip.root, visited, paints);
+
+        if let Some(ref clip) = clip.clip_path {
+            collect_clip_path_paints(clip, visited, paints);
+        }
+    }
+}
+
+fn collect_mask_paints(mask: &Rc<Mask>, visited: &mut HashSet<usize>, paints: &mut Vec<Paint>) {
+    if visited.insert(Rc::as_ptr(mask) as usize) {
+        collect_paints(&mask.root, visited, paints);
+
+        if let Some(ref mask) = mask.mask {
+            collect_mask_paints(mask, visited, paints);
+        }
+    }
+}
+
+/// Additional `Node` methods.
+pub trait NodeExt {
+    /// Returns node's ID.
+    ///
+    /// If a current node doesn't support ID - an empty string
+    /// will be returned.
+    fn id(&self) -> std::cell::Ref<str>;
+
+    /// Returns node's transform.
+    ///
+    /// If a current node doesn't support transformation - a default
+    /// transform will be returned.
     fn transform(&self) -> Transform;
 
     /// Returns node's absolute transform.
@@ -1076,6 +2892,12 @@ pub trait NodeExt {
     /// transform will be returned.
     fn abs_transform(&self) -> Transform;
 
+    /// Returns node's parent absolute transform.
+    ///
+    /// The same as `abs_transform`, but without the node's own transform.
+    /// Returns a default transform for a node without a parent.
+    fn parent_transform(&self) -> Transform;
+
     /// Appends `kind` as a node child.
     ///
     /// Shorthand for `Node::append(Node::new(Box::new(kind)))`.
@@ -1091,6 +2913,71 @@ pub trait NodeExt {
 
     /// Returns the node starting from which the filter background should be rendered.
     fn filter_background_start_node(&self, filter: &filter::Filter) -> Option<Node>;
+
+    /// Returns the background region read by the `filter` applied to this node.
+    ///
+    /// The region is in the coordinate space of `filter_background_start_node`
+    /// and is an intersection of the filter region and the `enable-background` rect, if any.
+    ///
+    /// `bbox` is the node's object bounding box in its user space.
+    /// It's required only for filters with `objectBoundingBox` units.
+    ///
+    /// Returns `None` when the filter doesn't read the background
+    /// or when the resulting region is empty.
+    fn filter_background_region(&self, filter: &filter::Filter, bbox: Option<Rect>)
+        -> Option<Rect>;
+
+    /// Returns an iterator over this node and its descendants, in tree order,
+    /// which kinds are matched by `f`.
+    ///
+    /// Non-matching nodes are skipped, but their children are still visited.
+    fn descendants_of_kind<F: Fn(&NodeKind) -> bool>(&self, f: F) -> DescendantsOfKind<F>;
+
+    /// Returns an iterator over this node and its descendants, in tree order,
+    /// up to the `max_depth` nesting level.
+    ///
+    /// The current node has a depth of 0, so `0` will yield only the current node
+    /// and `1` will yield the current node and its children.
+    /// Deeper subtrees are not visited at all.
+    fn descendants_max_depth(&self, max_depth: usize) -> DescendantsMaxDepth;
+
+    /// Checks that the node itself should be rendered.
+    ///
+    /// Paths and images with `Hidden` or `Collapse` visibility are not rendered.
+    /// Text is rendered when at least one of its spans is visible.
+    /// Groups do not have a visibility and are always rendered,
+    /// since their children can still be visible. Unless they are hidden.
+    ///
+    /// Only the node itself is checked. Descendants of a hidden group
+    /// are not rendered either, but this method doesn't check ancestors.
+    fn is_rendered(&self) -> bool;
+
+    /// Returns an iterator over this node and its descendants, in tree order,
+    /// which are rendered.
+    ///
+    /// Hidden and collapsed nodes are skipped. See `is_rendered` for details.
+    /// Descendants of hidden groups are skipped as well.
+    fn rendered_descendants(&self) -> RenderedDescendants;
+
+    /// Returns the clip path applied to this node, followed by the clip paths
+    /// it references via `ClipPath::clip_path`, recursively.
+    ///
+    /// Each clip path is returned with a transform from its content coordinates
+    /// to the root coordinates, i.e. the node's absolute transform followed by
+    /// `ClipPath::transform`. For `objectBoundingBox` units, the bbox transform
+    /// must be appended by the caller.
+    ///
+    /// The node should be clipped by the intersection of all returned clip paths.
+    /// Clip paths set on the clip path children are not included.
+    ///
+    /// Returns an empty list for nodes without a clip path.
+    /// Stops on a clip path that was already visited.
+    fn clip_chain(&self) -> Vec<(Rc<ClipPath>, Transform)>;
+
+    /// Checks that the node is a group without children.
+    ///
+    /// Group properties are not checked.
+    fn is_empty_group(&self) -> bool;
 }
 
 impl NodeExt for Node {
@@ -1104,6 +2991,13 @@ impl NodeExt for Node {
         self.borrow().transform()
     }
 
+    fn parent_transform(&self) -> Transform {
+        match self.parent() {
+            Some(parent) => parent.abs_transform(),
+            None => Transform::default(),
+        }
+    }
+
     fn abs_transform(&self) -> Transform {
         let mut ts_list = Vec::new();
         for p in self.ancestors() {
@@ -1130,6 +3024,59 @@ impl NodeExt for Node {
         calc_node_bbox(self, self.abs_transform())
     }
 
+    fn descendants_of_kind<F: Fn(&NodeKind) -> bool>(&self, f: F) -> DescendantsOfKind<F> {
+        DescendantsOfKind {
+            iter: self.descendants(),
+            f,
+        }
+    }
+
+    fn descendants_max_depth(&self, max_depth: usize) -> DescendantsMaxDepth {
+        DescendantsMaxDepth {
+            next: Some((self.clone(), 0)),
+            max_depth,
+        }
+    }
+
+    #[inline]
+    fn is_rendered(&self) -> bool {
+        is_kind_rendered(&self.borrow())
+    }
+
+    fn rendered_descendants(&self) -> RenderedDescendants {
+        RenderedDescendants {
+            root: self.clone(),
+            next: Some(self.clone()),
+        }
+    }
+
+    fn is_empty_group(&self) -> bool {
+        matches!(*self.borrow(), NodeKind::Group(_)) && !self.has_children()
+    }
+
+    fn clip_chain(&self) -> Vec<(Rc<ClipPath>, Transform)> {
+        let mut clip_path = match *self.borrow() {
+            NodeKind::Group(ref g) => g.clip_path.clone(),
+            _ => None,
+        };
+
+        let abs_ts = self.abs_transform();
+        let mut visited = HashSet::new();
+        let mut chain = Vec::new();
+        while let Some(clip) = clip_path {
+            if !visited.insert(Rc::as_ptr(&clip) as usize) {
+                break;
+            }
+
+            let mut ts = abs_ts;
+            ts.append(&clip.transform);
+            clip_path = clip.clip_path.clone();
+            chain.push((clip, ts));
+        }
+
+        chain
+    }
+
     fn filter_background_start_node(&self, filter: &filter::Filter) -> Option<Node> {
         fn has_enable_background(node: &Node) -> bool {
             if let NodeKind::Group(ref g) = *node.borrow() {
@@ -1155,33 +3102,1738 @@ impl NodeExt for Node {
         // Skip the current element.
         self.ancestors().skip(1).find(has_enable_background)
     }
+
+    fn filter_background_region(
+        &self,
+        filter: &filter::Filter,
+        bbox: Option<Rect>,
+    ) -> Option<Rect> {
+        let start = self.filter_background_start_node(filter)?;
+
+        // A transform from the current node user space to the start node one.
+        let mut ts = start.abs_transform().invert()?;
+        ts.append(&self.abs_transform());
+
+        let region = filter.region(bbox, ts)?;
+
+        let enable_background = match *start.borrow() {
+            NodeKind::Group(ref g) => g.enable_background,
+            _ => None,
+        };
+
+        match enable_background {
+            Some(EnableBackground(Some(rect))) => region.intersection(rect),
+            _ => Some(region),
+        }
+    }
+}
+
+fn is_kind_rendered(kind: &NodeKind) -> bool {
+    match kind {
+        NodeKind::Group(ref g) => !g.hidden,
+        NodeKind::Path(ref path) => path.visibility == Visibility::Visible,
+        NodeKind::Image(ref image) => image.visibility == Visibility::Visible,
+        NodeKind::Text(ref text) => text
+            .chunks
+            .iter()
+            .flat_map(|chunk| &chunk.spans)
+            .any(|span| span.visibility == Visibility::Visible),
+    }
+}
+
+/// An iterator over node descendants of a specific kind.
+///
+/// Created by [`NodeExt::descendants_of_kind`].
+#[allow(missing_debug_implementations)]
+pub struct DescendantsOfKind<F> {
+    iter: rctree::Descendants<NodeKind>,
+    f: F,
+}
+
+impl<F: Fn(&NodeKind) -> bool> Iterator for DescendantsOfKind<F> {
+    type Item = Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let f = &self.f;
+        self.iter.find(|node| f(&node.borrow()))
+    }
+}
+
+/// A depth-limited iterator over node descendants.
+///
+/// Created by [`NodeExt::descendants_max_depth`].
+#[allow(missing_debug_implementations)]
+pub struct DescendantsMaxDepth {
+    next: Option<(Node, usize)>,
+    max_depth: usize,
+}
+
+impl Iterator for DescendantsMaxDepth {
+    type Item = Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, depth) = self.next.take()?;
+
+        if depth < self.max_depth {
+            if let Some(child) = node.first_child() {
+                self.next = Some((child, depth + 1));
+                return Some(node);
+            }
+        }
+
+        // Go up until a node with a next sibling is found, but not above the start node.
+        let mut curr = node.clone();
+        let mut curr_depth = depth;
+        while curr_depth > 0 {
+            if let Some(sibling) = curr.next_sibling() {
+                self.next = Some((sibling, curr_depth));
+                break;
+            }
+
+            curr = match curr.parent() {
+                Some(parent) => parent,
+                None => break,
+            };
+            curr_depth -= 1;
+        }
+
+        Some(node)
+    }
+}
+
+/// An iterator over rendered node descendants.
+///
+/// Created by [`NodeExt::rendered_descendants`].
+#[allow(missing_debug_implementations)]
+pub struct RenderedDescendants {
+    root: Node,
+    next: Option<Node>,
+}
+
+impl Iterator for RenderedDescendants {
+    type Item = Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = self.next.take()?;
+            let is_rendered = node.is_rendered();
+
+            // Children of a node that is not rendered are skipped.
+            if is_rendered {
+                self.next = node.first_child();
+            }
+
+            // Go up until a node with a next sibling is found, but not above the root node.
+            if self.next.is_none() {
+                let mut curr = node.clone();
+                while curr != self.root {
+                    if let Some(sibling) = curr.next_sibling() {
+                        self.next = Some(sibling);
+                        break;
+                    }
+
+                    curr = match curr.parent() {
+                        Some(parent) => parent,
+                        None => break,
+                    };
+                }
+            }
+
+            if is_rendered {
+                return Some(node);
+            }
+        }
+    }
 }
 
 fn calc_node_bbox(node: &Node, ts: Transform) -> Option<PathBbox> {
-    match *node.borrow() {
-        NodeKind::Path(ref path) => path.data.bbox_with_transform(ts, path.stroke.as_ref()),
+    let kind = node.borrow();
+    if !kind.contributes_to_bbox() {
+        return None;
+    }
+
+    match *kind {
+        NodeKind::Path(ref path) => {
+            if ts.b.is_fuzzy_zero() && ts.c.is_fuzzy_zero() {
+                // Scale and translate can be applied to the cached bbox directly.
+                calc_path_bbox_from_cache(path, ts)
+            } else {
+                path.data.bbox_with_transform(ts, path.stroke.as_ref())
+            }
+        }
         NodeKind::Image(ref img) => {
             let path = PathData::from_rect(img.view_box.rect);
-            path.bbox_with_transform(ts, None)
+            path.fill_bbox(ts)
+        }
+        NodeKind::Group(_) => calc_group_bbox(node, ts),
+        NodeKind::Text(_) => None,
+    }
+}
+
+/// A minimal number of group children to calculate the bbox in parallel.
+///
+/// Spawning jobs for a handful of paths is slower than calculating them in place.
+#[cfg(feature = "rayon")]
+const PARALLEL_BBOX_THRESHOLD: usize = 64;
+
+fn calc_group_bbox(node: &Node, ts: Transform) -> Option<PathBbox> {
+    #[cfg(feature = "rayon")]
+    {
+        if node.children().nth(PARALLEL_BBOX_THRESHOLD - 1).is_some() {
+            return calc_group_bbox_parallel(node, ts);
         }
-        NodeKind::Group(_) => {
-            let mut bbox = PathBbox::new_bbox();
+    }
 
-            for child in node.children() {
-                let mut child_transform = ts;
-                child_transform.append(&child.transform());
-                if let Some(c_bbox) = calc_node_bbox(&child, child_transform) {
-                    bbox = bbox.expand(c_bbox);
+    let mut bbox = PathBbox::new_bbox();
+
+    for child in node.children() {
+        let mut child_transform = ts;
+        child_transform.append(&child.transform());
+        if let Some(c_bbox) = calc_node_bbox(&child, child_transform) {
+            bbox = bbox.expand(c_bbox);
+        }
+    }
+
+    // Make sure bbox was changed.
+    if bbox.fuzzy_eq(&PathBbox::new_bbox()) {
+        return None;
+    }
+
+    Some(bbox)
+}
+
+#[cfg(feature = "rayon")]
+fn calc_group_bbox_parallel(node: &Node, ts: Transform) -> Option<PathBbox> {
+    use rayon::prelude::*;
+
+    // `Node` is neither `Send` nor `Sync`, therefore we have to collect all the nested
+    // paths and images with their absolute transforms on the current thread first.
+    fn collect_leaves(node: &Node, ts: Transform, leaves: &mut Vec<(Node, Transform)>) {
+        for child in node.children() {
+            // Must match `calc_node_bbox`.
+            if !child.borrow().contributes_to_bbox() {
+                continue;
+            }
+
+            let mut child_transform = ts;
+            child_transform.append(&child.transform());
+            if child.has_children() {
+                collect_leaves(&child, child_transform, leaves);
+            } else {
+                leaves.push((child, child_transform));
+            }
+        }
+    }
+
+    let mut leaves = Vec::new();
+    collect_leaves(node, ts, &mut leaves);
+    let kinds: Vec<_> = leaves.iter().map(|(n, ts)| (n.borrow(), *ts)).collect();
+
+    // Unlike `Path`, `PathData` is `Sync` and can be shared between threads.
+    let mut images_bbox = None;
+    let mut paths = Vec::with_capacity(kinds.len());
+    for (kind, ts) in &kinds {
+        match **kind {
+            NodeKind::Path(ref path) => {
+                let stroke_width = path.stroke.as_ref().map(|s| s.width.get());
+                paths.push((&*path.data, *ts, stroke_width));
+            }
+            NodeKind::Image(ref img) => {
+                let path = PathData::from_rect(img.view_box.rect);
+                if let Some(bbox) = path.fill_bbox(*ts) {
+                    images_bbox = Some(expand_bbox(images_bbox, bbox));
                 }
             }
+            NodeKind::Group(_) | NodeKind::Text(_) => {}
+        }
+    }
+
+    let paths_bbox = paths
+        .par_iter()
+        .filter_map(|(data, ts, stroke_width)| {
+            pathdata::calc_bbox_with_transform(data, *ts, *stroke_width)
+        })
+        .reduce_with(|bbox1, bbox2| bbox1.expand(bbox2));
+
+    match (paths_bbox, images_bbox) {
+        (Some(bbox1), Some(bbox2)) => Some(bbox1.expand(bbox2)),
+        (bbox1, bbox2) => bbox1.or(bbox2),
+    }
+}
 
-            // Make sure bbox was changed.
-            if bbox.fuzzy_eq(&PathBbox::new_bbox()) {
-                return None;
+#[cfg(feature = "rayon")]
+fn expand_bbox(bbox: Option<PathBbox>, other: PathBbox) -> PathBbox {
+    match bbox {
+        Some(bbox) => bbox.expand(other),
+        None => other,
+    }
+}
+
+// Stroked bboxes are not cached on their own, since they depend on the transform.
+// Instead, the stroke is added to the cached fill bbox, which is cheap.
+fn calc_path_bbox_from_cache(path: &Path, ts: Transform) -> Option<PathBbox> {
+    let bbox = path.bbox()?;
+    let (x1, y1) = ts.apply(bbox.left(), bbox.top());
+    let (x2, y2) = ts.apply(bbox.right(), bbox.bottom());
+
+    // Must match the stroke approximation in `PathData::bbox_with_transform`.
+    let w = match path.stroke {
+        Some(ref stroke) => stroke.width.get() / 2.0 * (ts.a * ts.d).abs().sqrt(),
+        None => 0.0,
+    };
+
+    let minx = x1.min(x2) - w;
+    let miny = y1.min(y2) - w;
+    let maxx = x1.max(x2) + w;
+    let maxy = y1.max(y2) + w;
+    PathBbox::new(minx, miny, maxx - minx, maxy - miny)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_from_hex_short() {
+        assert_eq!(Color::from_hex("#fff"), Some(Color::white()));
+        assert_eq!(Color::from_hex("f00"), Some(Color::new_rgb(255, 0, 0)));
+        assert_eq!(Color::from_hex("#fff").unwrap().to_hex_string(), "#ffffff");
+    }
+
+    #[test]
+    fn color_from_hex_long() {
+        assert_eq!(
+            Color::from_hex("#1A2b3C"),
+            Some(Color::new_rgb(0x1a, 0x2b, 0x3c))
+        );
+        assert_eq!(Color::new_rgb(0x1a, 0x2b, 0x3c).to_hex_string(), "#1a2b3c");
+    }
+
+    #[test]
+    fn stroke_miterlimit() {
+        assert_eq!(StrokeMiterlimit::clamp_min_1(4.0).get(), 4.0);
+        assert_eq!(StrokeMiterlimit::clamp_min_1(0.5).get(), 1.0);
+        assert_eq!(StrokeMiterlimit::clamp_min_1(-1.0).get(), 1.0);
+        assert_eq!(StrokeMiterlimit::clamp_min_1(f64::NAN).get(), 1.0);
+        assert_eq!(StrokeMiterlimit::clamp_min_1(f64::INFINITY).get(), 1.0);
+
+        let a = StrokeMiterlimit::new(2.0);
+        let b = StrokeMiterlimit::new(4.0);
+        assert!(a < b);
+        assert!(b > a);
+        assert!(a <= StrokeMiterlimit::new(2.0 + f64::EPSILON));
+        assert!(a >= StrokeMiterlimit::new(2.0 + f64::EPSILON));
+        assert_eq!(
+            a.partial_cmp(&StrokeMiterlimit::new(2.0 + f64::EPSILON)),
+            Some(std::cmp::Ordering::Equal)
+        );
+    }
+
+    #[test]
+    fn stroke_effective_width() {
+        let stroke = Stroke {
+            width: StrokeWidth::new(2.0).unwrap(),
+            ..Stroke::default()
+        };
+
+        assert!(stroke.effective_width(Transform::default()).fuzzy_eq(&2.0));
+        assert!(stroke
+            .effective_width(Transform::new_translate(10.0, 20.0))
+            .fuzzy_eq(&2.0));
+        assert!(stroke
+            .effective_width(Transform::new_scale(3.0, 3.0))
+            .fuzzy_eq(&6.0));
+        assert!(stroke
+            .effective_width(Transform::new_scale(-3.0, 3.0))
+            .fuzzy_eq(&6.0));
+        assert!(stroke
+            .effective_width(Transform::new_rotate(30.0))
+            .fuzzy_eq(&2.0));
+
+        let mut ts = Transform::new_rotate(45.0);
+        ts.scale(3.0, 3.0);
+        assert!(stroke.effective_width(ts).fuzzy_eq(&6.0));
+
+        // Non-uniform scale is approximated.
+        assert!(stroke
+            .effective_width(Transform::new_scale(4.0, 1.0))
+            .fuzzy_eq(&4.0));
+    }
+
+    #[test]
+    fn color_linear() {
+        assert_eq!(Color::black().to_linear(), [0.0, 0.0, 0.0]);
+        assert_eq!(Color::white().to_linear(), [1.0, 1.0, 1.0]);
+
+        // 128 is the closest to 0.5 in sRGB.
+        let [r, g, b] = Color::new_rgb(128, 10, 255).to_linear();
+        assert!((r - 0.2159).abs() < 0.0001, "{}", r);
+        assert!((g - 0.003035).abs() < 0.00001, "{}", g);
+        assert_eq!(b, 1.0);
+
+        assert_eq!(
+            Color::from_linear([0.2159, 0.0, 1.0]),
+            Color::new_rgb(128, 0, 255)
+        );
+        assert_eq!(
+            Color::from_linear([-1.0, 2.0, f32::NAN]),
+            Color::new_rgb(0, 255, 0)
+        );
+
+        for c in 0..=255 {
+            let color = Color::new_rgb(c, c, c);
+            assert_eq!(Color::from_linear(color.to_linear()), color);
+        }
+    }
+
+    #[test]
+    fn node_kind_classifiers() {
+        let group = NodeKind::Group(Group::default());
+        assert!(!group.is_renderable_leaf());
+        assert!(group.is_container());
+        assert!(group.contributes_to_bbox());
+
+        let hidden_group = NodeKind::Group(Group {
+            hidden: true,
+            ..Group::default()
+        });
+        assert!(hidden_group.is_container());
+        assert!(!hidden_group.contributes_to_bbox());
+
+        let path = NodeKind::Path(Path::default());
+        assert!(path.is_renderable_leaf());
+        assert!(!path.is_container());
+        assert!(path.contributes_to_bbox());
+    }
+
+    #[test]
+    fn color_lerp() {
+        let black = Color::black();
+        let white = Color::white();
+        assert_eq!(black.lerp(white, 0.5).to_hex_string(), "#808080");
+        assert_eq!(black.lerp(white, 0.0), black);
+        assert_eq!(black.lerp(white, 1.0), white);
+        assert_eq!(black.lerp(white, -1.0), black);
+        assert_eq!(black.lerp(white, 2.0), white);
+        assert_eq!(
+            Color::new_rgb(10, 100, 200).lerp(Color::new_rgb(20, 50, 0), 0.25),
+            Color::new_rgb(13, 88, 150)
+        );
+    }
+
+    #[test]
+    fn color_lighten_darken() {
+        let c = Color::new_rgb(100, 0, 200);
+        assert_eq!(c.lighten(0.5), Color::new_rgb(178, 128, 228));
+        assert_eq!(c.darken(0.5), Color::new_rgb(50, 0, 100));
+        assert_eq!(c.lighten(2.0), Color::white());
+        assert_eq!(c.darken(2.0), Color::black());
+        assert_eq!(c.lighten(-1.0), c);
+    }
+
+    #[test]
+    fn color_distance() {
+        let c = Color::new_rgb(100, 100, 100);
+        assert_eq!(c.distance(c), 0.0);
+
+        let black = Color::black();
+        let white = Color::white();
+        assert_eq!(black.distance(white), white.distance(black));
+        assert!((black.distance(white) - 764.83).abs() < 0.01);
+
+        // Green is weighted more than blue.
+        let green = black.distance(Color::new_rgb(0, 10, 0));
+        let blue = black.distance(Color::new_rgb(0, 0, 10));
+        assert!(green > blue);
+
+        let c2 = Color::new_rgb(101, 100, 100);
+        assert!(c.approx_eq_within(c2, 2.0));
+        assert!(!c.approx_eq_within(c2, 1.0));
+        assert!(c.approx_eq_within(c, 0.0));
+    }
+
+    #[test]
+    fn color_from_hex_invalid() {
+        assert_eq!(Color::from_hex("#ff000080"), None);
+        assert_eq!(Color::from_hex("#ff00"), None);
+        assert_eq!(Color::from_hex("#"), None);
+        assert_eq!(Color::from_hex("#gg0000"), None);
+        assert_eq!(Color::from_hex("#+f+f+f"), None);
+    }
+
+    fn gradient(spread_method: SpreadMethod, stops: &[(f64, Color, f64)]) -> BaseGradient {
+        BaseGradient {
+            units: Units::ObjectBoundingBox,
+            transform: Transform::default(),
+            spread_method,
+            color_interpolation: ColorInterpolation::SRGB,
+            stops: stops
+                .iter()
+                .map(|&(offset, color, opacity)| Stop {
+                    offset: StopOffset::new_clamped(offset),
+                    color,
+                    opacity: Opacity::new_clamped(opacity),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn gradient_color_at() {
+        let red = Color::new_rgb(255, 0, 0);
+        let blue = Color::new_rgb(0, 0, 255);
+        let g = gradient(SpreadMethod::Pad, &[(0.25, red, 1.0), (0.75, blue, 0.0)]);
+        assert_eq!(g.color_at(0.0), (red, Opacity::ONE));
+        assert_eq!(
+            g.color_at(0.5),
+            (Color::new_rgb(128, 0, 128), Opacity::new_clamped(0.5))
+        );
+        assert_eq!(g.color_at(2.0), (blue, Opacity::ZERO));
+
+        let g = gradient(SpreadMethod::Repeat, &[(0.0, red, 1.0), (1.0, blue, 1.0)]);
+        assert_eq!(g.color_at(1.25), g.color_at(0.25));
+        assert_eq!(g.color_at(-0.75), g.color_at(0.25));
+
+        let g = gradient(SpreadMethod::Reflect, &[(0.0, red, 1.0), (1.0, blue, 1.0)]);
+        assert_eq!(g.color_at(1.25), g.color_at(0.75));
+        assert_eq!(g.color_at(-0.25), g.color_at(0.25));
+    }
+
+    #[test]
+    fn stop_premultiplied_rgba() {
+        let red = Color::new_rgb(255, 0, 0);
+        let g = gradient(
+            SpreadMethod::Pad,
+            &[
+                (0.0, red, 0.5),
+                (0.5, Color::new_rgb(100, 51, 3), 0.2),
+                (1.0, red, 1.0),
+            ],
+        );
+        assert_eq!(g.stops[0].premultiplied_rgba(), [128, 0, 0, 128]);
+        assert_eq!(
+            g.stops_premultiplied(),
+            vec![[128, 0, 0, 128], [20, 10, 1, 51], [255, 0, 0, 255]]
+        );
+    }
+
+    #[test]
+    fn gradient_color_at_edge_cases() {
+        let red = Color::new_rgb(255, 0, 0);
+        let blue = Color::new_rgb(0, 0, 255);
+
+        let g = gradient(SpreadMethod::Pad, &[]);
+        assert_eq!(g.color_at(0.5), (Color::black(), Opacity::ZERO));
+
+        let g = gradient(SpreadMethod::Pad, &[(0.5, red, 0.5)]);
+        assert_eq!(g.color_at(0.0), (red, Opacity::new_clamped(0.5)));
+        assert_eq!(g.color_at(1.0), (red, Opacity::new_clamped(0.5)));
+
+        let g = gradient(SpreadMethod::Pad, &[(0.5, red, 1.0), (0.5, blue, 1.0)]);
+        assert_eq!(g.color_at(0.49).0, red);
+        assert_eq!(g.color_at(0.5).0, blue);
+    }
+
+    #[test]
+    fn gradient_normalize() {
+        let red = Color::new_rgb(255, 0, 0);
+        let blue = Color::new_rgb(0, 0, 255);
+        let mut g = gradient(
+            SpreadMethod::Pad,
+            &[
+                (0.5, red, 1.0),
+                (0.2, red, 1.0),
+                (0.3, blue, 1.0),
+                (1.5, blue, 1.0),
+                (1.0, blue, 1.0),
+                (1.0, blue, 0.5),
+            ],
+        );
+        g.normalize();
+
+        let stops: Vec<_> = g
+            .stops
+            .iter()
+            .map(|s| (s.offset.get(), s.color, s.opacity.get()))
+            .collect();
+        assert_eq!(
+            stops,
+            vec![
+                (0.5, red, 1.0),
+                (0.5, blue, 1.0),
+                (1.0, blue, 1.0),
+                (1.0, blue, 0.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn path_bbox() {
+        let mut path = Path {
+            data: Rc::new(PathData::from_rect(
+                Rect::new(10.0, 20.0, 30.0, 40.0).unwrap(),
+            )),
+            ..Path::default()
+        };
+        let bbox = PathBbox::new(10.0, 20.0, 30.0, 40.0).unwrap();
+        assert!(path.bbox().unwrap().fuzzy_eq(&bbox));
+        assert!(path.bbox().unwrap().fuzzy_eq(&bbox));
+
+        path.data = Rc::new(PathData::from_rect(Rect::new(1.0, 2.0, 3.0, 4.0).unwrap()));
+        let bbox = PathBbox::new(1.0, 2.0, 3.0, 4.0).unwrap();
+        assert!(path.bbox().unwrap().fuzzy_eq(&bbox));
+
+        Rc::get_mut(&mut path.data).unwrap().clear();
+        assert!(path.bbox().is_none());
+    }
+
+    #[test]
+    fn node_bbox_with_transform() {
+        let data = Rc::new(PathData::from_rect(
+            Rect::new(10.0, 20.0, 30.0, 40.0).unwrap(),
+        ));
+        let stroke = Stroke {
+            width: StrokeWidth::new(4.0).unwrap(),
+            ..Stroke::default()
+        };
+
+        for ts in [
+            Transform::default(),
+            Transform::new(2.0, 0.0, 0.0, -3.0, 5.0, 6.0),
+            Transform::new(1.0, 0.5, 0.0, 1.0, 0.0, 0.0),
+        ] {
+            let root = Node::new(NodeKind::Group(Group {
+                transform: ts,
+                ..Group::default()
+            }));
+            let node = root.append_kind(NodeKind::Path(Path {
+                stroke: Some(stroke.clone()),
+                data: data.clone(),
+                ..Path::default()
+            }));
+
+            let expected = data.bbox_with_transform(ts, Some(&stroke)).unwrap();
+            assert!(node.calculate_bbox().unwrap().fuzzy_eq(&expected));
+        }
+    }
+
+    #[test]
+    fn large_group_bbox() {
+        let root = Node::new(NodeKind::Group(Group::default()));
+        for i in 0..100 {
+            let rect = Rect::new(i as f64, 0.0, 10.0, 10.0).unwrap();
+            root.append_kind(NodeKind::Path(Path {
+                data: Rc::new(PathData::from_rect(rect)),
+                ..Path::default()
+            }));
+        }
+
+        let g = root.append_kind(NodeKind::Group(Group {
+            transform: Transform::new_translate(0.0, 50.0),
+            ..Group::default()
+        }));
+        g.append_kind(NodeKind::Path(Path {
+            data: Rc::new(PathData::from_rect(Rect::new(0.0, 0.0, 5.0, 5.0).unwrap())),
+            stroke: Some(Stroke::default()),
+            ..Path::default()
+        }));
+
+        let bbox = root.calculate_bbox().unwrap();
+        assert!(bbox.fuzzy_eq(&PathBbox::new(-0.5, 0.0, 109.5, 55.5).unwrap()));
+    }
+
+    #[test]
+    fn large_group_bbox_with_hidden_group() {
+        // Enough children to use the parallel calculation when `rayon` is enabled.
+        let root = Node::new(NodeKind::Group(Group::default()));
+        for i in 0..100 {
+            let rect = Rect::new(i as f64, 0.0, 10.0, 10.0).unwrap();
+            root.append_kind(NodeKind::Path(Path {
+                data: Rc::new(PathData::from_rect(rect)),
+                ..Path::default()
+            }));
+        }
+
+        let g = root.append_kind(NodeKind::Group(Group {
+            hidden: true,
+            ..Group::default()
+        }));
+        g.append_kind(NodeKind::Path(Path {
+            data: Rc::new(PathData::from_rect(
+                Rect::new(-100.0, -100.0, 500.0, 500.0).unwrap(),
+            )),
+            ..Path::default()
+        }));
+
+        let bbox = root.calculate_bbox().unwrap();
+        assert!(bbox.fuzzy_eq(&PathBbox::new(0.0, 0.0, 109.0, 10.0).unwrap()));
+    }
+
+    #[test]
+    fn parent_transform() {
+        let g1 = Node::new(NodeKind::Group(Group {
+            transform: Transform::new_translate(10.0, 20.0),
+            ..Group::default()
+        }));
+        let g2 = g1.append_kind(NodeKind::Group(Group {
+            transform: Transform::new_scale(2.0, 2.0),
+            ..Group::default()
+        }));
+        let g3 = g2.append_kind(NodeKind::Group(Group {
+            transform: Transform::new_translate(1.0, 1.0),
+            ..Group::default()
+        }));
+
+        assert!(g1.parent_transform().is_default());
+        assert_eq!(g2.parent_transform(), Transform::new_translate(10.0, 20.0));
+        assert_eq!(
+            g3.parent_transform(),
+            Transform::new(2.0, 0.0, 0.0, 2.0, 10.0, 20.0)
+        );
+
+        let mut ts = g3.parent_transform();
+        ts.append(&g3.transform());
+        assert_eq!(ts, g3.abs_transform());
+        assert_eq!(
+            g3.abs_transform(),
+            Transform::new(2.0, 0.0, 0.0, 2.0, 12.0, 22.0)
+        );
+    }
+
+    #[test]
+    fn descendants_iterators() {
+        let path = || NodeKind::Path(Path::default());
+
+        // g1 -> [g2 -> [p, g3 -> [p]], p]
+        let g1 = Node::new(NodeKind::Group(Group::default()));
+        let g2 = g1.append_kind(NodeKind::Group(Group::default()));
+        g2.append_kind(path());
+        let g3 = g2.append_kind(NodeKind::Group(Group::default()));
+        g3.append_kind(path());
+        g1.append_kind(path());
+
+        let is_path = |k: &NodeKind| matches!(k, NodeKind::Path(_));
+        assert_eq!(g1.descendants_of_kind(is_path).count(), 3);
+        assert_eq!(g3.descendants_of_kind(is_path).count(), 1);
+        assert_eq!(
+            g1.descendants_of_kind(|k| matches!(k, NodeKind::Group(_)))
+                .count(),
+            3
+        );
+
+        assert_eq!(g1.descendants_max_depth(0).count(), 1);
+        assert_eq!(g1.descendants_max_depth(1).count(), 3);
+        assert_eq!(g1.descendants_max_depth(2).count(), 5);
+        assert_eq!(g1.descendants_max_depth(3).count(), 6);
+        assert_eq!(g1.descendants_max_depth(10).count(), 6);
+        // Must not escape the start node.
+        assert_eq!(g2.descendants_max_depth(0).count(), 1);
+        assert_eq!(g2.descendants_max_depth(1).count(), 3);
+        assert_eq!(g3.descendants_max_depth(5).count(), 2);
+
+        let order: Vec<_> = g1.descendants_max_depth(2).collect();
+        let expected: Vec<_> = g1
+            .descendants()
+            .filter(|n| n != &g3.first_child().unwrap())
+            .collect();
+        assert_eq!(order, expected);
+    }
+
+    #[test]
+    fn filter_background_region() {
+        let start = Node::new(NodeKind::Group(Group {
+            transform: Transform::new_translate(10.0, 10.0),
+            enable_background: Some(EnableBackground(Some(
+                Rect::new(0.0, 0.0, 50.0, 50.0).unwrap(),
+            ))),
+            ..Group::default()
+        }));
+        let node = start.append_kind(NodeKind::Group(Group {
+            transform: Transform::new_scale(2.0, 2.0),
+            ..Group::default()
+        }));
+
+        let mut filter = filter::Filter {
+            id: String::new(),
+            units: Units::UserSpaceOnUse,
+            primitive_units: Units::UserSpaceOnUse,
+            rect: Rect::new(0.0, 0.0, 40.0, 40.0).unwrap(),
+            color_interpolation: ColorInterpolation::LinearRGB,
+            primitives: vec![filter::Primitive {
+                x: None,
+                y: None,
+                width: None,
+                height: None,
+                color_interpolation: ColorInterpolation::LinearRGB,
+                result: String::new(),
+                kind: filter::Kind::Offset(filter::Offset {
+                    input: filter::Input::BackgroundImage,
+                    dx: 0.0,
+                    dy: 0.0,
+                }),
+            }],
+        };
+
+        let r = node.filter_background_region(&filter, None).unwrap();
+        assert!(r.fuzzy_eq(&Rect::new(0.0, 0.0, 50.0, 50.0).unwrap()));
+
+        if let NodeKind::Group(ref mut g) = *start.borrow_mut() {
+            g.enable_background = Some(EnableBackground(None));
+        }
+        let r = node.filter_background_region(&filter, None).unwrap();
+        assert!(r.fuzzy_eq(&Rect::new(0.0, 0.0, 80.0, 80.0).unwrap()));
+
+        filter.primitives[0].kind = filter::Kind::Offset(filter::Offset {
+            input: filter::Input::SourceGraphic,
+            dx: 0.0,
+            dy: 0.0,
+        });
+        assert!(node.filter_background_region(&filter, None).is_none());
+    }
+
+    #[test]
+    fn rendered_descendants() {
+        let path = |visibility| {
+            NodeKind::Path(Path {
+                visibility,
+                ..Path::default()
+            })
+        };
+
+        // g1 -> [p, p(hidden), g2 -> [p(collapse), p], g3(hidden) -> [p]]
+        let g1 = Node::new(NodeKind::Group(Group::default()));
+        let p1 = g1.append_kind(path(Visibility::Visible));
+        let p2 = g1.append_kind(path(Visibility::Hidden));
+        let g2 = g1.append_kind(NodeKind::Group(Group::default()));
+        let p3 = g2.append_kind(path(Visibility::Collapse));
+        let p4 = g2.append_kind(path(Visibility::Visible));
+        let g3 = g1.append_kind(NodeKind::Group(Group {
+            hidden: true,
+            ..Group::default()
+        }));
+        let p5 = g3.append_kind(path(Visibility::Visible));
+
+        assert!(g1.is_rendered());
+        assert!(p1.is_rendered());
+        assert!(!p2.is_rendered());
+        assert!(!p3.is_rendered());
+        assert!(!g3.is_rendered());
+        assert!(p5.is_rendered());
+
+        let nodes: Vec<_> = g1.rendered_descendants().collect();
+        assert_eq!(nodes, vec![g1, p1, g2.clone(), p4.clone()]);
+
+        // The iteration doesn't leave the start node.
+        let nodes: Vec<_> = g2.rendered_descendants().collect();
+        assert_eq!(nodes, vec![g2, p4]);
+        assert_eq!(g3.rendered_descendants().count(), 0);
+    }
+
+    #[test]
+    fn hidden_group_content_eq() {
+        let pattern = |hidden| {
+            let root = Node::new(NodeKind::Group(Group::default()));
+            root.append_kind(NodeKind::Group(Group {
+                hidden,
+                ..Group::default()
+            }));
+
+            Paint::Pattern(Rc::new(Pattern {
+                id: "patt1".to_string(),
+                units: Units::UserSpaceOnUse,
+                content_units: Units::UserSpaceOnUse,
+                transform: Transform::default(),
+                rect: Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+                view_box: None,
+                overflow: Overflow::Hidden,
+                root,
+            }))
+        };
+
+        assert!(pattern(true).content_eq(&pattern(true)));
+        assert!(!pattern(true).content_eq(&pattern(false)));
+    }
+
+    #[test]
+    fn clip_chain() {
+        let clip2 = Rc::new(ClipPath {
+            id: "clip2".to_string(),
+            transform: Transform::new_scale(2.0, 2.0),
+            ..ClipPath::default()
+        });
+        let clip1 = Rc::new(ClipPath {
+            id: "clip1".to_string(),
+            transform: Transform::new_translate(10.0, 0.0),
+            clip_path: Some(clip2),
+            ..ClipPath::default()
+        });
+
+        let root = Node::new(NodeKind::Group(Group {
+            transform: Transform::new_translate(5.0, 0.0),
+            ..Group::default()
+        }));
+        let g = root.append_kind(NodeKind::Group(Group {
+            clip_path: Some(clip1),
+            ..Group::default()
+        }));
+        let path = g.append_kind(NodeKind::Path(Path::default()));
+
+        let chain: Vec<_> = g
+            .clip_chain()
+            .into_iter()
+            .map(|(clip, ts)| (clip.id.clone(), ts))
+            .collect();
+
+        let mut ts2 = Transform::new_translate(5.0, 0.0);
+        ts2.scale(2.0, 2.0);
+        assert_eq!(
+            chain,
+            vec![
+                ("clip1".to_string(), Transform::new_translate(15.0, 0.0)),
+                ("clip2".to_string(), ts2),
+            ]
+        );
+
+        assert!(root.clip_chain().is_empty());
+        assert!(path.clip_chain().is_empty());
+    }
+
+    #[test]
+    fn units_bbox_transform() {
+        let bbox = Rect::new(10.0, 20.0, 100.0, 50.0).unwrap();
+
+        assert_eq!(
+            Units::UserSpaceOnUse.bbox_transform(None),
+            Some(Transform::default())
+        );
+        assert_eq!(
+            Units::UserSpaceOnUse.bbox_transform(Some(bbox)),
+            Some(Transform::default())
+        );
+        assert_eq!(Units::ObjectBoundingBox.bbox_transform(None), None);
+
+        let ts = Units::ObjectBoundingBox.bbox_transform(Some(bbox)).unwrap();
+        assert_eq!(ts, Transform::new(100.0, 0.0, 0.0, 50.0, 10.0, 20.0));
+        let unit = Rect::new(0.0, 0.0, 1.0, 1.0).unwrap();
+        assert!(unit.transform(&ts).unwrap().fuzzy_eq(&bbox));
+        assert_eq!(ts.apply(1.0, 0.0), (110.0, 20.0));
+        assert_eq!(ts.apply(0.0, 1.0), (10.0, 70.0));
+        assert_eq!(ts.apply(0.5, 0.5), (60.0, 45.0));
+
+        // Must match the explicit `Rect` math.
+        let r = Rect::new(-0.1, -0.1, 1.2, 1.2).unwrap();
+        assert!(r.transform(&ts).unwrap().fuzzy_eq(&r.bbox_transform(bbox)));
+    }
+
+    #[test]
+    fn mask_chain() {
+        let mask = |id: &str, units, content_units, rect, mask| Mask {
+            id: id.to_string(),
+            units,
+            content_units,
+            rect,
+            kind: MaskType::Luminance,
+            overflow: Overflow::Hidden,
+            mask,
+            root: Node::new(NodeKind::Group(Group::default())),
+        };
+
+        let mask2 = mask(
+            "mask2",
+            Units::UserSpaceOnUse,
+            Units::ObjectBoundingBox,
+            Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+            None,
+        );
+        let mask1 = mask(
+            "mask1",
+            Units::ObjectBoundingBox,
+            Units::UserSpaceOnUse,
+            Rect::new(-0.1, -0.1, 1.2, 1.2).unwrap(),
+            Some(Rc::new(mask2)),
+        );
+
+        let bbox = Rect::new(10.0, 20.0, 100.0, 50.0).unwrap();
+        let chain = mask1.mask_chain(Some(bbox)).unwrap();
+        assert_eq!(chain.len(), 2);
+
+        assert_eq!(chain[0].mask.id, "mask1");
+        assert!(chain[0]
+            .region
+            .fuzzy_eq(&Rect::new(0.0, 15.0, 120.0, 60.0).unwrap()));
+        assert_eq!(chain[0].content_transform, Transform::default());
+
+        assert_eq!(chain[1].mask.id, "mask2");
+        assert!(chain[1]
+            .region
+            .fuzzy_eq(&Rect::new(0.0, 0.0, 10.0, 10.0).unwrap()));
+        assert_eq!(chain[1].content_transform, Transform::from_bbox(bbox));
+
+        // Both masks require a bbox.
+        assert!(mask1.mask_chain(None).is_none());
+    }
+
+    #[test]
+    fn pattern_effective_tile_transform() {
+        let bbox = Rect::new(10.0, 20.0, 100.0, 50.0).unwrap();
+        let mut pattern = Pattern {
+            id: "patt1".to_string(),
+            units: Units::ObjectBoundingBox,
+            content_units: Units::ObjectBoundingBox,
+            transform: Transform::default(),
+            rect: Rect::new(0.0, 0.0, 0.5, 0.5).unwrap(),
+            view_box: None,
+            overflow: Overflow::Hidden,
+            root: Node::new(NodeKind::Group(Group::default())),
+        };
+
+        let r = pattern.tile_rect(Some(bbox)).unwrap();
+        assert!(r.fuzzy_eq(&Rect::new(10.0, 20.0, 50.0, 25.0).unwrap()));
+        assert_eq!(
+            pattern.effective_tile_transform(Some(bbox)),
+            Some(Transform::new(100.0, 0.0, 0.0, 50.0, 10.0, 20.0))
+        );
+        assert!(pattern.tile_rect(None).is_none());
+        assert!(pattern.effective_tile_transform(None).is_none());
+
+        pattern.content_units = Units::UserSpaceOnUse;
+        assert_eq!(
+            pattern.effective_tile_transform(Some(bbox)),
+            Some(Transform::new_translate(10.0, 20.0))
+        );
+
+        // `content_units` is ignored when `view_box` is set.
+        pattern.units = Units::UserSpaceOnUse;
+        pattern.content_units = Units::ObjectBoundingBox;
+        pattern.rect = Rect::new(5.0, 5.0, 20.0, 10.0).unwrap();
+        pattern.transform = Transform::new_translate(1.0, 1.0);
+        pattern.view_box = Some(ViewBox {
+            rect: Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+            aspect: AspectRatio::default(),
+        });
+        assert_eq!(
+            pattern.effective_tile_transform(None),
+            Some(Transform::new(1.0, 0.0, 0.0, 1.0, 11.0, 6.0))
+        );
+    }
+
+    #[test]
+    fn image_rendered_rect() {
+        let mut image = Image {
+            id: String::new(),
+            transform: Transform::default(),
+            visibility: Visibility::Visible,
+            view_box: ViewBox {
+                rect: Rect::new(0.0, 0.0, 100.0, 100.0).unwrap(),
+                aspect: AspectRatio::default(),
+            },
+            rendering_mode: ImageRendering::default(),
+            overflow: Overflow::Hidden,
+            kind: ImageKind::GIF(Arc::new(b"GIF89a\xC8\x00\x64\x00".to_vec())),
+        };
+
+        let check = |image: &Image, x: f64, y: f64, w: f64, h: f64| {
+            let r = image.rendered_rect().unwrap();
+            assert!(r.fuzzy_eq(&Rect::new(x, y, w, h).unwrap()), "{:?}", r);
+        };
+
+        assert!(image
+            .decoded_size()
+            .unwrap()
+            .fuzzy_eq(&Size::new(200.0, 100.0).unwrap()));
+        check(&image, 0.0, 25.0, 100.0, 50.0);
+
+        image.view_box.aspect.slice = true;
+        check(&image, -50.0, 0.0, 200.0, 100.0);
+
+        image.view_box.aspect.slice = false;
+        image.view_box.aspect.align = Align::XMinYMax;
+        check(&image, 0.0, 50.0, 100.0, 50.0);
+
+        image.view_box.aspect.align = Align::None;
+        check(&image, 0.0, 0.0, 100.0, 100.0);
+
+        image.kind = ImageKind::GIF(Arc::new(b"GIF89a".to_vec()));
+        assert!(image.rendered_rect().is_none());
+    }
+
+    #[test]
+    fn image_svg_transform() {
+        let tree = Tree::new(
+            Size::new(200.0, 100.0).unwrap(),
+            ViewBox {
+                rect: Rect::new(10.0, 0.0, 20.0, 10.0).unwrap(),
+                aspect: AspectRatio::default(),
+            },
+            Node::new(NodeKind::Group(Group::default())),
+        );
+
+        let mut image = Image {
+            id: String::new(),
+            transform: Transform::default(),
+            visibility: Visibility::Visible,
+            view_box: ViewBox {
+                rect: Rect::new(0.0, 0.0, 100.0, 100.0).unwrap(),
+                aspect: AspectRatio::default(),
+            },
+            rendering_mode: ImageRendering::default(),
+            overflow: Overflow::Hidden,
+            kind: ImageKind::SVG(tree),
+        };
+
+        let ts = image.svg_transform().unwrap();
+        assert!(ts.fuzzy_eq(&Transform::new(5.0, 0.0, 0.0, 5.0, -50.0, 25.0)));
+        // The inner view box origin is mapped to the rendered rect origin.
+        assert_eq!(ts.apply(10.0, 0.0), (0.0, 25.0));
+
+        image.view_box.aspect.slice = true;
+        let ts = image.svg_transform().unwrap();
+        assert!(ts.fuzzy_eq(&Transform::new(10.0, 0.0, 0.0, 10.0, -150.0, 0.0)));
+
+        image.kind = ImageKind::GIF(Arc::new(b"GIF89a\xC8\x00\x64\x00".to_vec()));
+        assert!(image.svg_transform().is_none());
+    }
+
+    #[test]
+    fn tree_fit_transform() {
+        let tree = Tree::new(
+            Size::new(200.0, 100.0).unwrap(),
+            ViewBox {
+                rect: Rect::new(0.0, 0.0, 20.0, 10.0).unwrap(),
+                aspect: AspectRatio::default(),
+            },
+            Node::new(NodeKind::Group(Group::default())),
+        );
+
+        let target = Size::new(100.0, 100.0).unwrap();
+        let check = |mode, ts: Transform| {
+            assert!(tree.fit_transform(target, mode).fuzzy_eq(&ts), "{:?}", mode);
+        };
+
+        check(FitMode::Width, Transform::new(5.0, 0.0, 0.0, 5.0, 0.0, 0.0));
+        check(
+            FitMode::Height,
+            Transform::new(10.0, 0.0, 0.0, 10.0, 0.0, 0.0),
+        );
+        check(
+            FitMode::Contain,
+            Transform::new(5.0, 0.0, 0.0, 5.0, 0.0, 25.0),
+        );
+        check(
+            FitMode::Cover,
+            Transform::new(10.0, 0.0, 0.0, 10.0, -50.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn tree_bbox() {
+        let root = Node::new(NodeKind::Group(Group::default()));
+        let mut tree = Tree::new(
+            Size::new(100.0, 100.0).unwrap(),
+            ViewBox {
+                rect: Rect::new(0.0, 0.0, 100.0, 100.0).unwrap(),
+                aspect: AspectRatio::default(),
+            },
+            root.clone(),
+        );
+        assert!(tree.bbox().is_none());
+
+        root.append_kind(NodeKind::Path(Path {
+            data: Rc::new(PathData::from_rect(
+                Rect::new(10.0, 20.0, 5.0, 5.0).unwrap(),
+            )),
+            ..Path::default()
+        }));
+        let g = root.append_kind(NodeKind::Group(Group {
+            transform: Transform::new_translate(50.0, 50.0),
+            ..Group::default()
+        }));
+        g.append_kind(NodeKind::Path(Path {
+            data: Rc::new(PathData::from_rect(
+                Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+            )),
+            ..Path::default()
+        }));
+
+        let bbox = tree.bbox().unwrap();
+        assert!(bbox.fuzzy_eq(&Rect::new(10.0, 20.0, 50.0, 40.0).unwrap()));
+
+        tree.root = Node::new(NodeKind::Group(Group::default()));
+        let mut line = PathData::new();
+        line.push_move_to(0.0, 5.0);
+        line.push_line_to(10.0, 5.0);
+        tree.root.append_kind(NodeKind::Path(Path {
+            data: Rc::new(line),
+            ..Path::default()
+        }));
+        assert!(tree.bbox().is_none());
+    }
+
+    #[test]
+    fn tree_from_node() {
+        let root = Node::new(NodeKind::Group(Group::default()));
+        let g = root.append_kind(NodeKind::Group(Group {
+            transform: Transform::new_translate(50.0, 50.0),
+            ..Group::default()
+        }));
+        let path = g.append_kind(NodeKind::Path(Path {
+            id: "icon".to_string(),
+            transform: Transform::new_scale(2.0, 2.0),
+            data: Rc::new(PathData::from_rect(
+                Rect::new(0.0, 5.0, 10.0, 10.0).unwrap(),
+            )),
+            ..Path::default()
+        }));
+
+        let tree = Tree::from_node(&path).unwrap();
+        assert!(tree
+            .view_box
+            .rect
+            .fuzzy_eq(&Rect::new(50.0, 60.0, 20.0, 20.0).unwrap()));
+        assert!(tree.size.fuzzy_eq(&Size::new(20.0, 20.0).unwrap()));
+        assert_eq!(tree.root.transform(), Transform::new_translate(50.0, 50.0));
+        assert_eq!(tree.root.children().count(), 1);
+
+        // The node is copied and not moved.
+        let new_path = tree.node_by_id("icon").unwrap();
+        assert!(new_path != path);
+        assert_eq!(new_path.transform(), Transform::new_scale(2.0, 2.0));
+        assert!(path.parent() == Some(g));
+        assert!(tree.bbox().unwrap().fuzzy_eq(&tree.view_box.rect));
+
+        // An empty group has no bbox.
+        let empty = root.append_kind(NodeKind::Group(Group::default()));
+        assert!(Tree::from_node(&empty).is_none());
+    }
+
+    #[test]
+    fn tree_to_f32_paths() {
+        let root = Node::new(NodeKind::Group(Group::default()));
+        let g = root.append_kind(NodeKind::Group(Group::default()));
+        let path1 = g.append_kind(NodeKind::Path(Path {
+            data: Rc::new(PathData::from_rect(
+                Rect::new(0.0, 0.5, 10.0, 1e40).unwrap(),
+            )),
+            ..Path::default()
+        }));
+        let path2 = root.append_kind(NodeKind::Path(Path {
+            data: Rc::new(PathData::from_rect(Rect::new(0.1, 0.2, 1.0, 1.0).unwrap())),
+            ..Path::default()
+        }));
+
+        let tree = Tree::new(
+            Size::new(100.0, 100.0).unwrap(),
+            ViewBox {
+                rect: Rect::new(0.0, 0.0, 100.0, 100.0).unwrap(),
+                aspect: AspectRatio::default(),
+            },
+            root,
+        );
+
+        let paths = tree.to_f32_paths();
+        assert_eq!(paths.len(), 2);
+        assert!(paths[0].0 == path1);
+        assert!(paths[1].0 == path2);
+
+        // Out of range values are clamped.
+        assert_eq!(
+            paths[0].1,
+            vec![0.0, 0.5, 10.0, 0.5, 10.0, f32::MAX, 0.0, f32::MAX]
+        );
+        assert_eq!(paths[1].1, vec![0.1, 0.2, 1.1, 0.2, 1.1, 1.2, 0.1, 1.2]);
+    }
+
+    #[test]
+    fn text_to_paths() {
+        let text = Text {
+            id: "text1".to_string(),
+            transform: Transform::new_translate(10.0, 20.0),
+            rendering_mode: TextRendering::OptimizeSpeed,
+            positions: Vec::new(),
+            rotate: Vec::new(),
+            writing_mode: WritingMode::LeftToRight,
+            direction: TextDirection::LeftToRight,
+            bidi_override: false,
+            chunks: Vec::new(),
+        };
+        assert!(text.to_paths(Vec::new()).is_none());
+
+        let rect = Rect::new(0.0, -8.0, 6.0, 10.0).unwrap();
+        let glyph = Path {
+            data: Rc::new(PathData::from_rect(Rect::new(1.0, -7.0, 4.0, 7.0).unwrap())),
+            text_bbox: Some(rect),
+            ..Path::default()
+        };
+
+        let group = text.to_paths(vec![glyph]).unwrap();
+        assert_eq!(*group.id(), *"text1");
+        assert!(group.transform() == text.transform);
+
+        let path = group.first_child().unwrap();
+        if let NodeKind::Path(ref path) = *path.borrow() {
+            assert_eq!(path.rendering_mode, ShapeRendering::CrispEdges);
+            assert!(path.text_bbox.unwrap().x().fuzzy_eq(&rect.x()));
+        } else {
+            panic!("a path is expected");
+        }
+
+        let bbox = group.calculate_bbox().unwrap();
+        assert!(bbox.fuzzy_eq(&PathBbox::new(11.0, 13.0, 4.0, 7.0).unwrap()));
+    }
+
+    #[test]
+    fn for_each_node_mut() {
+        let pattern_root = Node::new(NodeKind::Group(Group::default()));
+        pattern_root.append_kind(NodeKind::Path(Path::default()));
+        let pattern = Rc::new(Pattern {
+            id: "patt1".to_string(),
+            units: Units::UserSpaceOnUse,
+            content_units: Units::UserSpaceOnUse,
+            transform: Transform::default(),
+            rect: Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+            view_box: None,
+            overflow: Overflow::Hidden,
+            root: pattern_root.clone(),
+        });
+
+        let root = Node::new(NodeKind::Group(Group::default()));
+        for _ in 0..2 {
+            root.append_kind(NodeKind::Path(Path {
+                fill: Some(Fill {
+                    paint: Paint::Pattern(pattern.clone()),
+                    ..Fill::default()
+                }),
+                ..Path::default()
+            }));
+        }
+
+        let mut tree = Tree::new(
+            Size::new(10.0, 10.0).unwrap(),
+            ViewBox {
+                rect: Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+                aspect: AspectRatio::default(),
+            },
+            root.clone(),
+        );
+
+        let mut count = 0;
+        tree.for_each_node_mut(|kind| {
+            count += 1;
+            if let NodeKind::Path(ref mut path) = kind {
+                path.visibility = Visibility::Hidden;
             }
+        });
 
-            Some(bbox)
+        // The shared pattern content is visited only once.
+        assert_eq!(count, 5);
+        for node in root.descendants().chain(pattern_root.descendants()) {
+            if let NodeKind::Path(ref path) = *node.borrow() {
+                assert_eq!(path.visibility, Visibility::Hidden);
+            }
         }
-        NodeKind::Text(_) => None,
+    }
+
+    #[test]
+    fn remove_empty_groups() {
+        let root = Node::new(NodeKind::Group(Group::default()));
+
+        // Nested non-isolated groups.
+        let g1 = root.append_kind(NodeKind::Group(Group {
+            transform: Transform::new_translate(10.0, 0.0),
+            ..Group::default()
+        }));
+        let g2 = g1.append_kind(NodeKind::Group(Group {
+            transform: Transform::new_scale(2.0, 2.0),
+            ..Group::default()
+        }));
+        g2.append_kind(NodeKind::Path(Path {
+            id: "path1".to_string(),
+            ..Path::default()
+        }));
+
+        // An isolated group.
+        let g3 = root.append_kind(NodeKind::Group(Group {
+            opacity: Opacity::new_clamped(0.5),
+            ..Group::default()
+        }));
+        g3.append_kind(NodeKind::Path(Path::default()));
+
+        // A group with an ID.
+        let g4 = root.append_kind(NodeKind::Group(Group {
+            id: "g4".to_string(),
+            ..Group::default()
+        }));
+        g4.append_kind(NodeKind::Path(Path::default()));
+
+        // An empty group.
+        root.append_kind(NodeKind::Group(Group::default()));
+
+        // A group with multiple children.
+        let g5 = root.append_kind(NodeKind::Group(Group::default()));
+        g5.append_kind(NodeKind::Path(Path::default()));
+        g5.append_kind(NodeKind::Path(Path::default()));
+
+        let mut tree = Tree::new(
+            Size::new(10.0, 10.0).unwrap(),
+            ViewBox {
+                rect: Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+                aspect: AspectRatio::default(),
+            },
+            root.clone(),
+        );
+        tree.remove_empty_groups();
+
+        let children: Vec<_> = root.children().collect();
+        assert_eq!(children.len(), 4);
+        assert_eq!(*children[0].id(), *"path1");
+        assert!(children[0].transform() == Transform::new(2.0, 0.0, 0.0, 2.0, 10.0, 0.0));
+        assert!(children[1] == g3);
+        assert!(children[2] == g4);
+        assert!(children[3] == g5);
+    }
+
+    #[test]
+    fn fold_group_opacity() {
+        let root = Node::new(NodeKind::Group(Group::default()));
+        let half = Opacity::new_clamped(0.5);
+        let fill = Some(Fill::default());
+        let stroke = Some(Stroke::default());
+
+        // Nested groups with a filled path.
+        let g1 = root.append_kind(NodeKind::Group(Group {
+            opacity: half,
+            ..Group::default()
+        }));
+        let g2 = g1.append_kind(NodeKind::Group(Group {
+            opacity: half,
+            ..Group::default()
+        }));
+        let p1 = g2.append_kind(NodeKind::Path(Path {
+            fill: fill.clone(),
+            ..Path::default()
+        }));
+
+        // A stroked path.
+        let g3 = root.append_kind(NodeKind::Group(Group {
+            opacity: half,
+            ..Group::default()
+        }));
+        let p2 = g3.append_kind(NodeKind::Path(Path {
+            stroke: stroke.clone(),
+            ..Path::default()
+        }));
+
+        // Fill and stroke can overlap.
+        let g4 = root.append_kind(NodeKind::Group(Group {
+            opacity: half,
+            ..Group::default()
+        }));
+        g4.append_kind(NodeKind::Path(Path {
+            fill: fill.clone(),
+            stroke,
+            ..Path::default()
+        }));
+
+        // Multiple children can overlap.
+        let g5 = root.append_kind(NodeKind::Group(Group {
+            opacity: half,
+            ..Group::default()
+        }));
+        g5.append_kind(NodeKind::Path(Path {
+            fill: fill.clone(),
+            ..Path::default()
+        }));
+        g5.append_kind(NodeKind::Path(Path {
+            fill: fill.clone(),
+            ..Path::default()
+        }));
+
+        // A child with a blend mode.
+        let g6 = root.append_kind(NodeKind::Group(Group {
+            opacity: half,
+            ..Group::default()
+        }));
+        g6.append_kind(NodeKind::Group(Group {
+            blend_mode: BlendMode::Multiply,
+            ..Group::default()
+        }));
+
+        // An isolated group.
+        let g7 = root.append_kind(NodeKind::Group(Group {
+            opacity: half,
+            isolate: true,
+            ..Group::default()
+        }));
+        g7.append_kind(NodeKind::Path(Path {
+            fill,
+            ..Path::default()
+        }));
+
+        let mut tree = Tree::new(
+            Size::new(10.0, 10.0).unwrap(),
+            ViewBox {
+                rect: Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+                aspect: AspectRatio::default(),
+            },
+            root.clone(),
+        );
+        tree.fold_group_opacity();
+
+        let group_opacity = |node: &Node| match *node.borrow() {
+            NodeKind::Group(ref g) => g.opacity.get(),
+            _ => unreachable!(),
+        };
+
+        assert_eq!(group_opacity(&g1), 1.0);
+        assert_eq!(group_opacity(&g2), 1.0);
+        match *p1.borrow() {
+            NodeKind::Path(ref path) => assert_eq!(path.fill.as_ref().unwrap().opacity.get(), 0.25),
+            _ => unreachable!(),
+        }
+
+        assert_eq!(group_opacity(&g3), 1.0);
+        match *p2.borrow() {
+            NodeKind::Path(ref path) => {
+                assert_eq!(path.stroke.as_ref().unwrap().opacity.get(), 0.5)
+            }
+            _ => unreachable!(),
+        }
+
+        for g in [g4, g5, g6, g7].iter() {
+            assert_eq!(group_opacity(g), 0.5);
+        }
+
+        // Now the groups can be removed.
+        tree.remove_empty_groups();
+        assert!(root.first_child().unwrap() == p1);
+    }
+
+    #[test]
+    fn isolated_groups() {
+        let half = Opacity::new_clamped(0.5);
+
+        // A shared pattern with an isolated group.
+        let pattern_root = Node::new(NodeKind::Group(Group::default()));
+        pattern_root
+            .append_kind(NodeKind::Group(Group {
+                isolate: true,
+                ..Group::default()
+            }))
+            .append_kind(NodeKind::Path(Path::default()));
+        let pattern = Rc::new(Pattern {
+            id: "patt1".to_string(),
+            units: Units::UserSpaceOnUse,
+            content_units: Units::UserSpaceOnUse,
+            transform: Transform::default(),
+            rect: Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+            view_box: None,
+            overflow: Overflow::Hidden,
+            root: pattern_root,
+        });
+        let pattern_path = || {
+            NodeKind::Path(Path {
+                fill: Some(Fill {
+                    paint: Paint::Pattern(pattern.clone()),
+                    ..Fill::default()
+                }),
+                ..Path::default()
+            })
+        };
+
+        let root = Node::new(NodeKind::Group(Group::default()));
+
+        // Three nested isolated groups, the last one is inside the pattern.
+        let g1 = root.append_kind(NodeKind::Group(Group {
+            opacity: half,
+            ..Group::default()
+        }));
+        let g2 = g1.append_kind(NodeKind::Group(Group {
+            blend_mode: BlendMode::Multiply,
+            ..Group::default()
+        }));
+        g2.append_kind(pattern_path());
+
+        // A non-isolated group.
+        let g3 = root.append_kind(NodeKind::Group(Group {
+            transform: Transform::new_translate(10.0, 0.0),
+            ..Group::default()
+        }));
+        g3.append_kind(pattern_path());
+
+        let mut tree = Tree::new(
+            Size::new(10.0, 10.0).unwrap(),
+            ViewBox {
+                rect: Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+                aspect: AspectRatio::default(),
+            },
+            root.clone(),
+        );
+
+        assert_eq!(tree.count_isolated_groups(), 3);
+        assert_eq!(tree.max_isolated_groups_depth(), 3);
+
+        g1.detach();
+        assert_eq!(tree.count_isolated_groups(), 1);
+        assert_eq!(tree.max_isolated_groups_depth(), 1);
+
+        tree.root = Node::new(NodeKind::Group(Group::default()));
+        assert_eq!(tree.count_isolated_groups(), 0);
+        assert_eq!(tree.max_isolated_groups_depth(), 0);
+    }
+
+    #[test]
+    fn isolated_groups_with_recursive_pattern() {
+        // A pattern that contains a path filled with the same pattern.
+        let pattern_root = Node::new(NodeKind::Group(Group::default()));
+        let pattern_group = pattern_root.append_kind(NodeKind::Group(Group {
+            isolate: true,
+            ..Group::default()
+        }));
+        let pattern = Rc::new(Pattern {
+            id: "patt1".to_string(),
+            units: Units::UserSpaceOnUse,
+            content_units: Units::UserSpaceOnUse,
+            transform: Transform::default(),
+            rect: Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+            view_box: None,
+            overflow: Overflow::Hidden,
+            root: pattern_root,
+        });
+        let pattern_path = || {
+            NodeKind::Path(Path {
+                fill: Some(Fill {
+                    paint: Paint::Pattern(pattern.clone()),
+                    ..Fill::default()
+                }),
+                ..Path::default()
+            })
+        };
+        pattern_group.append_kind(pattern_path());
+
+        let root = Node::new(NodeKind::Group(Group::default()));
+        root.append_kind(pattern_path());
+
+        let tree = Tree::new(
+            Size::new(10.0, 10.0).unwrap(),
+            ViewBox {
+                rect: Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+                aspect: AspectRatio::default(),
+            },
+            root,
+        );
+
+        assert_eq!(tree.count_isolated_groups(), 1);
+        assert_eq!(tree.max_isolated_groups_depth(), 1);
+    }
+
+    #[test]
+    fn isolated_groups_depth_with_hidden_group() {
+        let root = Node::new(NodeKind::Group(Group::default()));
+        let g1 = root.append_kind(NodeKind::Group(Group {
+            isolate: true,
+            ..Group::default()
+        }));
+        let hidden = g1.append_kind(NodeKind::Group(Group {
+            isolate: true,
+            hidden: true,
+            ..Group::default()
+        }));
+        hidden.append_kind(NodeKind::Group(Group {
+            isolate: true,
+            ..Group::default()
+        }));
+
+        let tree = Tree::new(
+            Size::new(10.0, 10.0).unwrap(),
+            ViewBox {
+                rect: Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+                aspect: AspectRatio::default(),
+            },
+            root,
+        );
+
+        assert_eq!(tree.max_isolated_groups_depth(), 1);
+    }
+
+    #[test]
+    fn is_empty_group() {
+        let group = Node::new(NodeKind::Group(Group {
+            isolate: true,
+            ..Group::default()
+        }));
+        assert!(group.is_empty_group());
+
+        group.append_kind(NodeKind::Group(Group::default()));
+        assert!(!group.is_empty_group());
+        assert!(group.first_child().unwrap().is_empty_group());
+
+        let path = Node::new(NodeKind::Path(Path::default()));
+        assert!(!path.is_empty_group());
+    }
+
+    #[test]
+    fn blend_mode_names() {
+        use std::str::FromStr;
+
+        let modes = [
+            BlendMode::Normal,
+            BlendMode::Multiply,
+            BlendMode::Screen,
+            BlendMode::Overlay,
+            BlendMode::Darken,
+            BlendMode::Lighten,
+            BlendMode::ColorDodge,
+            BlendMode::ColorBurn,
+            BlendMode::HardLight,
+            BlendMode::SoftLight,
+            BlendMode::Difference,
+            BlendMode::Exclusion,
+            BlendMode::Hue,
+            BlendMode::Saturation,
+            BlendMode::Color,
+            BlendMode::Luminosity,
+        ];
+
+        for mode in modes {
+            assert_eq!(BlendMode::from_str(mode.to_svg_name()), Ok(mode));
+        }
+
+        assert_eq!(BlendMode::ColorDodge.to_svg_name(), "color-dodge");
+        assert!(BlendMode::from_str("colorDodge").is_err());
+    }
+
+    #[test]
+    fn image_rendering_names() {
+        use std::str::FromStr;
+
+        assert_eq!(
+            ImageRendering::from_str("optimizeQuality"),
+            Ok(ImageRendering::OptimizeQuality)
+        );
+        assert_eq!(
+            ImageRendering::from_str("optimizeSpeed"),
+            Ok(ImageRendering::OptimizeSpeed)
+        );
+        assert_eq!(
+            ImageRendering::from_str("pixelated"),
+            Ok(ImageRendering::OptimizeSpeed)
+        );
+        assert_eq!(
+            ImageRendering::from_str("crisp-edges"),
+            Ok(ImageRendering::OptimizeSpeed)
+        );
+        assert!(ImageRendering::from_str("crispEdges").is_err());
+        assert!(ImageRendering::from_str("auto").is_err());
+        assert_eq!(ImageRendering::default(), ImageRendering::OptimizeQuality);
+    }
+
+    #[test]
+    fn color_keyword() {
+        assert_eq!(Color::from_keyword("Red"), Some(Color::new_rgb(255, 0, 0)));
+        assert_eq!(Color::from_keyword("#ff0000"), None);
+        assert_eq!(Color::from_keyword("transparent"), None);
+        assert_eq!(Color::new_rgb(255, 0, 0).to_keyword(), Some("red"));
+        assert_eq!(Color::new_rgb(0, 255, 255).to_keyword(), Some("aqua"));
+        assert_eq!(Color::new_rgb(1, 2, 3).to_keyword(), None);
     }
 }
@@ -0,0 +1,89 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Tree post-processing passes.
+//!
+//! See [`Tree::run_passes`](crate::Tree::run_passes).
+
+use crate::{Color, Fill, NodeKind, Paint, Stroke, Tree};
+
+/// A tree post-processing pass.
+pub trait TreePass {
+    /// Runs the pass on the tree.
+    fn run(&self, tree: &mut Tree);
+}
+
+/// Removes groups that do not impact rendering.
+///
+/// See [`Tree::remove_empty_groups`] for details.
+#[derive(Clone, Copy, Debug)]
+pub struct RemoveEmptyGroups;
+
+impl TreePass for RemoveEmptyGroups {
+    fn run(&self, tree: &mut Tree) {
+        tree.remove_empty_groups();
+    }
+}
+
+/// Folds opacity of groups with a single child into this child.
+///
+/// See [`Tree::fold_group_opacity`] for details.
+#[derive(Clone, Copy, Debug)]
+pub struct FoldGroupOpacity;
+
+impl TreePass for FoldGroupOpacity {
+    fn run(&self, tree: &mut Tree) {
+        tree.fold_group_opacity();
+    }
+}
+
+/// Replaces solid fill and stroke colors using the provided function.
+///
+/// Paths, text spans and text decorations are affected, including the ones
+/// inside clip paths, masks and patterns. Gradient stops are not affected.
+pub struct RemapColors<F: Fn(Color) -> Color>(pub F);
+
+impl<F: Fn(Color) -> Color> std::fmt::Debug for RemapColors<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("RemapColors")
+    }
+}
+
+impl<F: Fn(Color) -> Color> TreePass for RemapColors<F> {
+    fn run(&self, tree: &mut Tree) {
+        tree.for_each_node_mut(|kind| match kind {
+            NodeKind::Path(ref mut path) => {
+                remap_style(path.fill.as_mut(), path.stroke.as_mut(), &self.0);
+            }
+            NodeKind::Text(ref mut text) => {
+                for span in text.chunks.iter_mut().flat_map(|c| &mut c.spans) {
+                    remap_style(span.fill.as_mut(), span.stroke.as_mut(), &self.0);
+
+                    let decoration = &mut span.decoration;
+                    for style in [
+                        &mut decoration.underline,
+                        &mut decoration.overline,
+                        &mut decoration.line_through,
+                    ]
+                    .iter_mut()
+                    .filter_map(|d| d.as_mut())
+                    {
+                        remap_style(style.fill.as_mut(), style.stroke.as_mut(), &self.0);
+                    }
+                }
+            }
+            NodeKind::Group(_) | NodeKind::Image(_) => {}
+        });
+    }
+}
+
+fn remap_style(fill: Option<&mut Fill>, stroke: Option<&mut Stroke>, f: &dyn Fn(Color) -> Color) {
+    let fill = fill.map(|v| &mut v.paint);
+    let stroke = stroke.map(|v| &mut v.paint);
+    for paint in fill.into_iter().chain(stroke) {
+        if let Paint::Color(ref mut c) = *paint {
+            *c = f(*c);
+        }
+    }
+}
@@ -2,13 +2,14 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use kurbo::{ParamCurve, ParamCurveArclen, ParamCurveExtrema};
+use kurbo::{ParamCurve, ParamCurveArclen, ParamCurveDeriv, ParamCurveExtrema};
 
-use crate::{FuzzyZero, PathBbox, Rect, Transform};
+use crate::{FuzzyZero, PathBbox, Point, Rect, Transform};
 
 /// A path command.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PathCommand {
     MoveTo,
     LineTo,
@@ -45,10 +46,53 @@ pub enum PathSegment {
 /// An SVG path data container.
 ///
 /// All segments are in absolute coordinates.
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default)]
 pub struct PathData {
     commands: Vec<PathCommand>,
     points: Vec<f64>,
+    // A lazily calculated `bbox()` result.
+    // Must be reset by every method that modifies `commands` or `points`.
+    bbox: BboxCache,
+}
+
+/// A `PathData::bbox` cache.
+///
+/// Uses a `Mutex` instead of a `Cell`, so `PathData` stays `Sync`
+/// and can still be shared between threads.
+#[derive(Default)]
+struct BboxCache(std::sync::Mutex<Option<Option<PathBbox>>>);
+
+impl BboxCache {
+    #[inline]
+    fn get(&self) -> Option<Option<PathBbox>> {
+        *self.0.lock().unwrap()
+    }
+
+    #[inline]
+    fn set(&self, bbox: Option<PathBbox>) {
+        *self.0.lock().unwrap() = Some(bbox);
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        *self.0.get_mut().unwrap() = None;
+    }
+}
+
+impl Clone for BboxCache {
+    fn clone(&self) -> Self {
+        BboxCache(std::sync::Mutex::new(self.get()))
+    }
+}
+
+// The bbox cache is an implementation detail and must not be printed.
+impl std::fmt::Debug for PathData {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("PathData")
+            .field("commands", &self.commands)
+            .field("points", &self.points)
+            .finish()
+    }
 }
 
 impl PathData {
@@ -82,8 +126,26 @@ impl PathData {
         &self.points
     }
 
+    /// Returns a copy of the path points with a single precision.
+    ///
+    /// Useful for memory-constrained targets, since it halves the points memory usage.
+    /// Points are stored in the same order as `PathData::points`,
+    /// so `PathData::commands` can still be used to interpret them.
+    ///
+    /// `f32` has only 24 bits of mantissa, so coordinates larger than `16777216`
+    /// cannot be represented exactly even when they are integers, and smaller coordinates
+    /// are rounded to roughly 7 significant digits. Values outside of the `f32` range
+    /// are clamped to `f32::MIN`/`f32::MAX` instead of becoming infinite.
+    pub fn to_f32_points(&self) -> Vec<f32> {
+        self.points
+            .iter()
+            .map(|n| n.max(f32::MIN as f64).min(f32::MAX as f64) as f32)
+            .collect()
+    }
+
     /// Clears the path.
     pub fn clear(&mut self) {
+        self.bbox.reset();
         self.commands.clear();
         self.points.clear();
     }
@@ -102,9 +164,70 @@ impl PathData {
         path
     }
 
+    /// Creates a circle path.
+    ///
+    /// Same as `PathData::from_ellipse(cx, cy, r, r)`.
+    #[inline]
+    pub fn from_circle(cx: f64, cy: f64, r: f64) -> Self {
+        Self::from_ellipse(cx, cy, r, r)
+    }
+
+    /// Creates an ellipse path.
+    ///
+    /// The ellipse is built from four clockwise arcs starting at `(cx + rx, cy)`,
+    /// which are converted into cubic curves by `PathData::push_arc_to`,
+    /// exactly like the `ellipse` and `circle` elements are converted.
+    pub fn from_ellipse(cx: f64, cy: f64, rx: f64, ry: f64) -> Self {
+        let mut p = PathData::new();
+        p.push_move_to(cx + rx, cy);
+        p.push_arc_to(rx, ry, 0.0, false, true, cx, cy + ry);
+        p.push_arc_to(rx, ry, 0.0, false, true, cx - rx, cy);
+        p.push_arc_to(rx, ry, 0.0, false, true, cx, cy - ry);
+        p.push_arc_to(rx, ry, 0.0, false, true, cx + rx, cy);
+        p.push_close_path();
+        p
+    }
+
+    /// Creates a rounded rect path.
+    ///
+    /// `rx` and `ry` are clamped to the `0..=half` of the rect width and height.
+    /// When either of them is zero, a plain rect will be created.
+    ///
+    /// Follows the `rect` element conversion from the SVG spec,
+    /// with corners built by `PathData::push_arc_to`.
+    pub fn from_rounded_rect(rect: Rect, rx: f64, ry: f64) -> Self {
+        let rx = rx.max(0.0).min(rect.width() / 2.0);
+        let ry = ry.max(0.0).min(rect.height() / 2.0);
+        if rx.is_fuzzy_zero() || ry.is_fuzzy_zero() {
+            return Self::from_rect(rect);
+        }
+
+        let (x, y, width, height) = (rect.x(), rect.y(), rect.width(), rect.height());
+
+        let mut p = PathData::new();
+        p.push_move_to(x + rx, y);
+
+        p.push_line_to(x + width - rx, y);
+        p.push_arc_to(rx, ry, 0.0, false, true, x + width, y + ry);
+
+        p.push_line_to(x + width, y + height - ry);
+        p.push_arc_to(rx, ry, 0.0, false, true, x + width - rx, y + height);
+
+        p.push_line_to(x + rx, y + height);
+        p.push_arc_to(rx, ry, 0.0, false, true, x, y + height - ry);
+
+        p.push_line_to(x, y + ry);
+        p.push_arc_to(rx, ry, 0.0, false, true, x + rx, y);
+
+        p.push_close_path();
+
+        p
+    }
+
     /// Pushes a MoveTo segment to the path.
     #[inline]
     pub fn push_move_to(&mut self, x: f64, y: f64) {
+        self.bbox.reset();
         self.commands.push(PathCommand::MoveTo);
         self.points.push(x);
         self.points.push(y);
@@ -114,6 +237,7 @@ impl PathData {
     /// Pushes a LineTo segment to the path.
     #[inline]
     pub fn push_line_to(&mut self, x: f64, y: f64) {
+        self.bbox.reset();
         self.commands.push(PathCommand::LineTo);
         self.points.push(x);
         self.points.push(y);
@@ -136,6 +260,7 @@ impl PathData {
     /// Pushes a CurveTo segment to the path.
     #[inline]
     pub fn push_curve_to(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, x: f64, y: f64) {
+        self.bbox.reset();
         self.commands.push(PathCommand::CurveTo);
         self.points.push(x1);
         self.points.push(y1);
@@ -184,6 +309,7 @@ impl PathData {
     /// Pushes a ClosePath segment to the path.
     #[inline]
     pub fn push_close_path(&mut self) {
+        self.bbox.reset();
         self.commands.push(PathCommand::ClosePath);
     }
 
@@ -200,6 +326,7 @@ impl PathData {
     /// Pushes a path to the path.
     #[inline]
     pub fn push_path(&mut self, path: &PathData) {
+        self.bbox.reset();
         self.commands.extend_from_slice(&path.commands);
         self.points.extend_from_slice(&path.points);
     }
@@ -220,14 +347,25 @@ impl PathData {
 
     /// Calculates path's bounding box.
     ///
-    /// This operation is expensive.
+    /// Calculated on the first call and cached afterwards.
+    /// The cache is reset by every method that modifies the path,
+    /// so it can never be outdated.
     #[inline]
     pub fn bbox(&self) -> Option<PathBbox> {
-        calc_bbox(self)
+        if let Some(bbox) = self.bbox.get() {
+            return bbox;
+        }
+
+        let bbox = calc_bbox(self);
+        self.bbox.set(bbox);
+        bbox
     }
 
     /// Calculates path's bounding box with a specified transform.
     ///
+    /// Unlike `bbox`, the result is not cached, since it depends on the transform
+    /// and the stroke, which are not part of the path.
+    ///
     /// This operation is expensive.
     #[inline]
     pub fn bbox_with_transform(
@@ -235,7 +373,19 @@ impl PathData {
         ts: Transform,
         stroke: Option<&super::Stroke>,
     ) -> Option<PathBbox> {
-        calc_bbox_with_transform(self, ts, stroke)
+        calc_bbox_with_transform(self, ts, stroke.map(|s| s.width.get()))
+    }
+
+    /// Calculates path's fill bounding box with a specified transform.
+    ///
+    /// Unlike `bbox_with_transform`, stroke is never taken into account,
+    /// so the result covers only the path geometry itself.
+    /// Useful when stroke is irrelevant, like for clip-path geometry.
+    ///
+    /// This operation is expensive.
+    #[inline]
+    pub fn fill_bbox(&self, ts: Transform) -> Option<PathBbox> {
+        calc_bbox_with_transform(self, ts, None)
     }
 
     /// Checks that path has a bounding box.
@@ -248,7 +398,7 @@ impl PathData {
 
     /// Calculates path's length.
     ///
-    /// Length from the first segment to the first MoveTo, ClosePath or slice end.
+    /// Lengths of all subpaths are summed. Gaps between subpaths are ignored.
     ///
     /// This operation is expensive.
     #[inline]
@@ -256,12 +406,182 @@ impl PathData {
         calc_length(self)
     }
 
+    /// Returns a point and a tangent angle in degrees at the specified distance along the path.
+    ///
+    /// Gaps between subpaths are ignored, like in `PathData::length`.
+    /// Out of range distances are clamped to the path start or end.
+    ///
+    /// Returns `None` when path has no segments besides MoveTo.
+    ///
+    /// This operation is expensive.
+    pub fn point_at_length(&self, len: f64) -> Option<(Point<f64>, f64)> {
+        let len = if len.is_finite() { len.max(0.0) } else { 0.0 };
+
+        let mut length = 0.0;
+        let mut last_curve = None;
+        for curve in cubic_curves(self) {
+            let curve_len = curve.arclen(ARCLEN_ACCURACY);
+            if len <= length + curve_len {
+                let t = curve.inv_arclen(len - length, ARCLEN_ACCURACY);
+                return Some(eval_curve(&curve, t.clamp(0.0, 1.0)));
+            }
+
+            length += curve_len;
+            last_curve = Some(curve);
+        }
+
+        last_curve.map(|curve| eval_curve(&curve, 1.0))
+    }
+
+    /// Calculates path's signed area.
+    ///
+    /// Areas of all subpaths are summed. Open subpaths are treated as closed,
+    /// just like during filling. Curves are integrated exactly.
+    ///
+    /// The area is positive for clockwise paths, assuming the SVG coordinate system,
+    /// where the Y-axis points down.
+    pub fn signed_area(&self) -> f64 {
+        use kurbo::Shape;
+        to_closed_bez_path(self).area()
+    }
+
+    /// Checks that the path has a clockwise orientation.
+    ///
+    /// I.e. its `signed_area` is positive.
+    #[inline]
+    pub fn is_clockwise(&self) -> bool {
+        self.signed_area() > 0.0
+    }
+
+    /// Checks that the point is inside the path filled with the specified fill rule.
+    ///
+    /// Open subpaths are treated as closed, just like during filling.
+    /// Curve intersections are solved analytically.
+    pub fn contains_point(&self, p: Point<f64>, rule: super::FillRule) -> bool {
+        use kurbo::Shape;
+        let winding = to_closed_bez_path(self).winding(kurbo::Point::new(p.x, p.y));
+        match rule {
+            super::FillRule::NonZero => winding != 0,
+            super::FillRule::EvenOdd => winding & 1 != 0,
+        }
+    }
+
+    /// Converts all curves into line segments.
+    ///
+    /// `tolerance` is the maximum distance between a curve and its approximation
+    /// in user units. Values smaller than `0.001` will be clamped.
+    ///
+    /// Uses adaptive subdivision, so flat curves will produce fewer segments.
+    pub fn flatten_to_lines(&self, tolerance: f64) -> PathData {
+        let tolerance = tolerance.max(0.001);
+
+        let elements = self.segments().map(|seg| match seg {
+            PathSegment::MoveTo { x, y } => kurbo::PathEl::MoveTo(kurbo::Point::new(x, y)),
+            PathSegment::LineTo { x, y } => kurbo::PathEl::LineTo(kurbo::Point::new(x, y)),
+            PathSegment::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => kurbo::PathEl::CurveTo(
+                kurbo::Point::new(x1, y1),
+                kurbo::Point::new(x2, y2),
+                kurbo::Point::new(x, y),
+            ),
+            PathSegment::ClosePath => kurbo::PathEl::ClosePath,
+        });
+
+        let mut path = PathData::new();
+        kurbo::flatten(elements, tolerance, |el| match el {
+            kurbo::PathEl::MoveTo(p) => path.push_move_to(p.x, p.y),
+            kurbo::PathEl::LineTo(p) => path.push_line_to(p.x, p.y),
+            kurbo::PathEl::ClosePath => path.push_close_path(),
+            // `flatten` produces only lines.
+            kurbo::PathEl::QuadTo(..) | kurbo::PathEl::CurveTo(..) => {}
+        });
+
+        path
+    }
+
+    /// Returns a path with a reversed direction of each subpath.
+    ///
+    /// The subpaths order is preserved. Closed subpaths remain closed,
+    /// but will start at the point the original subpath ended before closing.
+    pub fn reverse(&self) -> PathData {
+        let mut path = PathData::new();
+        let mut subpath = Vec::new();
+        let mut start = (0.0, 0.0);
+        let mut prev = (0.0, 0.0);
+        for seg in self.segments() {
+            match seg {
+                PathSegment::MoveTo { x, y } => {
+                    push_reversed_subpath(prev, &subpath, false, &mut path);
+                    subpath.clear();
+                    start = (x, y);
+                    prev = start;
+                }
+                PathSegment::LineTo { x, y } => {
+                    subpath.push((prev, None));
+                    prev = (x, y);
+                }
+                PathSegment::CurveTo {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => {
+                    subpath.push((prev, Some(((x1, y1), (x2, y2)))));
+                    prev = (x, y);
+                }
+                PathSegment::ClosePath => {
+                    push_reversed_subpath(prev, &subpath, true, &mut path);
+                    subpath.clear();
+                    // A segment after ClosePath starts at the subpath start.
+                    prev = start;
+                }
+            }
+        }
+
+        push_reversed_subpath(prev, &subpath, false, &mut path);
+
+        path
+    }
+
+    /// Splits the path into dashes.
+    ///
+    /// Follows the `stroke-dasharray` and `stroke-dashoffset` semantics:
+    /// an odd list of dashes is repeated to make it even, an offset can be negative
+    /// or larger than the pattern length and the pattern restarts at each subpath.
+    /// Dashes separated by a zero-length gap are merged, and zero-length dashes
+    /// are preserved as zero-length subpaths, so they can still be rendered using caps.
+    ///
+    /// Returns an unchanged path when the pattern is invalid,
+    /// i.e. is empty, has negative values or the sum of zero.
+    pub fn apply_dash(&self, dashes: &[f64], offset: f64) -> PathData {
+        dash_path(self, dashes, offset)
+    }
+
     /// Applies the transform to the path.
     #[inline]
     pub fn transform(&mut self, ts: Transform) {
+        self.bbox.reset();
         transform_path(&mut self.points, ts);
     }
 
+    /// Returns a copy of the path with the transform applied to every point.
+    ///
+    /// Segment types are preserved, i.e. lines stay lines and curves stay curves.
+    #[inline]
+    pub fn transformed(&self, ts: Transform) -> PathData {
+        let mut path = self.clone();
+        path.transform(ts);
+        path
+    }
+
     /// Applies the transform to the path from the specified offset.
     #[inline]
     pub fn transform_from(&mut self, offset: usize, ts: Transform) {
@@ -274,13 +594,105 @@ impl PathData {
             }
         }
 
+        self.bbox.reset();
         transform_path(&mut self.points[points_offset..], ts);
     }
 
+    /// Parses a path from an SVG `d` attribute value.
+    ///
+    /// Relative segments are converted into absolute ones, while horizontal, vertical,
+    /// smooth, quadratic and arc segments are converted into `L` and `C` segments,
+    /// exactly like during the `path` element conversion.
+    ///
+    /// Returns an error, which includes a position when available, on invalid input.
+    /// Use `PathData::from_svg_string_partial` to keep segments before the error.
+    pub fn from_svg_string(text: &str) -> Result<PathData, svgtypes::Error> {
+        match PathData::from_svg_string_partial(text) {
+            (path, None) => Ok(path),
+            (_, Some(e)) => Err(e),
+        }
+    }
+
+    /// Parses a path from an SVG `d` attribute value up to the first error.
+    ///
+    /// Follows the SVG error handling, where all segments before an error
+    /// are still rendered. Returns parsed segments and an error, if any.
+    pub fn from_svg_string_partial(text: &str) -> (PathData, Option<svgtypes::Error>) {
+        let mut path = PathData::new();
+        for segment in svgtypes::SimplifyingPathParser::from(text) {
+            let segment = match segment {
+                Ok(v) => v,
+                Err(e) => return (path, Some(e)),
+            };
+
+            match segment {
+                svgtypes::SimplePathSegment::MoveTo { x, y } => {
+                    path.push_move_to(x, y);
+                }
+                svgtypes::SimplePathSegment::LineTo { x, y } => {
+                    path.push_line_to(x, y);
+                }
+                svgtypes::SimplePathSegment::CurveTo {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => {
+                    path.push_curve_to(x1, y1, x2, y2, x, y);
+                }
+                svgtypes::SimplePathSegment::Quadratic { x1, y1, x, y } => {
+                    path.push_quad_to(x1, y1, x, y);
+                }
+                svgtypes::SimplePathSegment::ClosePath => {
+                    path.push_close_path();
+                }
+            }
+        }
+
+        (path, None)
+    }
+
+    /// Writes the path as an SVG `d` attribute value.
+    ///
+    /// Uses only absolute `M`, `L`, `C` and `Z` commands separated by spaces.
+    /// Numbers are rounded to `precision` decimal digits, which is clamped to 12,
+    /// and written without trailing zeros. A negative number rounded to zero is written as `0`.
+    pub fn to_svg_string(&self, precision: u8) -> String {
+        path_to_svg_string(self, precision, false)
+    }
+
+    /// Writes the path as a compact SVG `d` attribute value.
+    ///
+    /// Same as `PathData::to_svg_string`, but `L` and `C` command letters are omitted
+    /// when they repeat the previous command. `M` and `Z` are always written,
+    /// since coordinates following `M` are treated as an implicit `L`.
+    pub fn to_compact_svg_string(&self, precision: u8) -> String {
+        path_to_svg_string(self, precision, true)
+    }
+
     /// Returns an iterator over path segments.
     #[inline]
     pub fn segments(&self) -> PathSegmentsIter {
         PathSegmentsIter {
+            path: self,
+            cmd_index: 0,
+            cmd_end: self.commands.len(),
+            points_index: 0,
+        }
+    }
+
+    /// Returns an iterator over subpaths.
+    ///
+    /// A subpath starts at `MoveTo` and ends either right after `ClosePath`
+    /// or right before the next `MoveTo`.
+    /// Segments following `ClosePath` without an explicit `MoveTo`
+    /// are yielded as a separate subpath, which implicitly starts
+    /// at the start of the previous one.
+    #[inline]
+    pub fn subpaths(&self) -> SubPathsIter<'_> {
+        SubPathsIter {
             path: self,
             cmd_index: 0,
             points_index: 0,
@@ -294,6 +706,7 @@ impl PathData {
 pub struct PathSegmentsIter<'a> {
     path: &'a PathData,
     cmd_index: usize,
+    cmd_end: usize,
     points_index: usize,
 }
 
@@ -301,7 +714,7 @@ impl<'a> Iterator for PathSegmentsIter<'a> {
     type Item = PathSegment;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.cmd_index < self.path.commands.len() {
+        if self.cmd_index < self.cmd_end {
             let verb = self.path.commands[self.cmd_index];
             self.cmd_index += 1;
 
@@ -339,6 +752,95 @@ impl<'a> Iterator for PathSegmentsIter<'a> {
     }
 }
 
+/// A read-only view of a single subpath.
+#[derive(Clone, Copy, Debug)]
+pub struct SubPath<'a> {
+    path: &'a PathData,
+    cmd_start: usize,
+    cmd_end: usize,
+    points_start: usize,
+    closed: bool,
+}
+
+impl<'a> SubPath<'a> {
+    /// Returns a range of the subpath commands inside `PathData::commands`.
+    #[inline]
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.cmd_start..self.cmd_end
+    }
+
+    /// Checks that the subpath ends with `ClosePath`.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Returns subpath commands.
+    #[inline]
+    pub fn commands(&self) -> &'a [PathCommand] {
+        &self.path.commands[self.range()]
+    }
+
+    /// Returns an iterator over subpath segments.
+    #[inline]
+    pub fn segments(&self) -> PathSegmentsIter<'a> {
+        PathSegmentsIter {
+            path: self.path,
+            cmd_index: self.cmd_start,
+            cmd_end: self.cmd_end,
+            points_index: self.points_start,
+        }
+    }
+}
+
+/// A path subpaths iterator.
+#[allow(missing_debug_implementations)]
+#[derive(Clone)]
+pub struct SubPathsIter<'a> {
+    path: &'a PathData,
+    cmd_index: usize,
+    points_index: usize,
+}
+
+impl<'a> Iterator for SubPathsIter<'a> {
+    type Item = SubPath<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let commands = &self.path.commands;
+        if self.cmd_index >= commands.len() {
+            return None;
+        }
+
+        let cmd_start = self.cmd_index;
+        let points_start = self.points_index;
+        let mut closed = false;
+        while self.cmd_index < commands.len() {
+            let cmd = commands[self.cmd_index];
+            if cmd == PathCommand::MoveTo && self.cmd_index != cmd_start {
+                break;
+            }
+
+            self.cmd_index += 1;
+            match cmd {
+                PathCommand::MoveTo | PathCommand::LineTo => self.points_index += 2,
+                PathCommand::CurveTo => self.points_index += 6,
+                PathCommand::ClosePath => {
+                    closed = true;
+                    break;
+                }
+            }
+        }
+
+        Some(SubPath {
+            path: self.path,
+            cmd_start,
+            cmd_end: self.cmd_index,
+            points_start,
+            closed,
+        })
+    }
+}
+
 fn calc_bbox(path: &PathData) -> Option<PathBbox> {
     if path.is_empty() {
         return None;
@@ -406,10 +908,10 @@ fn calc_bbox(path: &PathData) -> Option<PathBbox> {
     PathBbox::new(minx, miny, width, height)
 }
 
-fn calc_bbox_with_transform(
+pub(crate) fn calc_bbox_with_transform(
     path: &PathData,
     ts: Transform,
-    stroke: Option<&super::Stroke>,
+    stroke_width: Option<f64>,
 ) -> Option<PathBbox> {
     if path.is_empty() {
         return None;
@@ -482,8 +984,8 @@ fn calc_bbox_with_transform(
 
     // TODO: find a better way
     // It's an approximation, but it's better than nothing.
-    if let Some(stroke) = stroke {
-        let w = stroke.width.get()
+    if let Some(stroke_width) = stroke_width {
+        let w = stroke_width
             / if ts.is_default() {
                 2.0
             } else {
@@ -571,15 +1073,318 @@ fn has_bbox(path: &PathData) -> bool {
     false
 }
 
-fn calc_length(path: &PathData) -> f64 {
-    if path.is_empty() {
-        return 0.0;
+type Coord = (f64, f64);
+
+/// Appends a reversed subpath.
+///
+/// Each segment is stored as its start point and optional curve control points.
+fn push_reversed_subpath(
+    end: Coord,
+    segments: &[(Coord, Option<(Coord, Coord)>)],
+    closed: bool,
+    path: &mut PathData,
+) {
+    if segments.is_empty() && !closed {
+        return;
+    }
+
+    path.push_move_to(end.0, end.1);
+    for &(start, controls) in segments.iter().rev() {
+        match controls {
+            Some((c1, c2)) => path.push_curve_to(c2.0, c2.1, c1.0, c1.1, start.0, start.1),
+            None => path.push_line_to(start.0, start.1),
+        }
+    }
+
+    if closed {
+        path.push_close_path();
+    }
+}
+
+/// An arc length accuracy used during dashing.
+const DASH_ACCURACY: f64 = 0.01;
+
+#[derive(Clone, Copy)]
+enum DashSegment {
+    Line(kurbo::Line),
+    Curve(kurbo::CubicBez),
+}
+
+impl DashSegment {
+    fn start(&self) -> kurbo::Point {
+        match self {
+            DashSegment::Line(line) => line.p0,
+            DashSegment::Curve(curve) => curve.p0,
+        }
+    }
+
+    fn length(&self) -> f64 {
+        match self {
+            DashSegment::Line(line) => line.arclen(DASH_ACCURACY),
+            DashSegment::Curve(curve) => curve.arclen(DASH_ACCURACY),
+        }
+    }
+
+    /// Returns a part of the segment between two arc lengths.
+    fn subsegment(&self, from: f64, to: f64) -> Self {
+        match self {
+            DashSegment::Line(line) => {
+                if from == 0.0 && to >= self.length() {
+                    // Also prevents division by zero on zero-length lines.
+                    return *self;
+                }
+
+                let t0 = line.inv_arclen(from, DASH_ACCURACY);
+                let t1 = line.inv_arclen(to, DASH_ACCURACY);
+                DashSegment::Line(line.subsegment(t0..t1))
+            }
+            DashSegment::Curve(curve) => {
+                let t0 = curve.inv_arclen(from, DASH_ACCURACY);
+                let t1 = curve.inv_arclen(to, DASH_ACCURACY);
+                DashSegment::Curve(curve.subsegment(t0..t1))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct DashState {
+    index: usize,
+    remaining: f64,
+    /// Indicates that the current index points to a dash and not a gap.
+    on: bool,
+}
+
+struct Dasher<'a> {
+    dashes: &'a [f64],
+    initial: DashState,
+    state: DashState,
+    /// Indicates that the current subpath has a non-zero gap.
+    broken: bool,
+    current: Option<Vec<DashSegment>>,
+    pieces: Vec<Vec<DashSegment>>,
+    path: PathData,
+}
+
+fn dash_path(path: &PathData, dashes: &[f64], offset: f64) -> PathData {
+    if dashes.is_empty() || dashes.iter().any(|n| !n.is_finite() || *n < 0.0) {
+        return path.clone();
+    }
+
+    // An odd list is repeated to make it even.
+    let dashes: Vec<f64> = if dashes.len() % 2 == 1 {
+        dashes.iter().chain(dashes.iter()).copied().collect()
+    } else {
+        dashes.to_vec()
+    };
+
+    let sum: f64 = dashes.iter().sum();
+    if !(sum > 0.0) || !offset.is_finite() {
+        return path.clone();
     }
 
-    let mut prev_mx = path.points[0];
-    let mut prev_my = path.points[1];
-    let mut prev_x = prev_mx;
-    let mut prev_y = prev_my;
+    let mut offset = offset % sum;
+    if offset < 0.0 {
+        offset += sum;
+    }
+
+    let mut index = 0;
+    let mut on = true;
+    while offset > 0.0 && offset >= dashes[index] {
+        offset -= dashes[index];
+        index = (index + 1) % dashes.len();
+        on = !on;
+    }
+
+    let initial = DashState {
+        index,
+        remaining: dashes[index] - offset,
+        on,
+    };
+
+    let mut dasher = Dasher {
+        dashes: &dashes,
+        initial,
+        state: initial,
+        broken: false,
+        current: None,
+        pieces: Vec::new(),
+        path: PathData::new(),
+    };
+
+    let mut start = kurbo::Point::ZERO;
+    let mut prev = kurbo::Point::ZERO;
+    let mut has_segments = false;
+    for seg in path.segments() {
+        match seg {
+            PathSegment::MoveTo { x, y } => {
+                dasher.finish_subpath(false);
+                start = kurbo::Point::new(x, y);
+                prev = start;
+                has_segments = false;
+            }
+            PathSegment::LineTo { x, y } => {
+                let p = kurbo::Point::new(x, y);
+                dasher.process(DashSegment::Line(kurbo::Line::new(prev, p)));
+                prev = p;
+                has_segments = true;
+            }
+            PathSegment::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                let p = kurbo::Point::new(x, y);
+                let curve = kurbo::CubicBez::new(
+                    prev,
+                    kurbo::Point::new(x1, y1),
+                    kurbo::Point::new(x2, y2),
+                    p,
+                );
+                dasher.process(DashSegment::Curve(curve));
+                prev = p;
+                has_segments = true;
+            }
+            PathSegment::ClosePath => {
+                // A zero-length closing segment matters only for `M x y Z` subpaths.
+                if !has_segments || prev != start {
+                    dasher.process(DashSegment::Line(kurbo::Line::new(prev, start)));
+                }
+
+                dasher.finish_subpath(true);
+                prev = start;
+                has_segments = false;
+            }
+        }
+    }
+
+    dasher.finish_subpath(false);
+    dasher.path
+}
+
+impl Dasher<'_> {
+    fn process(&mut self, seg: DashSegment) {
+        let len = seg.length();
+        let mut pos = 0.0;
+        loop {
+            let left = len - pos;
+            if self.state.remaining > left {
+                // The current dash or gap continues after this segment.
+                if self.state.on {
+                    // Zero-length segments are preserved, so `M x y L x y` subpaths
+                    // can still be rendered using caps.
+                    if left > 0.0 || (len == 0.0 && self.current.is_none()) {
+                        self.push(seg.subsegment(pos, len));
+                    }
+                } else if left > 0.0 {
+                    self.broken = true;
+                }
+
+                self.state.remaining -= left;
+                break;
+            }
+
+            let end = pos + self.state.remaining;
+            if self.state.on {
+                if self.state.remaining > 0.0 || self.current.is_none() {
+                    self.push(seg.subsegment(pos, end));
+                }
+
+                // Dashes separated by a zero-length gap are merged,
+                // therefore they will be joined and not capped.
+                let next_gap = self.dashes[(self.state.index + 1) % self.dashes.len()];
+                if next_gap > 0.0 {
+                    if let Some(piece) = self.current.take() {
+                        self.pieces.push(piece);
+                    }
+                }
+            } else if self.state.remaining > 0.0 {
+                self.broken = true;
+            }
+
+            pos = end;
+            self.state.index = (self.state.index + 1) % self.dashes.len();
+            self.state.remaining = self.dashes[self.state.index];
+            self.state.on = !self.state.on;
+        }
+    }
+
+    fn push(&mut self, seg: DashSegment) {
+        self.current.get_or_insert_with(Vec::new).push(seg);
+    }
+
+    fn finish_subpath(&mut self, closed: bool) {
+        let ends_on = self.current.is_some();
+        if let Some(piece) = self.current.take() {
+            self.pieces.push(piece);
+        }
+
+        let mut pieces = std::mem::take(&mut self.pieces);
+        if closed && !self.broken && pieces.len() == 1 {
+            // The whole subpath is covered by a single dash.
+            // The closing segment is replaced by ClosePath.
+            let mut piece = pieces.remove(0);
+            if let Some(DashSegment::Line(line)) = piece.last() {
+                if piece.len() > 1 && line.p1 == piece[0].start() {
+                    piece.pop();
+                }
+            }
+
+            Self::push_piece(&piece, &mut self.path);
+            self.path.push_close_path();
+        } else {
+            // Join the last and the first dash when the closed subpath starts and ends
+            // inside the same dash.
+            let starts_on = self.initial.on && self.initial.remaining > 0.0;
+            if closed && starts_on && ends_on && pieces.len() > 1 {
+                let first = pieces.remove(0);
+                pieces.last_mut().unwrap().extend(first);
+            }
+
+            for piece in &pieces {
+                Self::push_piece(piece, &mut self.path);
+            }
+        }
+
+        self.state = self.initial;
+        self.broken = false;
+    }
+
+    fn push_piece(piece: &[DashSegment], path: &mut PathData) {
+        let start = match piece.first() {
+            Some(seg) => seg.start(),
+            None => return,
+        };
+
+        path.push_move_to(start.x, start.y);
+        for seg in piece {
+            match seg {
+                DashSegment::Line(line) => path.push_line_to(line.p1.x, line.p1.y),
+                DashSegment::Curve(c) => {
+                    path.push_curve_to(c.p1.x, c.p1.y, c.p2.x, c.p2.y, c.p3.x, c.p3.y)
+                }
+            }
+        }
+    }
+}
+
+const ARCLEN_ACCURACY: f64 = 0.5;
+
+fn calc_length(path: &PathData) -> f64 {
+    cubic_curves(path)
+        .map(|curve| curve.arclen(ARCLEN_ACCURACY))
+        .sum()
+}
+
+/// Returns all path segments, except MoveTo, as cubic curves.
+fn cubic_curves(path: &PathData) -> impl Iterator<Item = kurbo::CubicBez> + '_ {
+    let mut prev_mx = 0.0;
+    let mut prev_my = 0.0;
+    let mut prev_x = 0.0;
+    let mut prev_y = 0.0;
 
     fn create_curve_from_line(px: f64, py: f64, x: f64, y: f64) -> kurbo::CubicBez {
         let line = kurbo::Line::new(kurbo::Point::new(px, py), kurbo::Point::new(x, y));
@@ -588,15 +1393,14 @@ fn calc_length(path: &PathData) -> f64 {
         kurbo::CubicBez::from_points(px, py, p1.x, p1.y, p2.x, p2.y, x, y)
     }
 
-    let mut length = 0.0;
-    for seg in path.segments() {
+    path.segments().filter_map(move |seg| {
         let curve = match seg {
             PathSegment::MoveTo { x, y } => {
                 prev_mx = x;
                 prev_my = y;
                 prev_x = x;
                 prev_y = y;
-                continue;
+                return None;
             }
             PathSegment::LineTo { x, y } => create_curve_from_line(prev_x, prev_y, x, y),
             PathSegment::CurveTo {
@@ -610,12 +1414,43 @@ fn calc_length(path: &PathData) -> f64 {
             PathSegment::ClosePath => create_curve_from_line(prev_x, prev_y, prev_mx, prev_my),
         };
 
-        length += curve.arclen(0.5);
         prev_x = curve.p3.x;
         prev_y = curve.p3.y;
+        Some(curve)
+    })
+}
+
+fn to_closed_bez_path(path: &PathData) -> kurbo::BezPath {
+    let mut bez = kurbo::BezPath::new();
+    for subpath in path.subpaths() {
+        for seg in subpath.segments() {
+            match seg {
+                PathSegment::MoveTo { x, y } => bez.move_to((x, y)),
+                PathSegment::LineTo { x, y } => bez.line_to((x, y)),
+                PathSegment::CurveTo {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => bez.curve_to((x1, y1), (x2, y2), (x, y)),
+                PathSegment::ClosePath => bez.close_path(),
+            }
+        }
+
+        if !subpath.is_closed() {
+            bez.close_path();
+        }
     }
 
-    length
+    bez
+}
+
+fn eval_curve(curve: &kurbo::CubicBez, t: f64) -> (Point<f64>, f64) {
+    let p = curve.eval(t);
+    let d = curve.deriv().eval(t);
+    (Point::new(p.x, p.y), d.y.atan2(d.x).to_degrees())
 }
 
 // TODO: port tiny-skia logic
@@ -691,6 +1526,54 @@ impl<'a> Iterator for TransformedPath<'a> {
     }
 }
 
+fn path_to_svg_string(path: &PathData, precision: u8, compact: bool) -> String {
+    use std::fmt::Write;
+
+    let scale = 10f64.powi(i32::from(precision.min(12)));
+
+    let mut s = String::new();
+    let mut points = path.points.iter();
+    let mut prev_cmd = None;
+    for cmd in path.commands.iter().copied() {
+        let (letter, count) = match cmd {
+            PathCommand::MoveTo => ('M', 2),
+            PathCommand::LineTo => ('L', 2),
+            PathCommand::CurveTo => ('C', 6),
+            PathCommand::ClosePath => ('Z', 0),
+        };
+
+        let is_repeated =
+            prev_cmd == Some(cmd) && matches!(cmd, PathCommand::LineTo | PathCommand::CurveTo);
+        if !(compact && is_repeated) {
+            if !s.is_empty() {
+                s.push(' ');
+            }
+
+            s.push(letter);
+        }
+
+        for n in points.by_ref().take(count) {
+            // Integers are written as is, since rounding can only introduce an error.
+            let mut v = if n.fract() == 0.0 {
+                *n
+            } else {
+                (n * scale).round() / scale
+            };
+
+            // Prevent `-0`.
+            if v == 0.0 {
+                v = 0.0;
+            }
+
+            write!(s, " {}", v).unwrap();
+        }
+
+        prev_cmd = Some(cmd);
+    }
+
+    s
+}
+
 pub(crate) trait CubicBezExt {
     fn from_points(px: f64, py: f64, x1: f64, y1: f64, x2: f64, y2: f64, x: f64, y: f64) -> Self;
 }
@@ -705,3 +1588,611 @@ impl CubicBezExt for kurbo::CubicBez {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FuzzyEq;
+
+    fn circle(cx: f64, cy: f64, r: f64) -> PathData {
+        // A standard cubic approximation of a quarter circle.
+        let k = r * 0.5522847498;
+        let mut p = PathData::new();
+        p.push_move_to(cx + r, cy);
+        p.push_curve_to(cx + r, cy + k, cx + k, cy + r, cx, cy + r);
+        p.push_curve_to(cx - k, cy + r, cx - r, cy + k, cx - r, cy);
+        p.push_curve_to(cx - r, cy - k, cx - k, cy - r, cx, cy - r);
+        p.push_curve_to(cx + k, cy - r, cx + r, cy - k, cx + r, cy);
+        p.push_close_path();
+        p
+    }
+
+    #[test]
+    fn flatten_circle() {
+        let path = circle(0.0, 0.0, 100.0).flatten_to_lines(0.1);
+
+        assert!(!path.commands().contains(&PathCommand::CurveTo));
+        assert_eq!(path.commands().first(), Some(&PathCommand::MoveTo));
+        assert_eq!(path.commands().last(), Some(&PathCommand::ClosePath));
+
+        let lines = path
+            .commands()
+            .iter()
+            .filter(|c| **c == PathCommand::LineTo)
+            .count();
+        assert!(lines > 20 && lines < 200, "{} lines", lines);
+
+        for p in path.points().chunks(2) {
+            let r = (p[0] * p[0] + p[1] * p[1]).sqrt();
+            assert!((r - 100.0).abs() < 0.2);
+        }
+
+        let coarse = circle(0.0, 0.0, 100.0).flatten_to_lines(5.0);
+        assert!(coarse.len() < path.len());
+    }
+
+    #[test]
+    fn fill_bbox() {
+        let path = circle(50.0, 50.0, 40.0);
+        let ts = Transform::new_translate(10.0, 20.0);
+
+        let bbox = path.fill_bbox(ts).unwrap();
+        assert!(bbox.fuzzy_eq(&PathBbox::new(20.0, 30.0, 80.0, 80.0).unwrap()));
+        assert!(bbox.fuzzy_eq(&path.bbox_with_transform(ts, None).unwrap()));
+
+        let stroke = crate::Stroke {
+            width: crate::StrokeWidth::new(10.0).unwrap(),
+            ..crate::Stroke::default()
+        };
+        let stroked = path.bbox_with_transform(ts, Some(&stroke)).unwrap();
+        assert!(stroked.width() > bbox.width());
+
+        assert!(PathData::new().fill_bbox(ts).is_none());
+    }
+
+    #[test]
+    fn transformed() {
+        let mut path = circle(50.0, 50.0, 40.0);
+        path.push_move_to(0.0, 0.0);
+        path.push_line_to(10.0, 0.0);
+
+        let mut ts = Transform::new_translate(10.0, 20.0);
+        ts.rotate(30.0);
+        ts.scale(2.0, 0.5);
+
+        let new_path = path.transformed(ts);
+        assert_eq!(new_path.commands(), path.commands());
+        assert_eq!(new_path.points()[0..2], {
+            let (x, y) = ts.apply(path.points()[0], path.points()[1]);
+            [x, y]
+        });
+
+        let bbox = new_path
+            .bbox_with_transform(Transform::default(), None)
+            .unwrap();
+        assert!(bbox.fuzzy_eq(&path.bbox_with_transform(ts, None).unwrap()));
+    }
+
+    #[test]
+    fn subpaths() {
+        let mut path = PathData::new();
+        path.push_move_to(0.0, 0.0);
+        path.push_line_to(10.0, 0.0);
+        path.push_close_path();
+        path.push_line_to(0.0, 10.0);
+        path.push_move_to(20.0, 20.0);
+        path.push_curve_to(30.0, 20.0, 30.0, 30.0, 20.0, 30.0);
+        path.push_move_to(40.0, 40.0);
+        path.push_line_to(50.0, 40.0);
+        path.push_close_path();
+
+        let subpaths: Vec<_> = path.subpaths().collect();
+        assert_eq!(subpaths.len(), 4);
+
+        assert_eq!(subpaths[0].range(), 0..3);
+        assert!(subpaths[0].is_closed());
+
+        assert_eq!(subpaths[1].commands(), &[PathCommand::LineTo]);
+        assert!(!subpaths[1].is_closed());
+
+        assert_eq!(subpaths[2].range(), 4..6);
+        assert!(!subpaths[2].is_closed());
+        match subpaths[2].segments().last() {
+            Some(PathSegment::CurveTo { x, y, .. }) => assert_eq!((x, y), (20.0, 30.0)),
+            _ => panic!("expected CurveTo"),
+        }
+
+        assert_eq!(subpaths[3].range(), 6..9);
+        assert!(subpaths[3].is_closed());
+        match subpaths[3].segments().next() {
+            Some(PathSegment::MoveTo { x, y }) => assert_eq!((x, y), (40.0, 40.0)),
+            _ => panic!("expected MoveTo"),
+        }
+        assert_eq!(subpaths[3].segments().count(), 3);
+
+        assert_eq!(PathData::new().subpaths().count(), 0);
+    }
+
+    fn star() -> PathData {
+        // A self-intersecting pentagram with a clockwise orientation.
+        let mut path = PathData::new();
+        for i in 0..5 {
+            let angle = (-90.0 + 144.0 * i as f64).to_radians();
+            let (x, y) = (100.0 * angle.cos(), 100.0 * angle.sin());
+            if i == 0 {
+                path.push_move_to(x, y);
+            } else {
+                path.push_line_to(x, y);
+            }
+        }
+        path.push_close_path();
+        path
+    }
+
+    #[test]
+    fn signed_area() {
+        let rect = PathData::from_rect(Rect::new(10.0, 10.0, 20.0, 30.0).unwrap());
+        assert!(rect.signed_area().fuzzy_eq(&600.0));
+        assert!(rect.is_clockwise());
+        assert!(rect.reverse().signed_area().fuzzy_eq(&-600.0));
+        assert!(!rect.reverse().is_clockwise());
+
+        // An open subpath is treated as closed.
+        let mut open = PathData::new();
+        open.push_move_to(0.0, 0.0);
+        open.push_line_to(10.0, 0.0);
+        open.push_line_to(10.0, 10.0);
+        assert!(open.signed_area().fuzzy_eq(&50.0));
+
+        let circle = circle(0.0, 0.0, 100.0);
+        let expected = std::f64::consts::PI * 100.0 * 100.0;
+        assert!((circle.signed_area() - expected).abs() / expected < 0.001);
+
+        // A hole cancels out the outer contour.
+        let mut path = circle.clone();
+        path.push_path(&circle.reverse());
+        assert!(path.signed_area().abs() < 1e-6);
+    }
+
+    #[test]
+    fn contains_point() {
+        let path = star();
+        let center = Point::new(0.0, 0.0);
+        let tip = Point::new(0.0, -80.0);
+        let outside = Point::new(90.0, 90.0);
+        assert!(path.is_clockwise());
+
+        assert!(path.contains_point(center, crate::FillRule::NonZero));
+        assert!(!path.contains_point(center, crate::FillRule::EvenOdd));
+
+        assert!(path.contains_point(tip, crate::FillRule::NonZero));
+        assert!(path.contains_point(tip, crate::FillRule::EvenOdd));
+
+        assert!(!path.contains_point(outside, crate::FillRule::NonZero));
+        assert!(!path.contains_point(outside, crate::FillRule::EvenOdd));
+
+        let circle = circle(0.0, 0.0, 100.0);
+        assert!(circle.contains_point(Point::new(70.0, 70.0), crate::FillRule::NonZero));
+        assert!(!circle.contains_point(Point::new(71.0, 71.0), crate::FillRule::NonZero));
+    }
+
+    #[test]
+    fn basic_shapes() {
+        let path = PathData::from_circle(50.0, 40.0, 30.0);
+        assert!(path
+            .bbox()
+            .unwrap()
+            .fuzzy_eq(&PathBbox::new(20.0, 10.0, 60.0, 60.0).unwrap()));
+        assert_eq!(path.commands().last(), Some(&PathCommand::ClosePath));
+        assert!(path.is_clockwise());
+
+        let path = PathData::from_ellipse(50.0, 40.0, 30.0, 10.0);
+        assert!(path
+            .bbox()
+            .unwrap()
+            .fuzzy_eq(&PathBbox::new(20.0, 30.0, 60.0, 20.0).unwrap()));
+
+        let rect = Rect::new(10.0, 20.0, 100.0, 50.0).unwrap();
+        let path = PathData::from_rounded_rect(rect, 10.0, 5.0);
+        assert!(path.bbox().unwrap().fuzzy_eq(&rect.to_path_bbox()));
+        assert!(path.contains_point(Point::new(15.0, 25.0), crate::FillRule::NonZero));
+        assert!(!path.contains_point(Point::new(11.0, 21.0), crate::FillRule::NonZero));
+
+        // Radii are clamped, so this is an ellipse.
+        let path = PathData::from_rounded_rect(rect, 100.0, 100.0);
+        assert!(path.bbox().unwrap().fuzzy_eq(&rect.to_path_bbox()));
+        let area = std::f64::consts::PI * 50.0 * 25.0;
+        assert!((path.signed_area() - area).abs() / area < 0.001);
+
+        // A zero or negative radius produces a plain rect.
+        for (rx, ry) in [(0.0, 5.0), (10.0, 0.0), (-10.0, 5.0), (10.0, -5.0)] {
+            let path = PathData::from_rounded_rect(rect, rx, ry);
+            assert_eq!(path.commands(), PathData::from_rect(rect).commands());
+            assert_eq!(path.points(), PathData::from_rect(rect).points());
+        }
+    }
+
+    #[test]
+    fn point_at_length() {
+        let mut path = PathData::new();
+        path.push_move_to(0.0, 0.0);
+        path.push_line_to(100.0, 0.0);
+        path.push_move_to(0.0, 100.0);
+        path.push_line_to(0.0, 200.0);
+
+        assert!((path.length() - 200.0).abs() < 0.5);
+
+        let check = |len: f64, x: f64, y: f64, angle: f64| {
+            let (p, a) = path.point_at_length(len).unwrap();
+            assert!((p.x - x).abs() < 0.5, "{} {}", len, p);
+            assert!((p.y - y).abs() < 0.5, "{} {}", len, p);
+            assert!((a - angle).abs() < 0.001, "{} {}", len, a);
+        };
+
+        check(50.0, 50.0, 0.0, 0.0);
+        check(150.0, 0.0, 150.0, 90.0);
+        check(-10.0, 0.0, 0.0, 0.0);
+        check(500.0, 0.0, 200.0, 90.0);
+
+        let mut path = PathData::new();
+        path.push_move_to(10.0, 10.0);
+        assert!(path.point_at_length(0.0).is_none());
+        assert!(PathData::new().point_at_length(0.0).is_none());
+    }
+
+    #[test]
+    fn bbox_cache() {
+        let mut path = PathData::from_rect(Rect::new(10.0, 20.0, 30.0, 40.0).unwrap());
+        assert!(path
+            .bbox()
+            .unwrap()
+            .fuzzy_eq(&PathBbox::new(10.0, 20.0, 30.0, 40.0).unwrap()));
+
+        path.push_line_to(100.0, 100.0);
+        assert!(path
+            .bbox()
+            .unwrap()
+            .fuzzy_eq(&PathBbox::new(10.0, 20.0, 90.0, 80.0).unwrap()));
+
+        path.transform(Transform::new_translate(-10.0, -20.0));
+        assert!(path
+            .bbox()
+            .unwrap()
+            .fuzzy_eq(&PathBbox::new(0.0, 0.0, 90.0, 80.0).unwrap()));
+
+        path.clear();
+        assert!(path.bbox().is_none());
+
+        // The cache is not a part of the debug output.
+        let path = PathData::from_rect(Rect::new(10.0, 20.0, 30.0, 40.0).unwrap());
+        let _ = path.bbox();
+        assert!(!format!("{:?}", path).contains("bbox"));
+
+        // The cache must not prevent sharing paths between threads.
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<PathData>();
+    }
+
+    #[test]
+    fn reverse_open() {
+        let mut path = PathData::new();
+        path.push_move_to(10.0, 20.0);
+        path.push_line_to(30.0, 40.0);
+        path.push_curve_to(1.0, 2.0, 3.0, 4.0, 5.0, 6.0);
+
+        let rev = path.reverse();
+        assert_eq!(
+            rev.commands(),
+            &[
+                PathCommand::MoveTo,
+                PathCommand::CurveTo,
+                PathCommand::LineTo
+            ]
+        );
+        assert_eq!(
+            rev.points(),
+            &[5.0, 6.0, 3.0, 4.0, 1.0, 2.0, 30.0, 40.0, 10.0, 20.0]
+        );
+    }
+
+    #[test]
+    fn reverse_open_before_move_to() {
+        let mut path = PathData::new();
+        path.push_move_to(0.0, 0.0);
+        path.push_line_to(10.0, 0.0);
+        path.push_move_to(20.0, 0.0);
+        path.push_line_to(30.0, 0.0);
+
+        let rev = path.reverse();
+        assert_eq!(
+            rev.commands(),
+            &[
+                PathCommand::MoveTo,
+                PathCommand::LineTo,
+                PathCommand::MoveTo,
+                PathCommand::LineTo
+            ]
+        );
+        assert_eq!(rev.points(), &[10.0, 0.0, 0.0, 0.0, 30.0, 0.0, 20.0, 0.0]);
+
+        let rev2 = rev.reverse();
+        assert_eq!(rev2.points(), path.points());
+    }
+
+    #[test]
+    fn reverse_twice() {
+        let mut path = circle(50.0, 50.0, 20.0);
+        path.push_move_to(0.0, 0.0);
+        path.push_line_to(10.0, 0.0);
+        path.push_line_to(10.0, 10.0);
+        path.push_close_path();
+        path.push_move_to(100.0, 100.0);
+        path.push_line_to(110.0, 100.0);
+
+        let rev = path.reverse();
+        assert_eq!(rev.commands().len(), path.commands().len());
+        assert_eq!(
+            rev.commands()
+                .iter()
+                .filter(|c| **c == PathCommand::ClosePath)
+                .count(),
+            2
+        );
+        assert_ne!(rev.points(), path.points());
+
+        let rev2 = rev.reverse();
+        assert_eq!(rev2.commands(), path.commands());
+        assert_eq!(rev2.points(), path.points());
+    }
+
+    #[test]
+    fn flatten_lines() {
+        let mut path = PathData::new();
+        path.push_move_to(10.0, 20.0);
+        path.push_line_to(30.0, 40.0);
+        path.push_close_path();
+
+        let flat = path.flatten_to_lines(0.1);
+        assert_eq!(flat.commands(), path.commands());
+        assert_eq!(flat.points(), path.points());
+    }
+
+    fn line(x1: f64, y1: f64, x2: f64, y2: f64) -> PathData {
+        let mut path = PathData::new();
+        path.push_move_to(x1, y1);
+        path.push_line_to(x2, y2);
+        path
+    }
+
+    fn count(path: &PathData, cmd: PathCommand) -> usize {
+        path.commands().iter().filter(|c| **c == cmd).count()
+    }
+
+    fn points_eq(path: &PathData, points: &[f64]) -> bool {
+        path.points().len() == points.len()
+            && path
+                .points()
+                .iter()
+                .zip(points)
+                .all(|(a, b)| (a - b).abs() < 1e-6)
+    }
+
+    #[test]
+    fn dash_line() {
+        let path = line(0.0, 0.0, 10.0, 0.0).apply_dash(&[2.0, 2.0], 0.0);
+        assert_eq!(count(&path, PathCommand::MoveTo), 3);
+        assert!(points_eq(
+            &path,
+            &[0.0, 0.0, 2.0, 0.0, 4.0, 0.0, 6.0, 0.0, 8.0, 0.0, 10.0, 0.0]
+        ));
+
+        // An odd list is repeated.
+        let path = line(0.0, 0.0, 10.0, 0.0).apply_dash(&[3.0], 0.0);
+        assert!(points_eq(&path, &[0.0, 0.0, 3.0, 0.0, 6.0, 0.0, 9.0, 0.0]));
+    }
+
+    #[test]
+    fn dash_offset() {
+        let expected = [0.0, 0.0, 1.0, 0.0, 3.0, 0.0, 5.0, 0.0, 7.0, 0.0, 9.0, 0.0];
+        let path = line(0.0, 0.0, 10.0, 0.0);
+        assert!(points_eq(&path.apply_dash(&[2.0, 2.0], 1.0), &expected));
+        // An offset larger than the pattern.
+        assert!(points_eq(&path.apply_dash(&[2.0, 2.0], 13.0), &expected));
+        // A negative offset.
+        assert!(points_eq(&path.apply_dash(&[2.0, 2.0], -3.0), &expected));
+    }
+
+    #[test]
+    fn dash_zero_length() {
+        // Dashes separated by zero-length gaps are merged.
+        let mut path = line(0.0, 0.0, 10.0, 0.0);
+        path.push_line_to(10.0, 10.0);
+        let dashed = path.apply_dash(&[3.0, 0.0], 0.0);
+        assert_eq!(count(&dashed, PathCommand::MoveTo), 1);
+        assert!(dashed.bbox().unwrap().fuzzy_eq(&path.bbox().unwrap()));
+
+        // Zero-length dashes are preserved.
+        let dashed = line(0.0, 0.0, 10.0, 0.0).apply_dash(&[0.0, 5.0], 0.0);
+        assert_eq!(count(&dashed, PathCommand::MoveTo), 3);
+        assert!(points_eq(
+            &dashed,
+            &[0.0, 0.0, 0.0, 0.0, 5.0, 0.0, 5.0, 0.0, 10.0, 0.0, 10.0, 0.0]
+        ));
+
+        // A zero-length subpath.
+        let dashed = line(5.0, 5.0, 5.0, 5.0).apply_dash(&[2.0, 2.0], 0.0);
+        assert!(points_eq(&dashed, &[5.0, 5.0, 5.0, 5.0]));
+    }
+
+    #[test]
+    fn dash_subpaths() {
+        let mut path = line(0.0, 0.0, 3.0, 0.0);
+        path.push_move_to(0.0, 10.0);
+        path.push_line_to(3.0, 10.0);
+
+        // The pattern restarts at each subpath.
+        let dashed = path.apply_dash(&[2.0, 2.0], 0.0);
+        assert!(points_eq(
+            &dashed,
+            &[0.0, 0.0, 2.0, 0.0, 0.0, 10.0, 2.0, 10.0]
+        ));
+    }
+
+    #[test]
+    fn dash_closed() {
+        let rect = PathData::from_rect(Rect::new(0.0, 0.0, 10.0, 10.0).unwrap());
+
+        // A single dash longer than the subpath.
+        let dashed = rect.apply_dash(&[100.0, 5.0], 0.0);
+        assert_eq!(dashed.commands(), rect.commands());
+        assert!(points_eq(&dashed, rect.points()));
+
+        // The last and the first dashes are joined.
+        let dashed = rect.apply_dash(&[30.0, 5.0], 0.0);
+        assert_eq!(count(&dashed, PathCommand::MoveTo), 1);
+        assert_eq!(count(&dashed, PathCommand::ClosePath), 0);
+        assert!(points_eq(
+            &dashed,
+            &[0.0, 5.0, 0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0]
+        ));
+
+        let dashed = circle(0.0, 0.0, 100.0).apply_dash(&[10.0, 10.0], 0.0);
+        assert_eq!(count(&dashed, PathCommand::MoveTo), 31);
+        assert_eq!(count(&dashed, PathCommand::LineTo), 0);
+    }
+
+    #[test]
+    fn dash_invalid() {
+        let path = line(0.0, 0.0, 10.0, 0.0);
+        assert!(points_eq(&path.apply_dash(&[], 0.0), path.points()));
+        assert!(points_eq(
+            &path.apply_dash(&[2.0, -1.0], 0.0),
+            path.points()
+        ));
+        assert!(points_eq(&path.apply_dash(&[0.0, 0.0], 0.0), path.points()));
+    }
+
+    fn parse_svg_path(text: &str) -> PathData {
+        let mut path = PathData::new();
+        for seg in svgtypes::PathParser::from(text) {
+            match seg.unwrap() {
+                svgtypes::PathSegment::MoveTo { abs: true, x, y } => path.push_move_to(x, y),
+                svgtypes::PathSegment::LineTo { abs: true, x, y } => path.push_line_to(x, y),
+                svgtypes::PathSegment::CurveTo {
+                    abs: true,
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => path.push_curve_to(x1, y1, x2, y2, x, y),
+                svgtypes::PathSegment::ClosePath { abs: true } => path.push_close_path(),
+                seg => panic!("unexpected segment: {:?}", seg),
+            }
+        }
+
+        path
+    }
+
+    #[test]
+    fn to_svg_string() {
+        let mut path = PathData::new();
+        path.push_move_to(10.0, -0.0001);
+        path.push_line_to(20.5, 30.0);
+        path.push_line_to(1.0 / 3.0, -2.0 / 3.0);
+        path.push_curve_to(1.0, 2.0, 3.0, 4.0, 5.0, 6.0);
+        path.push_curve_to(7.0, 8.0, 9.0, 10.0, 11.0, 12.0);
+        path.push_close_path();
+        path.push_move_to(0.0, 0.0);
+        path.push_line_to(1.0, 1.0);
+
+        assert_eq!(
+            path.to_svg_string(2),
+            "M 10 0 L 20.5 30 L 0.33 -0.67 C 1 2 3 4 5 6 C 7 8 9 10 11 12 Z M 0 0 L 1 1"
+        );
+        assert_eq!(
+            path.to_compact_svg_string(2),
+            "M 10 0 L 20.5 30 0.33 -0.67 C 1 2 3 4 5 6 7 8 9 10 11 12 Z M 0 0 L 1 1"
+        );
+        assert_eq!(path.to_svg_string(0).split(' ').nth(8), Some("-1"));
+        assert_eq!(PathData::new().to_svg_string(2), "");
+    }
+
+    #[test]
+    fn svg_string_round_trip() {
+        let mut path = circle(50.0, 50.0, 40.0);
+        path.push_path(&PathData::from_rect(
+            Rect::new(-10.25, 0.5, 20.0, 1e20).unwrap(),
+        ));
+        path.push_move_to(0.0, 0.0);
+        path.push_line_to(-1.0, 1.0);
+        path.push_line_to(2.0, -2.0);
+
+        for text in [path.to_svg_string(12), path.to_compact_svg_string(12)] {
+            let parsed = parse_svg_path(&text);
+            assert_eq!(parsed.commands(), path.commands());
+            assert!(points_eq(&parsed, path.points()));
+            assert_eq!(parsed.to_svg_string(12), path.to_svg_string(12));
+        }
+
+        // Lower precision stays within the rounding error.
+        let parsed = parse_svg_path(&path.to_svg_string(3));
+        assert_eq!(parsed.commands(), path.commands());
+        for (a, b) in parsed.points().iter().zip(path.points()) {
+            assert!((a - b).abs() <= 0.0005);
+        }
+    }
+
+    #[test]
+    fn from_svg_string() {
+        let path = PathData::from_svg_string("m 10 20 h 5 v 5 l -5 0 z").unwrap();
+        assert_eq!(
+            path.commands(),
+            &[
+                PathCommand::MoveTo,
+                PathCommand::LineTo,
+                PathCommand::LineTo,
+                PathCommand::LineTo,
+                PathCommand::ClosePath,
+            ]
+        );
+        assert!(points_eq(
+            &path,
+            &[10.0, 20.0, 15.0, 20.0, 15.0, 25.0, 10.0, 25.0]
+        ));
+
+        // Quadratic and arc segments are converted into cubic curves.
+        let path = PathData::from_svg_string("M 0 0 Q 5 10 10 0 A 5 5 0 0 1 20 0").unwrap();
+        assert_eq!(path.commands()[0], PathCommand::MoveTo);
+        assert!(path.commands()[1..]
+            .iter()
+            .all(|c| *c == PathCommand::CurveTo));
+        let end = &path.points()[path.points().len() - 2..];
+        assert!((end[0] - 20.0).abs() < 1e-6 && end[1].abs() < 1e-6);
+
+        assert!(PathData::from_svg_string("").unwrap().is_empty());
+        assert_eq!(
+            PathData::from_svg_string("M 10 20 L 30 40 C 1 2 3 4 5 6 Z")
+                .unwrap()
+                .to_svg_string(2),
+            "M 10 20 L 30 40 C 1 2 3 4 5 6 Z"
+        );
+    }
+
+    #[test]
+    fn from_svg_string_invalid() {
+        assert!(PathData::from_svg_string("M 10 20 L 30").is_err());
+        assert!(PathData::from_svg_string("M 10 20 X 30 40").is_err());
+        assert!(PathData::from_svg_string("L 10 20").is_err());
+
+        // Segments before an error are preserved.
+        let (path, err) = PathData::from_svg_string_partial("M 10 20 L 30 40 L 50");
+        assert!(err.is_some());
+        assert_eq!(path.to_svg_string(2), "M 10 20 L 30 40");
+
+        let (path, err) = PathData::from_svg_string_partial("M 10 20 L 30 40");
+        assert!(err.is_none());
+        assert_eq!(path.len(), 2);
+    }
+}
@@ -0,0 +1,494 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! `serde` support.
+//!
+//! Most types simply derive `Serialize` and `Deserialize`.
+//! This module handles the rest: the nodes tree, shared paint servers/clip paths/etc.,
+//! validated numbers and foreign types.
+//!
+//! Shared `Rc`/`Arc` values are deduplicated inside a `Tree`.
+//! The first occurrence is written as `Def(id, value)` and all the following ones
+//! as `Ref(id)`. Sharing is restored during deserialization.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use serde::de::Error as _;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use strict_num::{NonZeroPositiveF64, NormalizedF64, PositiveF64};
+
+use crate::{
+    Align, AspectRatio, Node, NodeKind, NonZeroF64, PathCommand, PathData, Point, Rect, Size,
+    StrokeMiterlimit, Tree, ViewBox,
+};
+
+#[derive(Default)]
+struct SharedState {
+    depth: u32,
+    next_id: u32,
+    written: HashMap<usize, u32>,
+    read: HashMap<u32, Box<dyn Any>>,
+}
+
+thread_local! {
+    static SHARED: RefCell<SharedState> = RefCell::new(SharedState::default());
+}
+
+/// Enables shared values deduplication until dropped.
+struct Scope;
+
+impl Scope {
+    fn enter() -> Self {
+        SHARED.with(|s| s.borrow_mut().depth += 1);
+        Scope
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        SHARED.with(|s| {
+            let mut s = s.borrow_mut();
+            s.depth -= 1;
+            if s.depth == 0 {
+                *s = SharedState::default();
+            }
+        });
+    }
+}
+
+pub(crate) trait SharedPtr: Clone + 'static {
+    type Target: 'static;
+    fn new(value: Self::Target) -> Self;
+    fn addr(&self) -> usize;
+    fn get(&self) -> &Self::Target;
+}
+
+impl<T: 'static> SharedPtr for Rc<T> {
+    type Target = T;
+
+    fn new(value: T) -> Self {
+        Rc::new(value)
+    }
+
+    fn addr(&self) -> usize {
+        Rc::as_ptr(self) as usize
+    }
+
+    fn get(&self) -> &T {
+        self
+    }
+}
+
+impl<T: 'static> SharedPtr for Arc<T> {
+    type Target = T;
+
+    fn new(value: T) -> Self {
+        Arc::new(value)
+    }
+
+    fn addr(&self) -> usize {
+        Arc::as_ptr(self) as usize
+    }
+
+    fn get(&self) -> &T {
+        self
+    }
+}
+
+#[derive(Serialize)]
+enum SharedSer<'a, T> {
+    Def(u32, &'a T),
+    Ref(u32),
+}
+
+#[derive(Deserialize)]
+enum SharedDe<T> {
+    Def(u32, T),
+    Ref(u32),
+}
+
+/// `serde(with)` module for `Rc<T>` and `Arc<T>`.
+pub(crate) mod shared {
+    use super::*;
+
+    pub fn serialize<P, S>(value: &P, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        P: SharedPtr,
+        P::Target: Serialize,
+        S: Serializer,
+    {
+        let (id, is_new) = SHARED.with(|s| {
+            let mut s = s.borrow_mut();
+            if s.depth == 0 {
+                // Outside of a `Tree`. Nothing to deduplicate against.
+                return (0, true);
+            }
+
+            if let Some(id) = s.written.get(&value.addr()) {
+                return (*id, false);
+            }
+
+            let id = s.next_id;
+            s.next_id += 1;
+            s.written.insert(value.addr(), id);
+            (id, true)
+        });
+
+        if is_new {
+            SharedSer::Def(id, value.get()).serialize(serializer)
+        } else {
+            SharedSer::<P::Target>::Ref(id).serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, P, D>(deserializer: D) -> Result<P, D::Error>
+    where
+        P: SharedPtr,
+        P::Target: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        match SharedDe::<P::Target>::deserialize(deserializer)? {
+            SharedDe::Def(id, value) => {
+                let ptr = P::new(value);
+                SHARED.with(|s| {
+                    let mut s = s.borrow_mut();
+                    if s.depth != 0 {
+                        s.read.insert(id, Box::new(ptr.clone()));
+                    }
+                });
+                Ok(ptr)
+            }
+            SharedDe::Ref(id) => SHARED.with(|s| {
+                s.borrow()
+                    .read
+                    .get(&id)
+                    .and_then(|v| v.downcast_ref::<P>())
+                    .cloned()
+                    .ok_or_else(|| D::Error::custom(format!("unresolved shared value #{}", id)))
+            }),
+        }
+    }
+}
+
+struct SharedWrapper<P>(P);
+
+impl<P: SharedPtr> Serialize for SharedWrapper<P>
+where
+    P::Target: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        shared::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, P: SharedPtr> Deserialize<'de> for SharedWrapper<P>
+where
+    P::Target: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        shared::deserialize(deserializer).map(SharedWrapper)
+    }
+}
+
+/// `serde(with)` module for `Option<Rc<T>>`.
+pub(crate) mod shared_opt {
+    use super::*;
+
+    pub fn serialize<P, S>(value: &Option<P>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        P: SharedPtr,
+        P::Target: Serialize,
+        S: Serializer,
+    {
+        value.clone().map(SharedWrapper).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, P, D>(deserializer: D) -> Result<Option<P>, D::Error>
+    where
+        P: SharedPtr,
+        P::Target: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let v = Option::<SharedWrapper<P>>::deserialize(deserializer)?;
+        Ok(v.map(|v| v.0))
+    }
+}
+
+/// `serde(with)` module for `Vec<Rc<T>>`.
+pub(crate) mod shared_vec {
+    use super::*;
+
+    pub fn serialize<P, S>(value: &[P], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        P: SharedPtr,
+        P::Target: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(value.iter().cloned().map(SharedWrapper))
+    }
+
+    pub fn deserialize<'de, P, D>(deserializer: D) -> Result<Vec<P>, D::Error>
+    where
+        P: SharedPtr,
+        P::Target: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let v = Vec::<SharedWrapper<P>>::deserialize(deserializer)?;
+        Ok(v.into_iter().map(|v| v.0).collect())
+    }
+}
+
+struct NodeSer(Node);
+
+impl Serialize for NodeSer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Node", 2)?;
+        s.serialize_field("kind", &*self.0.borrow())?;
+        s.serialize_field("children", &NodeChildrenSer(&self.0))?;
+        s.end()
+    }
+}
+
+struct NodeChildrenSer<'a>(&'a Node);
+
+impl Serialize for NodeChildrenSer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.children().map(NodeSer))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Node")]
+struct NodeDe {
+    kind: NodeKind,
+    children: Vec<NodeDe>,
+}
+
+impl NodeDe {
+    fn into_node(self) -> Node {
+        let node = Node::new(self.kind);
+        for child in self.children {
+            node.append(child.into_node());
+        }
+        node
+    }
+}
+
+/// `serde(with)` module for `Node`.
+///
+/// Serializes the node with all its descendants.
+pub(crate) mod node {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Node, serializer: S) -> Result<S::Ok, S::Error> {
+        NodeSer(value.clone()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Node, D::Error> {
+        Ok(NodeDe::deserialize(deserializer)?.into_node())
+    }
+}
+
+impl Serialize for Tree {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let _scope = Scope::enter();
+        let mut s = serializer.serialize_struct("Tree", 3)?;
+        s.serialize_field("size", &self.size)?;
+        s.serialize_field("view_box", &self.view_box)?;
+        s.serialize_field("root", &NodeSer(self.root.clone()))?;
+        s.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Tree {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "Tree")]
+        struct TreeDe {
+            size: Size,
+            view_box: ViewBox,
+            #[serde(with = "node")]
+            root: Node,
+        }
+
+        let _scope = Scope::enter();
+        let tree = TreeDe::deserialize(deserializer)?;
+        Ok(Tree::new(tree.size, tree.view_box, tree.root))
+    }
+}
+
+macro_rules! f64_module {
+    ($name:ident, $ty:ty) => {
+        /// `serde(with)` module for a validated `f64` wrapper.
+        pub(crate) mod $name {
+            use super::*;
+
+            pub fn serialize<S: Serializer>(value: &$ty, serializer: S) -> Result<S::Ok, S::Error> {
+                value.get().serialize(serializer)
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<$ty, D::Error> {
+                let n = f64::deserialize(deserializer)?;
+                <$ty>::new(n).ok_or_else(|| {
+                    D::Error::custom(format!("{} is not a valid {}", n, stringify!($ty)))
+                })
+            }
+        }
+    };
+}
+
+f64_module!(normalized_f64, NormalizedF64);
+f64_module!(positive_f64, PositiveF64);
+f64_module!(non_zero_positive_f64, NonZeroPositiveF64);
+
+/// `serde(with)` module for `Point<PositiveF64>`.
+pub(crate) mod positive_point {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        value: &Point<PositiveF64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        (value.x.get(), value.y.get()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Point<PositiveF64>, D::Error> {
+        let (x, y) = <(f64, f64)>::deserialize(deserializer)?;
+        match (PositiveF64::new(x), PositiveF64::new(y)) {
+            (Some(x), Some(y)) => Ok(Point::new(x, y)),
+            _ => Err(D::Error::custom("point coordinates must be positive")),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Align")]
+enum AlignDef {
+    None,
+    XMinYMin,
+    XMidYMin,
+    XMaxYMin,
+    XMinYMid,
+    XMidYMid,
+    XMaxYMid,
+    XMinYMax,
+    XMidYMax,
+    XMaxYMax,
+}
+
+/// `serde(with)` module for `AspectRatio`.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "AspectRatio")]
+pub(crate) struct AspectRatioDef {
+    defer: bool,
+    #[serde(with = "AlignDef")]
+    align: Align,
+    slice: bool,
+}
+
+impl Serialize for NonZeroF64 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for NonZeroF64 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let n = f64::deserialize(deserializer)?;
+        NonZeroF64::new(n).ok_or_else(|| D::Error::custom("value must not be zero"))
+    }
+}
+
+impl Serialize for StrokeMiterlimit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.get().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for StrokeMiterlimit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let n = f64::deserialize(deserializer)?;
+        if n.is_finite() && n >= 1.0 {
+            Ok(StrokeMiterlimit::new(n))
+        } else {
+            Err(D::Error::custom("stroke-miterlimit must be >= 1"))
+        }
+    }
+}
+
+impl Serialize for Size {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.width(), self.height()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Size {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (w, h) = <(f64, f64)>::deserialize(deserializer)?;
+        Size::new(w, h).ok_or_else(|| D::Error::custom("invalid size"))
+    }
+}
+
+impl Serialize for Rect {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.x(), self.y(), self.width(), self.height()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rect {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (x, y, w, h) = <(f64, f64, f64, f64)>::deserialize(deserializer)?;
+        Rect::new(x, y, w, h).ok_or_else(|| D::Error::custom("invalid rect"))
+    }
+}
+
+impl Serialize for PathData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.commands(), self.points()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PathData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (commands, points) = <(Vec<PathCommand>, Vec<f64>)>::deserialize(deserializer)?;
+
+        let expected: usize = commands
+            .iter()
+            .map(|c| match c {
+                PathCommand::MoveTo | PathCommand::LineTo => 2,
+                PathCommand::CurveTo => 6,
+                PathCommand::ClosePath => 0,
+            })
+            .sum();
+        if expected != points.len() {
+            return Err(D::Error::custom("path commands and points mismatch"));
+        }
+
+        let mut path = PathData::new();
+        let mut points = points.into_iter();
+        let mut next = || points.next().unwrap();
+        for c in commands {
+            match c {
+                PathCommand::MoveTo => path.push_move_to(next(), next()),
+                PathCommand::LineTo => path.push_line_to(next(), next()),
+                PathCommand::CurveTo => {
+                    let (x1, y1, x2, y2, x, y) = (next(), next(), next(), next(), next(), next());
+                    path.push_curve_to(x1, y1, x2, y2, x, y)
+                }
+                PathCommand::ClosePath => path.push_close_path(),
+            }
+        }
+
+        Ok(path)
+    }
+}
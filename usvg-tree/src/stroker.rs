@@ -0,0 +1,416 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Stroke to fill conversion.
+//!
+//! The path is dashed first and then flattened into polylines,
+//! which are offset to both sides with the requested joins and caps.
+
+use kurbo::Vec2;
+
+use crate::{LineCap, LineJoin, PathData, PathSegment, Stroke};
+
+pub(crate) fn stroke_to_path(path: &PathData, stroke: &Stroke) -> PathData {
+    let dashed;
+    let path = match stroke.dasharray {
+        Some(ref dashes) => {
+            dashed = path.apply_dash(dashes, stroke.dashoffset as f64);
+            &dashed
+        }
+        None => path,
+    };
+
+    // Flatten relative to the path size, so tiny paths,
+    // like the ones in `objectBoundingBox` units, are not distorted.
+    let size = path
+        .bbox()
+        .map(|r| r.width().max(r.height()))
+        .unwrap_or(0.0);
+    let tolerance = (size / 10_000.0).clamp(0.001, 0.1);
+
+    let mut stroker = Stroker {
+        path: PathData::new(),
+        half_width: stroke.width.get() / 2.0,
+        cap: stroke.linecap,
+        join: stroke.linejoin,
+        miterlimit: stroke.miterlimit.get(),
+        tolerance,
+    };
+
+    for (points, closed) in polylines(&path.flatten_to_lines(tolerance)) {
+        stroker.stroke_polyline(&points, closed);
+    }
+
+    stroker.path
+}
+
+/// Splits a flattened path into polylines.
+///
+/// Consecutive duplicated points are removed, therefore zero-length subpaths
+/// are represented by a single point. Subpaths without any drawing commands are skipped.
+fn polylines(path: &PathData) -> Vec<(Vec<kurbo::Point>, bool)> {
+    fn finish(
+        points: &mut Vec<kurbo::Point>,
+        closed: bool,
+        list: &mut Vec<(Vec<kurbo::Point>, bool)>,
+    ) {
+        if closed && points.len() > 1 && is_same_point(points[0], points[points.len() - 1]) {
+            points.pop();
+        }
+
+        list.push((std::mem::take(points), closed));
+    }
+
+    let mut list = Vec::new();
+    let mut points = Vec::new();
+    let mut has_segments = false;
+    let mut start = kurbo::Point::ZERO;
+    for seg in path.segments() {
+        match seg {
+            PathSegment::MoveTo { x, y } => {
+                if has_segments {
+                    finish(&mut points, false, &mut list);
+                }
+
+                start = kurbo::Point::new(x, y);
+                points.clear();
+                points.push(start);
+                has_segments = false;
+            }
+            PathSegment::LineTo { x, y } => {
+                if points.is_empty() {
+                    // A segment after ClosePath starts at the subpath start.
+                    points.push(start);
+                }
+
+                let p = kurbo::Point::new(x, y);
+                if !is_same_point(points[points.len() - 1], p) {
+                    points.push(p);
+                }
+
+                has_segments = true;
+            }
+            PathSegment::ClosePath => {
+                if points.is_empty() {
+                    points.push(start);
+                }
+
+                finish(&mut points, true, &mut list);
+                has_segments = false;
+            }
+            // `flatten_to_lines` produces only lines.
+            PathSegment::CurveTo { .. } => {}
+        }
+    }
+
+    if has_segments {
+        finish(&mut points, false, &mut list);
+    }
+
+    list
+}
+
+struct Stroker {
+    path: PathData,
+    half_width: f64,
+    cap: LineCap,
+    join: LineJoin,
+    miterlimit: f64,
+    tolerance: f64,
+}
+
+impl Stroker {
+    fn stroke_polyline(&mut self, points: &[kurbo::Point], closed: bool) {
+        match points.len() {
+            0 => {}
+            1 => self.stroke_point(points[0]),
+            _ if closed => {
+                self.push_closed_side(points);
+                let reversed: Vec<_> = points.iter().rev().copied().collect();
+                self.push_closed_side(&reversed);
+            }
+            _ => {
+                let dirs = directions(points, false);
+                let first = dirs[0];
+                let last = dirs[dirs.len() - 1];
+
+                self.move_to(points[0] + self.normal(first));
+                self.push_open_side(points, &dirs);
+                self.push_cap(points[points.len() - 1], last);
+
+                let reversed: Vec<_> = points.iter().rev().copied().collect();
+                let dirs: Vec<_> = dirs.iter().rev().map(|d| -*d).collect();
+                self.push_open_side(&reversed, &dirs);
+                self.push_cap(points[0], -first);
+                self.path.push_close_path();
+            }
+        }
+    }
+
+    /// Strokes a zero-length subpath.
+    ///
+    /// Only round and square caps are rendered in this case.
+    /// The direction of a square cap is aligned with the X axis.
+    fn stroke_point(&mut self, p: kurbo::Point) {
+        if self.cap == LineCap::Butt {
+            return;
+        }
+
+        let dir = Vec2::new(1.0, 0.0);
+        self.move_to(p + self.normal(dir));
+        self.push_cap(p, dir);
+        self.push_cap(p, -dir);
+        self.path.push_close_path();
+    }
+
+    /// Pushes an offset polyline starting after the first point.
+    fn push_open_side(&mut self, points: &[kurbo::Point], dirs: &[Vec2]) {
+        for i in 1..points.len() - 1 {
+            self.push_join(points[i], dirs[i - 1], dirs[i]);
+        }
+
+        let last = dirs[dirs.len() - 1];
+        self.line_to(points[points.len() - 1] + self.normal(last));
+    }
+
+    fn push_closed_side(&mut self, points: &[kurbo::Point]) {
+        let dirs = directions(points, true);
+        self.move_to(points[0] + self.normal(dirs[0]));
+        for i in 1..points.len() {
+            self.push_join(points[i], dirs[i - 1], dirs[i]);
+        }
+        self.push_join(points[0], dirs[dirs.len() - 1], dirs[0]);
+        self.path.push_close_path();
+    }
+
+    /// Pushes a join between two segments on the left side of the direction.
+    ///
+    /// Ends at the offset start of the next segment.
+    fn push_join(&mut self, p: kurbo::Point, prev_dir: Vec2, next_dir: Vec2) {
+        let n1 = self.normal(prev_dir);
+        let n2 = self.normal(next_dir);
+        self.line_to(p + n1);
+
+        let cross = prev_dir.cross(next_dir);
+        let dot = prev_dir.dot(next_dir);
+        if cross.abs() < 1e-9 && dot > 0.0 {
+            // Collinear segments.
+            return;
+        }
+
+        if cross > 0.0 {
+            // An inner join. Going through the pivot point keeps the outline
+            // filled with the `nonzero` rule.
+            self.line_to(p);
+            self.line_to(p + n2);
+            return;
+        }
+
+        // A turn angle in the `-PI..=0` range.
+        let turn = -(cross.abs().atan2(dot));
+        match self.join {
+            LineJoin::Round => {
+                self.push_arc(p, n1.atan2(), turn);
+            }
+            LineJoin::Miter => {
+                // `1 / cos(turn / 2)` is the miter length to the stroke width ratio.
+                let cos_half = (turn / 2.0).cos();
+                if cos_half > 1e-9 && 1.0 / cos_half <= self.miterlimit {
+                    let miter = (n1 + n2).normalize() * (self.half_width / cos_half);
+                    self.line_to(p + miter);
+                }
+            }
+            LineJoin::Bevel => {}
+        }
+
+        self.line_to(p + n2);
+    }
+
+    /// Pushes a cap from the left to the right side of the direction.
+    fn push_cap(&mut self, p: kurbo::Point, dir: Vec2) {
+        let n = self.normal(dir);
+        match self.cap {
+            LineCap::Butt => {}
+            LineCap::Round => {
+                self.push_arc(p, n.atan2(), -std::f64::consts::PI);
+            }
+            LineCap::Square => {
+                let ext = dir * self.half_width;
+                self.line_to(p + n + ext);
+                self.line_to(p - n + ext);
+            }
+        }
+
+        self.line_to(p - n);
+    }
+
+    fn push_arc(&mut self, center: kurbo::Point, start_angle: f64, sweep_angle: f64) {
+        let arc = kurbo::Arc {
+            center,
+            radii: Vec2::new(self.half_width, self.half_width),
+            start_angle,
+            sweep_angle,
+            x_rotation: 0.0,
+        };
+
+        arc.to_cubic_beziers(self.tolerance, |p1, p2, p| {
+            self.path.push_curve_to(p1.x, p1.y, p2.x, p2.y, p.x, p.y);
+        });
+    }
+
+    fn normal(&self, dir: Vec2) -> Vec2 {
+        Vec2::new(-dir.y, dir.x) * self.half_width
+    }
+
+    fn move_to(&mut self, p: kurbo::Point) {
+        self.path.push_move_to(p.x, p.y);
+    }
+
+    fn line_to(&mut self, p: kurbo::Point) {
+        self.path.push_line_to(p.x, p.y);
+    }
+}
+
+/// Returns normalized segment directions.
+///
+/// Points must not contain consecutive duplicates.
+fn directions(points: &[kurbo::Point], closed: bool) -> Vec<Vec2> {
+    let mut dirs: Vec<_> = points
+        .windows(2)
+        .map(|w| (w[1] - w[0]).normalize())
+        .collect();
+    if closed {
+        dirs.push((points[0] - points[points.len() - 1]).normalize());
+    }
+
+    dirs
+}
+
+fn is_same_point(p1: kurbo::Point, p2: kurbo::Point) -> bool {
+    (p1 - p2).hypot2() < 1e-18
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FuzzyEq, PathBbox, StrokeMiterlimit, StrokeWidth};
+
+    fn line_path(points: &[(f64, f64)]) -> PathData {
+        let mut path = PathData::new();
+        path.push_move_to(points[0].0, points[0].1);
+        for p in &points[1..] {
+            path.push_line_to(p.0, p.1);
+        }
+        path
+    }
+
+    fn stroke(width: f64, linecap: LineCap, linejoin: LineJoin) -> Stroke {
+        Stroke {
+            width: StrokeWidth::new(width).unwrap(),
+            linecap,
+            linejoin,
+            ..Stroke::default()
+        }
+    }
+
+    fn has_point(path: &PathData, x: f64, y: f64) -> bool {
+        path.segments().any(|seg| match seg {
+            PathSegment::MoveTo { x: px, y: py } | PathSegment::LineTo { x: px, y: py } => {
+                (px - x).abs() < 1e-9 && (py - y).abs() < 1e-9
+            }
+            _ => false,
+        })
+    }
+
+    fn subpaths_count(path: &PathData) -> usize {
+        path.segments()
+            .filter(|seg| matches!(seg, PathSegment::MoveTo { .. }))
+            .count()
+    }
+
+    fn bbox_eq(path: &PathData, x: f64, y: f64, w: f64, h: f64) -> bool {
+        let bbox = path.bbox().unwrap();
+        let expected = PathBbox::new(x, y, w, h).unwrap();
+        (bbox.x() - expected.x()).abs() < 1e-6
+            && (bbox.y() - expected.y()).abs() < 1e-6
+            && bbox.width().fuzzy_eq(&expected.width())
+            && bbox.height().fuzzy_eq(&expected.height())
+    }
+
+    #[test]
+    fn caps() {
+        let path = line_path(&[(0.0, 0.0), (10.0, 0.0)]);
+
+        let outline = stroke_to_path(&path, &stroke(2.0, LineCap::Butt, LineJoin::Miter));
+        assert!(bbox_eq(&outline, 0.0, -1.0, 10.0, 2.0));
+
+        let outline = stroke_to_path(&path, &stroke(2.0, LineCap::Square, LineJoin::Miter));
+        assert!(bbox_eq(&outline, -1.0, -1.0, 12.0, 2.0));
+
+        let outline = stroke_to_path(&path, &stroke(2.0, LineCap::Round, LineJoin::Miter));
+        assert!(bbox_eq(&outline, -1.0, -1.0, 12.0, 2.0));
+    }
+
+    #[test]
+    fn joins() {
+        let path = line_path(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+
+        let outline = stroke_to_path(&path, &stroke(2.0, LineCap::Butt, LineJoin::Miter));
+        assert!(has_point(&outline, 11.0, -1.0));
+
+        let outline = stroke_to_path(&path, &stroke(2.0, LineCap::Butt, LineJoin::Bevel));
+        assert!(!has_point(&outline, 11.0, -1.0));
+        assert!(has_point(&outline, 10.0, -1.0));
+        assert!(has_point(&outline, 11.0, 0.0));
+
+        // A right angle requires a miterlimit of at least `sqrt(2)`.
+        let mut s = stroke(2.0, LineCap::Butt, LineJoin::Miter);
+        s.miterlimit = StrokeMiterlimit::new(1.4);
+        let outline = stroke_to_path(&path, &s);
+        assert!(!has_point(&outline, 11.0, -1.0));
+
+        let outline = stroke_to_path(&path, &stroke(2.0, LineCap::Butt, LineJoin::Round));
+        assert!(!has_point(&outline, 11.0, -1.0));
+        assert!(bbox_eq(&outline, 0.0, -1.0, 11.0, 11.0));
+    }
+
+    #[test]
+    fn closed_path() {
+        let mut path = line_path(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+        path.push_close_path();
+
+        let outline = stroke_to_path(&path, &stroke(2.0, LineCap::Butt, LineJoin::Miter));
+        assert_eq!(subpaths_count(&outline), 2);
+        assert!(bbox_eq(&outline, -1.0, -1.0, 12.0, 12.0));
+        assert!(has_point(&outline, 11.0, 11.0));
+        assert!(has_point(&outline, 10.0, 1.0));
+    }
+
+    #[test]
+    fn zero_length_subpath() {
+        let path = line_path(&[(5.0, 5.0), (5.0, 5.0)]);
+
+        let outline = stroke_to_path(&path, &stroke(2.0, LineCap::Butt, LineJoin::Miter));
+        assert!(outline.is_empty());
+
+        let outline = stroke_to_path(&path, &stroke(2.0, LineCap::Round, LineJoin::Miter));
+        assert!(bbox_eq(&outline, 4.0, 4.0, 2.0, 2.0));
+
+        let outline = stroke_to_path(&path, &stroke(2.0, LineCap::Square, LineJoin::Miter));
+        assert!(bbox_eq(&outline, 4.0, 4.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn dashed_line() {
+        let path = line_path(&[(0.0, 0.0), (10.0, 0.0)]);
+        let mut s = stroke(2.0, LineCap::Butt, LineJoin::Miter);
+        s.dasharray = Some(vec![2.0, 2.0]);
+
+        let outline = stroke_to_path(&path, &s);
+        assert_eq!(subpaths_count(&outline), 3);
+        assert!(bbox_eq(&outline, 0.0, -1.0, 10.0, 2.0));
+        assert!(has_point(&outline, 2.0, -1.0));
+        assert!(has_point(&outline, 4.0, -1.0));
+    }
+}
@@ -6,11 +6,15 @@ use std::rc::Rc;
 
 use strict_num::NonZeroPositiveF64;
 
-use crate::{Fill, PaintOrder, PathData, Stroke, TextRendering, Transform, Visibility};
+use crate::{
+    Fill, Group, Node, NodeExt, NodeKind, PaintOrder, Path, PathData, ShapeRendering, Stroke,
+    TextRendering, Transform, Visibility,
+};
 
 /// A font stretch property.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FontStretch {
     UltraCondensed,
     ExtraCondensed,
@@ -32,6 +36,7 @@ impl Default for FontStretch {
 
 /// A font style property.
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FontStyle {
     /// A face that is neither italic not obliqued.
     Normal,
@@ -50,6 +55,7 @@ impl Default for FontStyle {
 
 /// Text font properties.
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Font {
     /// A list of family names.
     ///
@@ -66,6 +72,7 @@ pub struct Font {
 /// A dominant baseline property.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DominantBaseline {
     Auto,
     UseScript,
@@ -90,6 +97,7 @@ impl Default for DominantBaseline {
 /// An alignment baseline property.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AlignmentBaseline {
     Auto,
     Baseline,
@@ -114,6 +122,7 @@ impl Default for AlignmentBaseline {
 /// A baseline shift property.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BaselineShift {
     Baseline,
     Subscript,
@@ -128,11 +137,32 @@ impl Default for BaselineShift {
     }
 }
 
+/// An OpenType font feature.
+///
+/// A single `font-feature-settings` entry in SVG, like `"liga" off`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontFeature {
+    /// A feature tag, like `liga` or `smcp`.
+    pub tag: [u8; 4],
+    /// A feature value.
+    ///
+    /// `0` disables the feature and `1` enables it.
+    /// Larger values are used to select alternates.
+    pub value: u32,
+}
+
 /// A length adjust property.
+///
+/// `lengthAdjust` attribute in the SVG.
+/// Controls how a span is stretched to match its `text_length`.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LengthAdjust {
+    /// Only the space between glyphs is adjusted.
     Spacing,
+    /// Glyphs are scaled along the text flow direction as well.
     SpacingAndGlyphs,
 }
 
@@ -149,6 +179,7 @@ impl Default for LengthAdjust {
 ///
 /// Also, in SVG you can specify text decoration stroking.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextDecorationStyle {
     /// A fill style.
     pub fill: Option<Fill>,
@@ -158,6 +189,7 @@ pub struct TextDecorationStyle {
 
 /// A text span decoration.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextDecoration {
     /// An optional underline and its style.
     pub underline: Option<TextDecorationStyle>,
@@ -171,6 +203,7 @@ pub struct TextDecoration {
 ///
 /// Spans do not overlap inside a text chunk.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextSpan {
     /// A span start in UTF-8 codepoints.
     ///
@@ -189,11 +222,21 @@ pub struct TextSpan {
     /// A font.
     pub font: Font,
     /// A font size.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_impl::non_zero_positive_f64")
+    )]
     pub font_size: NonZeroPositiveF64,
     /// Indicates that small caps should be used.
     ///
     /// Set by `font-variant="small-caps"`
     pub small_caps: bool,
+    /// A list of OpenType font features.
+    ///
+    /// Collected from the `font-variant` ligatures keywords
+    /// and then from `font-feature-settings`.
+    /// Should be passed to the shaper as is, in order.
+    pub font_features: Vec<FontFeature>,
     /// Indicates that a kerning should be applied.
     ///
     /// Supports both `kerning` and `font-kerning` properties.
@@ -215,14 +258,21 @@ pub struct TextSpan {
     /// A word spacing property.
     pub word_spacing: f64,
     /// A text length property.
+    ///
+    /// `textLength` attribute in the SVG.
+    /// The total advance the span should be adjusted to during layout.
+    /// Negative values are ignored.
     pub text_length: Option<f64>,
     /// A length adjust property.
+    ///
+    /// Used only when `text_length` is set.
     pub length_adjust: LengthAdjust,
 }
 
 /// A text chunk anchor property.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextAnchor {
     Start,
     Middle,
@@ -237,6 +287,7 @@ impl Default for TextAnchor {
 
 /// A path used by text-on-path.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextPath {
     /// A text offset in SVG coordinates.
     ///
@@ -244,17 +295,22 @@ pub struct TextPath {
     pub start_offset: f64,
 
     /// A path.
+    ///
+    /// Already reversed when `side="right"` was set.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared"))]
     pub path: Rc<PathData>,
 }
 
 /// A text chunk flow property.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextFlow {
     /// A linear layout.
     ///
     /// Includes left-to-right, right-to-left and top-to-bottom.
     Linear,
     /// A text-on-path layout.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::shared"))]
     Path(Rc<TextPath>),
 }
 
@@ -262,6 +318,7 @@ pub enum TextFlow {
 ///
 /// Text alignment and BIDI reordering can only be done inside a text chunk.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextChunk {
     /// An absolute X axis offset.
     pub x: Option<f64>,
@@ -281,6 +338,7 @@ pub struct TextChunk {
 ///
 /// _Character_ is a Unicode codepoint.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CharacterPosition {
     /// An absolute X axis position.
     pub x: Option<f64>,
@@ -295,15 +353,34 @@ pub struct CharacterPosition {
 /// A writing mode.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WritingMode {
     LeftToRight,
     TopToBottom,
 }
 
+/// A base text direction.
+///
+/// `direction` attribute in the SVG.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+impl Default for TextDirection {
+    fn default() -> Self {
+        Self::LeftToRight
+    }
+}
+
 /// A text element.
 ///
 /// `text` element in SVG.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Text {
     /// Element's ID.
     ///
@@ -333,6 +410,60 @@ pub struct Text {
     /// A writing mode.
     pub writing_mode: WritingMode,
 
+    /// A base text direction.
+    ///
+    /// Used as a paragraph embedding level during the bidi reordering.
+    /// Also swaps `start` and `end` text anchors.
+    pub direction: TextDirection,
+
+    /// Disables the implicit bidi reordering.
+    ///
+    /// When set, all characters are laid out in the `direction` order.
+    ///
+    /// `unicode-bidi="bidi-override"` or `unicode-bidi="isolate-override"` in SVG.
+    pub bidi_override: bool,
+
     /// A list of text chunks.
     pub chunks: Vec<TextChunk>,
 }
+
+impl Text {
+    /// Converts the text into a group of paths.
+    ///
+    /// `paths` are the outlined glyphs and decorations produced by a text layout
+    /// implementation, like `usvg-text-layout`. They must be in the text coordinates
+    /// and should have `text_bbox` already set.
+    ///
+    /// The returned group inherits text's ID and transform,
+    /// while paths inherit text's rendering mode.
+    ///
+    /// Returns `None` when `paths` is empty.
+    pub fn to_paths(&self, paths: Vec<Path>) -> Option<Node> {
+        if paths.is_empty() {
+            return None;
+        }
+
+        let group = Node::new(NodeKind::Group(Group {
+            id: self.id.clone(),
+            transform: self.transform,
+            ..Group::default()
+        }));
+
+        let rendering_mode = self.shape_rendering();
+        for mut path in paths {
+            path.rendering_mode = rendering_mode;
+            group.append_kind(NodeKind::Path(path));
+        }
+
+        Some(group)
+    }
+
+    /// Returns a shape rendering mode that should be used by the text outlines.
+    pub fn shape_rendering(&self) -> ShapeRendering {
+        match self.rendering_mode {
+            TextRendering::OptimizeSpeed => ShapeRendering::CrispEdges,
+            TextRendering::OptimizeLegibility => ShapeRendering::GeometricPrecision,
+            TextRendering::GeometricPrecision => ShapeRendering::GeometricPrecision,
+        }
+    }
+}
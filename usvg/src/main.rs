@@ -43,7 +43,8 @@ OPTIONS:
                                     geometricPrecision]
   --image-rendering HINT            Selects the default image rendering method
                                     [default: optimizeQuality]
-                                    [possible values: optimizeQuality, optimizeSpeed]
+                                    [possible values: optimizeQuality, optimizeSpeed,
+                                    pixelated, crisp-edges]
   --resources-dir DIR               Sets a directory that will be used during
                                     relative paths resolving.
                                     Expected to be the same as the directory that
@@ -102,6 +103,8 @@ OPTIONS:
   --transforms-precision NUM        Set the transform values numeric precision.
                                     Smaller precision can lead to a malformed output in some cases
                                     [values: 2..8 (inclusive)] [default: 8]
+  --simplify-transforms             Write simple transforms using translate, scale
+                                    and rotate instead of matrix
   --quiet                           Disables warnings
 
 ARGS:
@@ -137,6 +140,7 @@ struct Args {
     attrs_indent: xmlwriter::Indent,
     coordinates_precision: Option<u8>,
     transforms_precision: Option<u8>,
+    simplify_transforms: bool,
 
     quiet: bool,
 
@@ -204,6 +208,7 @@ fn collect_args() -> Result<Args, pico_args::Error> {
             .unwrap_or(xmlwriter::Indent::None),
         coordinates_precision: input.opt_value_from_fn("--coordinates-precision", parse_precision)?,
         transforms_precision: input.opt_value_from_fn("--transforms-precision", parse_precision)?,
+        simplify_transforms: input.contains("--simplify-transforms"),
 
         quiet: input.contains("--quiet"),
 
@@ -413,7 +418,7 @@ fn process(args: Args) -> Result<(), String> {
         image_rendering: args.image_rendering,
         default_size: usvg_tree::Size::new(args.default_width as f64, args.default_height as f64)
             .unwrap(),
-        image_href_resolver: usvg_parser::ImageHrefResolver::default(),
+        ..usvg_parser::Options::default()
     };
 
     let input_svg = match in_svg {
@@ -428,6 +433,7 @@ fn process(args: Args) -> Result<(), String> {
         id_prefix: args.id_prefix,
         coordinates_precision: args.coordinates_precision.unwrap_or(8),
         transforms_precision: args.transforms_precision.unwrap_or(8),
+        simplify_transforms: args.simplify_transforms,
         writer_opts: xmlwriter::Options {
             use_single_quote: false,
             indent: args.indent,
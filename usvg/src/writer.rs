@@ -44,6 +44,14 @@ pub struct XmlOptions {
     /// Default: 8
     pub transforms_precision: u8,
 
+    /// Write simple transforms using `translate`, `scale` and `rotate`
+    /// instead of `matrix`.
+    ///
+    /// Transforms that cannot be represented this way are still written as `matrix`.
+    ///
+    /// Default: false
+    pub simplify_transforms: bool,
+
     /// `xmlwriter` options.
     pub writer_opts: xmlwriter::Options,
 }
@@ -54,6 +62,7 @@ impl Default for XmlOptions {
             id_prefix: Default::default(),
             coordinates_precision: 8,
             transforms_precision: 8,
+            simplify_transforms: false,
             writer_opts: Default::default(),
         }
     }
@@ -180,6 +189,9 @@ fn conv_filters(tree: &Tree, opt: &XmlOptions, xml: &mut XmlWriter) {
             filter.primitive_units,
             Units::UserSpaceOnUse,
         );
+        if filter.color_interpolation == filter::ColorInterpolation::SRGB {
+            xml.write_svg_attribute(AId::ColorInterpolationFilters, "sRGB");
+        }
 
         for fe in &filter.primitives {
             match fe.kind {
@@ -223,27 +235,7 @@ fn conv_filters(tree: &Tree, opt: &XmlOptions, xml: &mut XmlWriter) {
                     xml.write_filter_primitive_attrs(fe);
                     xml.write_filter_input(AId::In, &blend.input1);
                     xml.write_filter_input(AId::In2, &blend.input2);
-                    xml.write_svg_attribute(
-                        AId::Mode,
-                        match blend.mode {
-                            BlendMode::Normal => "normal",
-                            BlendMode::Multiply => "multiply",
-                            BlendMode::Screen => "screen",
-                            BlendMode::Overlay => "overlay",
-                            BlendMode::Darken => "darken",
-                            BlendMode::Lighten => "lighten",
-                            BlendMode::ColorDodge => "color-dodge",
-                            BlendMode::ColorBurn => "color-burn",
-                            BlendMode::HardLight => "hard-light",
-                            BlendMode::SoftLight => "soft-light",
-                            BlendMode::Difference => "difference",
-                            BlendMode::Exclusion => "exclusion",
-                            BlendMode::Hue => "hue",
-                            BlendMode::Saturation => "saturation",
-                            BlendMode::Color => "color",
-                            BlendMode::Luminosity => "luminosity",
-                        },
-                    );
+                    xml.write_svg_attribute(AId::Mode, blend.mode.to_svg_name());
                     xml.write_svg_attribute(AId::Result, &fe.result);
                     xml.end_element();
                 }
@@ -536,6 +528,9 @@ fn conv_defs(tree: &Tree, opt: &XmlOptions, xml: &mut XmlWriter) {
                 write_base_grad(&rg.base, xml, opt);
                 xml.end_element();
             }
+            Paint::ConicGradient(_) => {
+                log::warn!("Conic gradients cannot be represented in SVG. Skipped.");
+            }
             Paint::Pattern(pattern) => {
                 xml.start_svg_element(EId::Pattern);
                 xml.write_id_attribute(&pattern.id, opt);
@@ -552,6 +547,8 @@ fn conv_defs(tree: &Tree, opt: &XmlOptions, xml: &mut XmlWriter) {
                     xml.write_viewbox(vbox);
                 }
 
+                xml.write_overflow(pattern.overflow);
+
                 conv_elements(&pattern.root, false, opt, xml);
 
                 xml.end_element();
@@ -591,6 +588,12 @@ fn conv_defs(tree: &Tree, opt: &XmlOptions, xml: &mut XmlWriter) {
         );
         xml.write_rect_attrs(mask.rect);
 
+        if mask.kind == MaskType::Alpha {
+            xml.write_svg_attribute(AId::MaskType, "alpha");
+        }
+
+        xml.write_overflow(mask.overflow);
+
         if let Some(ref mask) = mask.mask {
             xml.write_func_iri(AId::Mask, &mask.id, opt);
         }
@@ -624,6 +627,7 @@ fn conv_element(node: &Node, is_clip_path: bool, opt: &XmlOptions, xml: &mut Xml
             }
 
             xml.write_visibility(img.visibility);
+            xml.write_overflow(img.overflow);
 
             match img.rendering_mode {
                 ImageRendering::OptimizeQuality => {}
@@ -686,6 +690,10 @@ fn conv_element(node: &Node, is_clip_path: bool, opt: &XmlOptions, xml: &mut Xml
                 xml.write_svg_attribute(AId::Opacity, &g.opacity.get());
             }
 
+            if g.hidden {
+                xml.write_svg_attribute(AId::Display, "none");
+            }
+
             xml.write_transform(AId::Transform, g.transform, opt);
 
             if let Some(eb) = g.enable_background {
@@ -693,24 +701,7 @@ fn conv_element(node: &Node, is_clip_path: bool, opt: &XmlOptions, xml: &mut Xml
             }
 
             if g.blend_mode != BlendMode::Normal || g.isolate {
-                let blend_mode = match g.blend_mode {
-                    BlendMode::Normal => "normal",
-                    BlendMode::Multiply => "multiply",
-                    BlendMode::Screen => "screen",
-                    BlendMode::Overlay => "overlay",
-                    BlendMode::Darken => "darken",
-                    BlendMode::Lighten => "lighten",
-                    BlendMode::ColorDodge => "color-dodge",
-                    BlendMode::ColorBurn => "color-burn",
-                    BlendMode::HardLight => "hard-light",
-                    BlendMode::SoftLight => "soft-light",
-                    BlendMode::Difference => "difference",
-                    BlendMode::Exclusion => "exclusion",
-                    BlendMode::Hue => "hue",
-                    BlendMode::Saturation => "saturation",
-                    BlendMode::Color => "color",
-                    BlendMode::Luminosity => "luminosity",
-                };
+                let blend_mode = g.blend_mode.to_svg_name();
 
                 // For reasons unknown, `mix-blend-mode` and `isolation` must be written
                 // as `style` attribute.
@@ -742,6 +733,7 @@ trait XmlWriterExt {
     fn write_transform(&mut self, id: AId, units: Transform, opt: &XmlOptions);
     fn write_enable_background(&mut self, eb: EnableBackground);
     fn write_visibility(&mut self, value: Visibility);
+    fn write_overflow(&mut self, value: Overflow);
     fn write_func_iri(&mut self, aid: AId, id: &str, opt: &XmlOptions);
     fn write_rect_attrs(&mut self, r: Rect);
     fn write_numbers(&mut self, aid: AId, list: &[f64]);
@@ -848,6 +840,10 @@ impl XmlWriterExt for XmlWriter {
 
     fn write_transform(&mut self, id: AId, ts: Transform, opt: &XmlOptions) {
         if !ts.is_default() {
+            if opt.simplify_transforms && write_simple_transform(self, id, ts, opt) {
+                return;
+            }
+
             self.write_attribute_raw(id.to_str(), |buf| {
                 buf.extend_from_slice(b"matrix(");
                 write_num(ts.a, buf, opt.transforms_precision);
@@ -889,6 +885,13 @@ impl XmlWriterExt for XmlWriter {
         }
     }
 
+    fn write_overflow(&mut self, value: Overflow) {
+        match value {
+            Overflow::Visible => self.write_attribute(AId::Overflow.to_str(), "visible"),
+            Overflow::Hidden => {}
+        }
+    }
+
     fn write_func_iri(&mut self, aid: AId, id: &str, opt: &XmlOptions) {
         let prefix = opt.id_prefix.as_deref().unwrap_or_default();
         self.write_attribute_fmt(aid.to_str(), format_args!("url(#{}{})", prefix, id));
@@ -996,6 +999,7 @@ impl XmlWriterExt for XmlWriter {
             usvg_tree::ImageKind::JPEG(ref data) => ("jpeg", data.as_slice()),
             usvg_tree::ImageKind::PNG(ref data) => ("png", data.as_slice()),
             usvg_tree::ImageKind::GIF(ref data) => ("gif", data.as_slice()),
+            usvg_tree::ImageKind::WEBP(ref data) => ("webp", data.as_slice()),
             usvg_tree::ImageKind::SVG(ref tree) => {
                 svg_string = tree.to_string(&XmlOptions::default());
                 ("svg+xml", svg_string.as_bytes())
@@ -1049,6 +1053,10 @@ fn write_base_grad(g: &BaseGradient, xml: &mut XmlWriter, opt: &XmlOptions) {
         SpreadMethod::Repeat => xml.write_svg_attribute(AId::SpreadMethod, "repeat"),
     }
 
+    if g.color_interpolation == ColorInterpolation::LinearRGB {
+        xml.write_svg_attribute(AId::ColorInterpolation, "linearRGB");
+    }
+
     for s in &g.stops {
         xml.start_svg_element(EId::Stop);
         xml.write_svg_attribute(AId::Offset, &s.offset.get());
@@ -1078,7 +1086,21 @@ fn write_path(
 
     xml.write_visibility(path.visibility);
 
-    if path.paint_order == PaintOrder::StrokeAndFill {
+    // `paint_order` is the source of truth and `raw_paint_order` could be outdated.
+    let raw_paint_order = path
+        .raw_paint_order
+        .filter(|order| raw_paint_order_to_usvg(order) == path.paint_order);
+    if let Some(ref order) = raw_paint_order {
+        let names: Vec<_> = order
+            .iter()
+            .map(|kind| match kind {
+                PaintOrderKind::Fill => "fill",
+                PaintOrderKind::Stroke => "stroke",
+                PaintOrderKind::Markers => "markers",
+            })
+            .collect();
+        xml.write_svg_attribute(AId::PaintOrder, &names.join(" "));
+    } else if path.paint_order == PaintOrder::StrokeAndFill {
         xml.write_svg_attribute(AId::PaintOrder, "stroke");
     }
 
@@ -1090,6 +1112,10 @@ fn write_path(
         ShapeRendering::GeometricPrecision => {}
     }
 
+    if path.vector_effect == VectorEffect::NonScalingStroke {
+        xml.write_svg_attribute(AId::VectorEffect, "non-scaling-stroke");
+    }
+
     if let Some(id) = clip_path {
         xml.write_func_iri(AId::ClipPath, id, opt);
     }
@@ -1219,6 +1245,7 @@ fn write_paint(aid: AId, paint: &Paint, opt: &XmlOptions, xml: &mut XmlWriter) {
         Paint::Color(c) => xml.write_color(aid, *c),
         Paint::LinearGradient(ref lg) => xml.write_func_iri(aid, &lg.id, opt),
         Paint::RadialGradient(ref rg) => xml.write_func_iri(aid, &rg.id, opt),
+        Paint::ConicGradient(_) => xml.write_svg_attribute(aid, "none"),
         Paint::Pattern(ref patt) => xml.write_func_iri(aid, &patt.id, opt),
     }
 }
@@ -1270,6 +1297,76 @@ static POW_VEC: &'static [f64] = &[
     1_000_000_000_000.0,
 ];
 
+/// Writes a transform as a list of `translate`, `scale` and `rotate` functions.
+///
+/// Returns `false` when a transform has a skew or a combination of rotation and scale.
+fn write_simple_transform(xml: &mut XmlWriter, id: AId, ts: Transform, opt: &XmlOptions) -> bool {
+    let p = opt.transforms_precision;
+    let has_translate = !(ts.e.is_fuzzy_zero() && ts.f.is_fuzzy_zero());
+
+    if ts.b.is_fuzzy_zero() && ts.c.is_fuzzy_zero() {
+        let has_scale = !(ts.a.fuzzy_eq(&1.0) && ts.d.fuzzy_eq(&1.0));
+        xml.write_attribute_raw(id.to_str(), |buf| {
+            if has_translate {
+                buf.extend_from_slice(b"translate(");
+                write_num(ts.e, buf, p);
+                buf.push(b' ');
+                write_num(ts.f, buf, p);
+                buf.push(b')');
+            }
+
+            if has_scale {
+                if has_translate {
+                    buf.push(b' ');
+                }
+
+                buf.extend_from_slice(b"scale(");
+                write_num(ts.a, buf, p);
+                buf.push(b' ');
+                write_num(ts.d, buf, p);
+                buf.push(b')');
+            }
+        });
+
+        return true;
+    }
+
+    // A pure rotation, optionally followed by a translation.
+    let is_rotation =
+        ts.a.fuzzy_eq(&ts.d) && ts.b.fuzzy_eq(&-ts.c) && (ts.a * ts.a + ts.b * ts.b).fuzzy_eq(&1.0);
+    if is_rotation {
+        xml.write_attribute_raw(id.to_str(), |buf| {
+            if has_translate {
+                buf.extend_from_slice(b"translate(");
+                write_num(ts.e, buf, p);
+                buf.push(b' ');
+                write_num(ts.f, buf, p);
+                buf.extend_from_slice(b") ");
+            }
+
+            buf.extend_from_slice(b"rotate(");
+            write_num(ts.b.atan2(ts.a).to_degrees(), buf, p);
+            buf.push(b')');
+        });
+
+        return true;
+    }
+
+    false
+}
+
+fn raw_paint_order_to_usvg(order: &[PaintOrderKind; 3]) -> PaintOrder {
+    let stroke = order
+        .iter()
+        .position(|kind| *kind == PaintOrderKind::Stroke);
+    let fill = order.iter().position(|kind| *kind == PaintOrderKind::Fill);
+    if stroke < fill {
+        PaintOrder::StrokeAndFill
+    } else {
+        PaintOrder::FillAndStroke
+    }
+}
+
 fn write_num(num: f64, buf: &mut Vec<u8>, precision: u8) {
     // If number is an integer, it's faster to write it as i32.
     if num.fract().is_fuzzy_zero() {
@@ -1288,3 +1385,94 @@ fn write_num(num: f64, buf: &mut Vec<u8>, precision: u8) {
 
     write!(buf, "{}", v).unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use usvg_parser::TreeParsing;
+
+    fn first_path(tree: &Tree) -> Node {
+        tree.root
+            .descendants()
+            .find(|node| matches!(*node.borrow(), NodeKind::Path(_)))
+            .unwrap()
+    }
+
+    #[test]
+    fn raw_paint_order() {
+        let svg = "
+        <svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 100 100'>
+            <path d='M 10 10 L 90 90' paint-order='markers stroke'/>
+        </svg>
+        ";
+
+        let tree = Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+        let xml = tree.to_string(&XmlOptions::default());
+        assert!(xml.contains("paint-order=\"markers stroke fill\""));
+
+        // An outdated `raw_paint_order` must not override `paint_order`.
+        if let NodeKind::Path(ref mut path) = *first_path(&tree).borrow_mut() {
+            path.paint_order = PaintOrder::FillAndStroke;
+        }
+        let xml = tree.to_string(&XmlOptions::default());
+        assert!(!xml.contains("paint-order"));
+
+        if let NodeKind::Path(ref mut path) = *first_path(&tree).borrow_mut() {
+            path.raw_paint_order = None;
+            path.paint_order = PaintOrder::StrokeAndFill;
+        }
+        let xml = tree.to_string(&XmlOptions::default());
+        assert!(xml.contains("paint-order=\"stroke\""));
+    }
+
+    #[test]
+    fn simplify_transforms() {
+        fn check(transform: &str, expected: &str) {
+            let svg = format!(
+                "<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 100 100'>
+                    <path d='M 10 10 L 90 90' stroke='black' transform='{}'/>
+                </svg>",
+                transform
+            );
+
+            let tree = Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+            let opt = XmlOptions {
+                simplify_transforms: true,
+                ..XmlOptions::default()
+            };
+            let xml = tree.to_string(&opt);
+            assert!(
+                xml.contains(&format!("transform=\"{}\"", expected)),
+                "{}",
+                xml
+            );
+
+            // The written transform must be parsed back into the same matrix.
+            let tree2 = Tree::from_str(&xml, &usvg_parser::Options::default()).unwrap();
+            let ts1 = first_path(&tree).abs_transform();
+            let ts2 = first_path(&tree2).abs_transform();
+            // Numbers are written with `transforms_precision`.
+            let eq = [
+                (ts1.a, ts2.a),
+                (ts1.b, ts2.b),
+                (ts1.c, ts2.c),
+                (ts1.d, ts2.d),
+                (ts1.e, ts2.e),
+                (ts1.f, ts2.f),
+            ]
+            .iter()
+            .all(|(a, b)| (a - b).abs() < 1e-6);
+            assert!(eq, "{:?} != {:?}", ts1, ts2);
+        }
+
+        check("translate(10 20)", "translate(10 20)");
+        check("scale(2 3)", "scale(2 3)");
+        check("translate(10 20) scale(2)", "translate(10 20) scale(2 2)");
+        check("rotate(30)", "rotate(30)");
+        check("matrix(1 0.5 0 1 10 20)", "matrix(1 0.5 0 1 10 20)");
+        check(
+            "translate(10 20) rotate(30) scale(2 3)",
+            "matrix(1.73205081 1 -1.5 2.59807621 10 20)",
+        );
+    }
+}